@@ -3,6 +3,8 @@
 # Arquivos lidos:
 # - grammars/tokens.json
 # - grammars/syntax.txt 
+# - grammars/syntax-lalr.txt
+# - grammars/syntax-compat.txt
 # Arquivos gerados:
 # - src/grammar/token_type.rs
 # - src/grammar/non_terminals.rs
@@ -19,6 +21,9 @@ VALUED_TOKENS = [
   "const_string",
   "func_id",
   "id",
+  "string_end",
+  "string_middle",
+  "string_start",
   "var_type"
 ]
 
@@ -43,8 +48,11 @@ OPERATORS = [
 
 SCRIPT_NAME = "/".join(__file__.split("/")[-2:])
 
-# Load syntax.txt
-with open("grammars/syntax.txt") as f: syntax = [line.strip() for line in f.readlines()]
+# Load syntax.txt e a gramática do analisador LALR(1)
+# Os não terminais das duas gramáticas compartilham o mesmo enum NonTerminal
+syntax = []
+for grammar_file in ["grammars/syntax.txt", "grammars/syntax-lalr.txt", "grammars/syntax-compat.txt"]:
+  with open(grammar_file) as f: syntax += [line.strip() for line in f.readlines() if line.strip()]
 # Load tokens.json
 with open("grammars/tokens.json") as f: tokens = json.load(f)
 
@@ -76,12 +84,20 @@ if diff: print("Unused tokens:", ", ".join(sorted(diff)), '\n')
 def clean_token(token: str) -> str:
   return token.replace("_", " ").title().replace(" ", "")
 
+# Tokens definidos por uma única string são exibidos como a própria string, os demais pelo nome do token
+# As chaves precisam ser escapadas por serem usadas dentro de write!
+def display_token(token: str) -> str:
+  for name, data in tokens:
+    if name == token and type(data.get("string")) == str:
+      return data["string"].replace("{", "{{").replace("}", "}}")
+  return token
+
 terminals.add("eof")  # Adiciona o token EOF para indicar o fim do arquivo
 with open("scripts/token_type_template.txt") as f: token_type_template = f.read()
 with open("src/grammar/token_type.rs", "w") as f:
   token_list = "  ".join([f"{clean_token(token)},\n" for token in sorted(terminals)])[:-1]
   token_string_list = "      ".join([f"\"{token}\" => Ok(TokenType::{clean_token(token)}),\n" for token in sorted(terminals)])[:-1]
-  token_type_display = "      ".join([f"TokenType::{clean_token(token)} => write!(f, \"{token}\"),\n" for token in sorted(terminals)])[:-1]
+  token_type_display = "      ".join([f"TokenType::{clean_token(token)} => write!(f, \"{display_token(token)}\"),\n" for token in sorted(terminals)])[:-1]
   valued_string = " | ".join([f"TokenType::{clean_token(token)}" for token in VALUED_TOKENS])
   id_tokens = " | ".join([f"TokenType::{clean_token(token)}" for token in ID_TOKENS])
   operators = "      ".join([f"TokenType::{clean_token(token)} => Operator::{clean_token(token)[2:]},\n" for token in OPERATORS])
@@ -5,7 +5,7 @@ from fda import FDA
 # Todos os caracteres alfabéticos minúsculos, inclui acentos 
 # VALID_LETTERS = { chr(i) for i in range(256) if chr(i).isalpha() and chr(i).islower() }
 # Apenas a-z, exclui acentos
-VALID_LETTERS = { chr(i) for i in range(97, 97+27) }  # a-z in ASCII
+VALID_LETTERS = { chr(i) for i in range(97, 97+26) }  # a-z in ASCII
 EMPTY_CHARS = { chr(i) for i in range(256) if chr(i).isspace() }
 
 with open("grammars/tokens.json", "r") as f: token_types = json.load(f)
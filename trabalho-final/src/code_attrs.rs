@@ -1,8 +1,38 @@
+use std::collections::{HashMap, HashSet};
+use crate::grammar::const_type::{ConstType, VarType};
+use crate::scope_stack::TypeTable;
+
+// Destinos de `break` e `continue` de um laço. Um switch é destino apenas de `break`
+struct LoopLabels {
+  name: Option<String>,
+  break_label: String,
+  continue_label: Option<String>,
+}
+
 #[allow(dead_code)]
 pub struct CodeAttrs {
   register_counter: u32,
   label_counter: u32,
-  break_label: String,
+  variable_counter: u32,
+  // Laços que envolvem o código sendo gerado, do mais externo ao mais interno
+  loops: Vec<LoopLabels>,
+  // Valores das constantes nomeadas visíveis no bloco sendo gerado
+  pub constants: HashMap<String, ConstType>,
+  // Nomes no código gerado das variáveis declaradas na inicialização de um for visíveis no bloco sendo gerado.
+  // Os enumeradores são substituídos pelos seus valores
+  pub variables: HashMap<String, String>,
+  // Structs definidas pelo programa, usadas para calcular os deslocamentos dos campos
+  pub types: TypeTable,
+  // Struct e dimensões de cada variável do tipo struct visível no bloco sendo gerado
+  pub structs: HashMap<String, (String, Vec<u32>)>,
+  // Para cada função já gerada, quais parâmetros são vetores, passados por referência
+  pub array_params: HashMap<String, Vec<bool>>,
+  // Parâmetros vetores da função sendo gerada visíveis no bloco, que já guardam o endereço do vetor recebido
+  pub references: HashSet<String>,
+  // Variáveis e constantes globais já declaradas, que possuem armazenamento estático
+  pub globals: HashSet<String>,
+  // Texto impresso entre os valores de um print com várias expressões
+  pub print_separator: String,
   pub code: String,
 }
 
@@ -11,7 +41,16 @@ impl CodeAttrs {
     CodeAttrs {
       register_counter: 0,
       label_counter: 0,
-      break_label: String::new(),
+      variable_counter: 0,
+      loops: vec![],
+      constants: HashMap::new(),
+      variables: HashMap::new(),
+      types: TypeTable::default(),
+      structs: HashMap::new(),
+      array_params: HashMap::new(),
+      references: HashSet::new(),
+      globals: HashSet::new(),
+      print_separator: String::from(" "),
       code: String::new(),
     }
   }
@@ -28,11 +67,63 @@ impl CodeAttrs {
     label
   }
 
-  pub fn get_scope_label(&self) -> &String {
-    &self.break_label
+  // Converte o valor do temporário para o tipo registrado no nó pela análise semântica, retornando o temporário
+  pub fn convert(&mut self, conversion: &Option<VarType>, temp: String) -> String {
+    match conversion {
+      Some(VarType::Float) => self.code.push_str(&format!("{} = itof {}\n", temp, temp)),
+      Some(VarType::Int) => self.code.push_str(&format!("{} = ftoi {}\n", temp, temp)),
+      Some(VarType::String) => self.code.push_str(&format!("{} = to_string {}\n", temp, temp)),
+      _ => {},
+    }
+    temp
+  }
+
+  // Nome único para uma variável, que não coincide com nenhum identificador do programa
+  pub fn create_variable(&mut self, name: &str) -> String {
+    self.variable_counter += 1;
+    format!("{}.{}", name, self.variable_counter)
+  }
+
+  // Nome no código gerado de uma variável local, constante local ou parâmetro declarado no bloco sendo gerado,
+  // que esconde os nomes externos iguais até o fim do bloco. Quando o nome é o de uma variável global,
+  // a declaração recebe um nome único, para não compartilhar o armazenamento da global
+  pub fn declare_local(&mut self, name: &str) -> String {
+    self.references.remove(name);
+    if !self.globals.contains(name) {
+      self.variables.remove(name);
+      return name.to_string();
+    }
+    let variable = self.create_variable(name);
+    self.variables.insert(name.to_string(), variable.clone());
+    variable
   }
 
-  pub fn set_scope_end(&mut self, label: String) {
-    self.break_label = label;
+  pub fn push_loop(&mut self, name: Option<String>, break_label: String, continue_label: String) {
+    self.loops.push(LoopLabels { name, break_label, continue_label: Some(continue_label) });
   }
-}
\ No newline at end of file
+
+  pub fn push_switch(&mut self, break_label: String) {
+    self.loops.push(LoopLabels { name: None, break_label, continue_label: None });
+  }
+
+  // Remove o laço ou switch mais interno
+  pub fn pop_loop(&mut self) {
+    self.loops.pop();
+  }
+
+  // Laço (ou switch, para o break) mais interno ou, se houver rótulo, o laço com esse rótulo (já validado pela análise semântica)
+  fn target_loop(&self, name: Option<&str>, is_continue: bool) -> &LoopLabels {
+    self.loops.iter().rev()
+      .filter(|target| !is_continue || target.continue_label.is_some())
+      .find(|target| name.is_none() || target.name.as_deref() == name)
+      .expect("break/continue outside of a loop")
+  }
+
+  pub fn get_break_label(&self, name: Option<&str>) -> &String {
+    &self.target_loop(name, false).break_label
+  }
+
+  pub fn get_continue_label(&self, name: Option<&str>) -> &String {
+    self.target_loop(name, true).continue_label.as_ref().unwrap()
+  }
+}
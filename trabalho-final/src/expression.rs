@@ -26,6 +26,12 @@ pub enum ExpressionTreeNode {
     operator: Operator,
     operand: Box<ExpressionTreeNode>
   },
+  // cond ? a : b, onde apenas um dos ramos é avaliado
+  TernaryOperator {
+    condition: Box<ExpressionTreeNode>,
+    then_branch: Box<ExpressionTreeNode>,
+    else_branch: Box<ExpressionTreeNode>
+  },
   Operand {
     value: Operand
   }
@@ -51,6 +57,15 @@ impl ExpressionTreeNode {
         *counter += 1;
         operand.save(output, counter);
       },
+      ExpressionTreeNode::TernaryOperator { condition, then_branch, else_branch } => {
+        let name = *counter;
+        output.push_str(&format!("  {} [label=\"?:\"];\n", name));
+        for branch in [condition, then_branch, else_branch] {
+          output.push_str(&format!("  {} -> {};\n", name, *counter+1));
+          *counter += 1;
+          branch.save(output, counter);
+        }
+      },
       ExpressionTreeNode::Operand { value } => {
         output.push_str(&format!("  {} [label=\"{}\"];\n", *counter, value));
         *counter += 1;
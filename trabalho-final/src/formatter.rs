@@ -0,0 +1,254 @@
+use std::error::Error;
+use crate::lexer::{Comment, Lexer};
+use crate::syntax::SyntaxTree;
+use crate::token::Token;
+use crate::grammar::token_type::TokenType;
+use crate::grammar::const_type::ConstType;
+
+/// Indentação usada em cada nível de bloco
+const INDENT: &str = "  ";
+
+/// Escreve um programa ConvCC no estilo canônico.
+/// Recebe os tokens na ordem em que aparecem na árvore sintática (ver `SyntaxTree::format`)
+/// e decide as quebras de linha, a indentação e os espaços entre eles.
+/// Os comentários do código fonte são intercalados com os tokens de acordo com a sua posição.
+pub struct Formatter<'a> {
+  source_lines: Vec<&'a str>,
+  comments: &'a [Comment],
+  next_comment: usize,
+  output: String,
+  indent: usize,
+  /// Linha do código fonte do último token ou comentário escrito
+  last_line: usize,
+  /// Último token escrito na linha atual
+  previous: Option<TokenType>,
+  /// O próximo token deve começar uma nova linha
+  line_break: bool,
+  /// O próximo token é escrito sem espaço antes dele (ex: operador unário)
+  glue: bool,
+  /// Nenhuma linha em branco é mantida logo após a abertura de um bloco
+  after_open: bool,
+  /// Profundidade de construções escritas em uma única linha, como o cabeçalho do for.
+  /// Dentro delas, as chaves não abrem um bloco (valores iniciais de um vetor)
+  pub inline: usize,
+}
+
+impl<'a> Formatter<'a> {
+  pub fn new(source: &'a str, comments: &'a [Comment]) -> Self {
+    Formatter {
+      source_lines: source.lines().collect(),
+      comments,
+      next_comment: 0,
+      output: String::new(),
+      indent: 0,
+      last_line: 0,
+      previous: None,
+      line_break: false,
+      glue: false,
+      after_open: true,
+      inline: 0,
+    }
+  }
+
+  /// Escreve um token, precedido pelos comentários que aparecem antes dele no código fonte.
+  pub fn token(&mut self, token: &Token) {
+    self.comments_before(token.line, token.column);
+    let token_type = token.token_type;
+    if token_type == TokenType::Rbrace && self.inline == 0 {
+      self.indent = self.indent.saturating_sub(1);
+      self.line_break = true;
+    }
+    // "} else" fica na mesma linha
+    if self.line_break && !(self.previous == Some(TokenType::Rbrace) && token_type == TokenType::KwElse) {
+      self.new_line(token.line, token_type != TokenType::Rbrace);
+    }
+    if self.at_line_start() {
+      self.output.push_str(&INDENT.repeat(self.indent));
+    } else if self.needs_space(token_type) {
+      self.output.push(' ');
+    }
+    let text = self.token_text(token);
+    self.output.push_str(&text);
+
+    self.previous = Some(token_type);
+    self.last_line = token.line;
+    self.line_break = false;
+    self.glue = false;
+    self.after_open = false;
+    match token_type {
+      TokenType::Lbrace if self.inline == 0 => {
+        self.indent += 1;
+        self.line_break = true;
+        self.after_open = true;
+      },
+      TokenType::Rbrace if self.inline == 0 => self.line_break = true,
+      TokenType::Semicolon if self.inline == 0 => self.line_break = true,
+      _ => {},
+    }
+  }
+
+  /// O próximo token é escrito colado ao anterior.
+  pub fn glue(&mut self) {
+    self.glue = true;
+  }
+
+  /// O próximo token continua na linha atual, mesmo após o fechamento de um bloco.
+  pub fn same_line(&mut self) {
+    self.line_break = false;
+  }
+
+  /// Inicia um bloco sem chaves, como os comandos de um caso do switch.
+  pub fn open_block(&mut self) {
+    self.indent += 1;
+    self.line_break = true;
+    self.after_open = true;
+  }
+
+  /// Termina um bloco iniciado por `open_block`.
+  pub fn close_block(&mut self) {
+    self.indent = self.indent.saturating_sub(1);
+    self.line_break = true;
+  }
+
+  /// Escreve os comentários restantes e retorna o código formatado.
+  pub fn finish(mut self) -> String {
+    self.comments_before(usize::MAX, usize::MAX);
+    if !self.at_line_start() {
+      self.output.push('\n');
+    }
+    self.output
+  }
+
+  /// Texto canônico do token.
+  /// Identificadores mantêm a grafia do código fonte, já que o lexer os converte para minúsculo.
+  /// Floats sempre possuem o ponto decimal, para que não sejam lidos como inteiros.
+  fn token_text(&self, token: &Token) -> String {
+    match (&token.token_type, &token.value) {
+      (TokenType::Id | TokenType::FuncId, _) => {
+        let line = self.source_lines.get(token.line - 1).unwrap_or(&"");
+        line.chars().skip(token.column - 1).take(token.length).collect()
+      },
+      (TokenType::ConstFloat, Some(ConstType::Float(value))) => {
+        let text = value.to_string();
+        if text.contains('.') { text } else { format!("{}.0", text) }
+      },
+      _ => token.to_string(),
+    }
+  }
+
+  fn needs_space(&self, token_type: TokenType) -> bool {
+    if self.glue { return false; }
+    !matches!((self.previous, token_type),
+      (Some(TokenType::Lparenthesis | TokenType::Lbracket | TokenType::Dot | TokenType::StringStart | TokenType::StringMiddle), _)
+      | (_, TokenType::Rparenthesis | TokenType::Rbracket | TokenType::Comma | TokenType::Semicolon | TokenType::Lbracket | TokenType::OpIncrement | TokenType::OpDecrement | TokenType::Dot | TokenType::StringMiddle | TokenType::StringEnd)
+      | (Some(TokenType::FuncId | TokenType::KwFunc), TokenType::Lparenthesis))
+  }
+
+  fn at_line_start(&self) -> bool {
+    self.output.is_empty() || self.output.ends_with('\n')
+  }
+
+  /// Termina a linha atual. Uma linha em branco do código fonte antes de `line` é mantida
+  /// (várias linhas em branco seguidas viram uma só), exceto no início de um bloco.
+  fn new_line(&mut self, line: usize, keep_blank: bool) {
+    if !self.at_line_start() {
+      self.output.push('\n');
+    }
+    if keep_blank && !self.after_open && line > self.last_line + 1 {
+      self.output.push('\n');
+    }
+  }
+
+  /// Escreve os comentários que aparecem antes da posição (line, column) no código fonte.
+  fn comments_before(&mut self, line: usize, column: usize) {
+    while let Some(comment) = self.comments.get(self.next_comment) {
+      if (comment.line, comment.column) > (line, column) { break; }
+      self.next_comment += 1;
+      // Comentário na mesma linha do último token continua nessa linha
+      if !self.at_line_start() && comment.line == self.last_line {
+        self.output.push(' ');
+      } else {
+        self.new_line(comment.line, true);
+        self.output.push_str(&INDENT.repeat(self.indent));
+      }
+      self.output.push_str(&comment.text);
+      // O comentário vai até o fim da linha, então o próximo token sempre começa uma nova linha
+      self.previous = None;
+      self.last_line = comment.line;
+      self.line_break = true;
+      self.after_open = false;
+    }
+  }
+}
+
+/// Lê o código fonte até a árvore sintática LL(1).
+fn parse(input: &str) -> Result<(SyntaxTree, Vec<Comment>), Box<dyn Error>> {
+  let mut lexer = Lexer::new();
+  lexer.parse(input)?;
+  let mut syntax_tree = SyntaxTree::new()?;
+  syntax_tree.parse(&lexer.token_list)?;
+  Ok((syntax_tree, lexer.comments))
+}
+
+/// Formata o código fonte.
+/// O resultado é lido novamente e sua árvore semântica, ignorando as posições, é comparada com a do código original,
+/// garantindo que a formatação não alterou o significado do programa.
+pub fn format_source(input: &str) -> Result<String, Box<dyn Error>> {
+  let (mut syntax_tree, comments) = parse(input)?;
+  let mut formatter = Formatter::new(input, &comments);
+  syntax_tree.format(&mut formatter);
+  let formatted = formatter.finish();
+
+  let (mut formatted_tree, formatted_comments) = parse(&formatted)?;
+  let mut original = syntax_tree.semantic_tree()?.root;
+  let mut result = formatted_tree.semantic_tree()?.root;
+  original.clear_positions();
+  result.clear_positions();
+  let same_comments = comments.iter().map(|c| &c.text).eq(formatted_comments.iter().map(|c| &c.text));
+  if original != result || !same_comments {
+    return Err("Erro do formatador: o código formatado não é equivalente ao código original".into());
+  }
+  Ok(formatted)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const SOURCE: &str = "// soma dois valores
+def int @Soma(int a,int b){
+int r;   // resultado
+r=a+b;
+
+
+  return r;}
+def @main(){ int v[3]={1,2,3};
+  for(int i=0;i<3;i++){ if(v[i]>1){print \"v[{i}] = {v[i]}, {{ok}}\";}else{ print -v[i] ; } }
+  // fim
+}
+";
+
+  /// Formata o código em uma thread com a pilha do programa principal, já que a análise
+  /// descendente recursiva não cabe na pilha padrão das threads de teste.
+  fn format(source: &str) -> String {
+    let source = source.to_string();
+    std::thread::Builder::new().stack_size(8 << 20)
+      .spawn(move || format_source(&source).map_err(|error| error.to_string()))
+      .unwrap().join().unwrap().unwrap()
+  }
+
+  #[test]
+  fn format_source_is_idempotent() {
+    let formatted = format(SOURCE);
+    assert_ne!(formatted, SOURCE);
+    assert_eq!(format(&formatted), formatted);
+  }
+
+  #[test]
+  fn format_source_keeps_comments() {
+    let formatted = format(SOURCE);
+    let comments: Vec<&str> = formatted.lines().filter_map(|line| line.find("//").map(|start| &line[start..])).collect();
+    assert_eq!(comments, vec!["// soma dois valores", "// resultado", "// fim"]);
+    assert!(formatted.contains("int r; // resultado"), "{}", formatted);
+  }
+}
@@ -16,6 +16,15 @@ impl ConstType {
     ConstType::String(s.to_string())
   }
 
+  /// Valor de uma string com as chaves escapadas (`{{` e `}}`) substituídas por chaves literais.
+  /// Os escapes são mantidos nos tokens, para que o formatador reescreva a string como no código fonte.
+  pub fn unescape_braces(self) -> ConstType {
+    match self {
+      ConstType::String(s) => ConstType::String(unescape_braces(&s)),
+      value => value,
+    }
+  }
+
   pub fn get_type(&self) -> VarType {
     match self {
       ConstType::Int(_) => VarType::Int,
@@ -42,6 +51,11 @@ impl ConstType {
   }
 }
 
+/// Texto de uma string com cada `{{` e `}}` substituído por uma única chave.
+pub fn unescape_braces(text: &str) -> String {
+  text.replace("{{", "{").replace("}}", "}")
+}
+
 impl std::fmt::Display for ConstType {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     match self {
@@ -57,7 +71,15 @@ impl std::fmt::Display for ConstType {
 pub enum VarType {
   Int,
   Float,
-  String
+  String,
+  // Apenas como tipo de retorno de funções
+  Void,
+  // Struct definida pelo programa, identificada pelo nome
+  Struct(String),
+  // Enum definido pelo programa, identificado pelo nome
+  Enum(String),
+  // Referência a uma função, com os tipos dos parâmetros e o tipo de retorno
+  Func(Vec<VarType>, Box<VarType>),
 }
 
 impl std::fmt::Display for VarType {
@@ -66,6 +88,12 @@ impl std::fmt::Display for VarType {
       VarType::Int => write!(f, "int"),
       VarType::Float => write!(f, "float"),
       VarType::String => write!(f, "string"),
+      VarType::Void => write!(f, "void"),
+      VarType::Struct(name) | VarType::Enum(name) => write!(f, "{}", name),
+      VarType::Func(params, return_type) => {
+        let params: Vec<String> = params.iter().map(|param| param.to_string()).collect();
+        write!(f, "func({}) -> {}", params.join(", "), return_type)
+      },
     }
   }
 }
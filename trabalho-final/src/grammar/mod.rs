@@ -1,4 +1,5 @@
 pub mod non_terminals;
 pub mod token_type;
 pub mod const_type;
-pub mod semantic_node;
\ No newline at end of file
+pub mod semantic_node;
+pub mod transform;
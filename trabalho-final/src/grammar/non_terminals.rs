@@ -4,23 +4,51 @@ use std::error::Error;
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum NonTerminal {
   Allocexpression,
+  Alloctype,
   Atribstat,
   Atribstatevalue,
+  Atribstat1,
+  Caselist,
+  Casestat,
+  Casestat1,
+  Castexpression,
   Constant,
+  Constdecl,
   ConstIndex,
+  Dowhilestat,
   Elsestat,
   Elsestat1,
+  Enumdef,
+  Enumlist,
+  Enumlist1,
   Expression,
   Expression1,
+  Expression2,
   Factor,
+  Factor1,
+  Factor2,
+  Fielddecl,
+  Fieldlist,
+  Fieldlist1,
+  FieldAccess,
+  Forinit,
   Forstat,
   Funccall,
   Funcdef,
   Funclist,
+  Functype,
   Ifstat,
+  Initlist,
+  Initlist1,
+  Interpolation,
+  Interpolation1,
+  Jumpstat,
+  Jumpstat1,
+  Loopstat,
   Lvalue,
   Numexpression,
   Numexpression1,
+  OpAtribstat,
   OpExpression,
   OpNumexpression,
   OpTerm,
@@ -28,41 +56,83 @@ pub enum NonTerminal {
   Paramlistcall,
   Paramlistcall1,
   Paramlist1,
+  ParamIndex,
   Printstat,
+  Printstat1,
   Program,
   Readstat,
   Returnstat,
+  Returnstat1,
+  Signature,
+  Signature1,
   Statelist,
   Statelist1,
   Statement,
+  Statement1,
+  Statement2,
+  Structdef,
+  Switchstat,
   Term,
   Term1,
+  Type,
+  Typelist,
+  Typelist1,
   Unaryexpression,
   Vardecl,
+  Varinit,
   VarIndex,
+  Whilestat,
 }
 
 impl NonTerminal {
   pub fn from_str(s: &str) -> Result<Self, Box<dyn Error>> {
     match s {
       "ALLOCEXPRESSION" => Ok(NonTerminal::Allocexpression),
+      "ALLOCTYPE" => Ok(NonTerminal::Alloctype),
       "ATRIBSTAT" => Ok(NonTerminal::Atribstat),
       "ATRIBSTATEVALUE" => Ok(NonTerminal::Atribstatevalue),
+      "ATRIBSTAT_1" => Ok(NonTerminal::Atribstat1),
+      "CASELIST" => Ok(NonTerminal::Caselist),
+      "CASESTAT" => Ok(NonTerminal::Casestat),
+      "CASESTAT_1" => Ok(NonTerminal::Casestat1),
+      "CASTEXPRESSION" => Ok(NonTerminal::Castexpression),
       "CONSTANT" => Ok(NonTerminal::Constant),
+      "CONSTDECL" => Ok(NonTerminal::Constdecl),
       "CONST_INDEX" => Ok(NonTerminal::ConstIndex),
+      "DOWHILESTAT" => Ok(NonTerminal::Dowhilestat),
       "ELSESTAT" => Ok(NonTerminal::Elsestat),
       "ELSESTAT_1" => Ok(NonTerminal::Elsestat1),
+      "ENUMDEF" => Ok(NonTerminal::Enumdef),
+      "ENUMLIST" => Ok(NonTerminal::Enumlist),
+      "ENUMLIST_1" => Ok(NonTerminal::Enumlist1),
       "EXPRESSION" => Ok(NonTerminal::Expression),
       "EXPRESSION_1" => Ok(NonTerminal::Expression1),
+      "EXPRESSION_2" => Ok(NonTerminal::Expression2),
       "FACTOR" => Ok(NonTerminal::Factor),
+      "FACTOR_1" => Ok(NonTerminal::Factor1),
+      "FACTOR_2" => Ok(NonTerminal::Factor2),
+      "FIELDDECL" => Ok(NonTerminal::Fielddecl),
+      "FIELDLIST" => Ok(NonTerminal::Fieldlist),
+      "FIELDLIST_1" => Ok(NonTerminal::Fieldlist1),
+      "FIELD_ACCESS" => Ok(NonTerminal::FieldAccess),
+      "FORINIT" => Ok(NonTerminal::Forinit),
       "FORSTAT" => Ok(NonTerminal::Forstat),
       "FUNCCALL" => Ok(NonTerminal::Funccall),
       "FUNCDEF" => Ok(NonTerminal::Funcdef),
       "FUNCLIST" => Ok(NonTerminal::Funclist),
+      "FUNCTYPE" => Ok(NonTerminal::Functype),
       "IFSTAT" => Ok(NonTerminal::Ifstat),
+      "INITLIST" => Ok(NonTerminal::Initlist),
+      "INITLIST_1" => Ok(NonTerminal::Initlist1),
+      "INTERPOLATION" => Ok(NonTerminal::Interpolation),
+      "INTERPOLATION_1" => Ok(NonTerminal::Interpolation1),
+      "JUMPSTAT" => Ok(NonTerminal::Jumpstat),
+      "JUMPSTAT_1" => Ok(NonTerminal::Jumpstat1),
+      "LOOPSTAT" => Ok(NonTerminal::Loopstat),
       "LVALUE" => Ok(NonTerminal::Lvalue),
       "NUMEXPRESSION" => Ok(NonTerminal::Numexpression),
       "NUMEXPRESSION_1" => Ok(NonTerminal::Numexpression1),
+      "OP_ATRIBSTAT" => Ok(NonTerminal::OpAtribstat),
       "OP_EXPRESSION" => Ok(NonTerminal::OpExpression),
       "OP_NUMEXPRESSION" => Ok(NonTerminal::OpNumexpression),
       "OP_TERM" => Ok(NonTerminal::OpTerm),
@@ -70,18 +140,32 @@ impl NonTerminal {
       "PARAMLISTCALL" => Ok(NonTerminal::Paramlistcall),
       "PARAMLISTCALL_1" => Ok(NonTerminal::Paramlistcall1),
       "PARAMLIST_1" => Ok(NonTerminal::Paramlist1),
+      "PARAM_INDEX" => Ok(NonTerminal::ParamIndex),
       "PRINTSTAT" => Ok(NonTerminal::Printstat),
+      "PRINTSTAT_1" => Ok(NonTerminal::Printstat1),
       "PROGRAM" => Ok(NonTerminal::Program),
       "READSTAT" => Ok(NonTerminal::Readstat),
       "RETURNSTAT" => Ok(NonTerminal::Returnstat),
+      "RETURNSTAT_1" => Ok(NonTerminal::Returnstat1),
+      "SIGNATURE" => Ok(NonTerminal::Signature),
+      "SIGNATURE_1" => Ok(NonTerminal::Signature1),
       "STATELIST" => Ok(NonTerminal::Statelist),
       "STATELIST_1" => Ok(NonTerminal::Statelist1),
       "STATEMENT" => Ok(NonTerminal::Statement),
+      "STATEMENT_1" => Ok(NonTerminal::Statement1),
+      "STATEMENT_2" => Ok(NonTerminal::Statement2),
+      "STRUCTDEF" => Ok(NonTerminal::Structdef),
+      "SWITCHSTAT" => Ok(NonTerminal::Switchstat),
       "TERM" => Ok(NonTerminal::Term),
       "TERM_1" => Ok(NonTerminal::Term1),
+      "TYPE" => Ok(NonTerminal::Type),
+      "TYPELIST" => Ok(NonTerminal::Typelist),
+      "TYPELIST_1" => Ok(NonTerminal::Typelist1),
       "UNARYEXPRESSION" => Ok(NonTerminal::Unaryexpression),
       "VARDECL" => Ok(NonTerminal::Vardecl),
+      "VARINIT" => Ok(NonTerminal::Varinit),
       "VAR_INDEX" => Ok(NonTerminal::VarIndex),
+      "WHILESTAT" => Ok(NonTerminal::Whilestat),
       _ => Err("Invalid non-terminal".into()),
     }
   }
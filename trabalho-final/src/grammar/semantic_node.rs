@@ -5,36 +5,84 @@ use crate::semantic::SemanticNode;
 #[derive(Debug, Clone, PartialEq)]
 pub enum SemanticNodeData {
   // ALLOCEXPRESSION -> vartype VARINDEX
+  // ALLOCEXPRESSION -> id VARINDEX
+  //   O id é o nome de uma struct
   Allocexpression {
     var_type: Box<SemanticNode>,
     dimensions: Box<SemanticNode>,
   },
   // ATRIBSTAT -> LVALUE value
+  // ATRIBSTAT -> LVALUE op value
+  //   Atribuição composta (x += e) e incremento (x++, equivalente a x += 1), com o operador aritmético correspondente
   Atribstat {
     lvalue: Box<SemanticNode>,
+    op: Option<Box<SemanticNode>>,
     value: Box<SemanticNode>,
   },
   // ATRIBSTATEVALUE -> EXPRESSION 
   // ATRIBSTATEVALUE -> ALLOCEXPRESSION
-  // ATRIBSTATEVALUE -> FUNCCALL
   Atribstatevalue {
     expression: Option<Box<SemanticNode>>,
     allocexpression: Option<Box<SemanticNode>>,
-    funccall: Option<Box<SemanticNode>>,
+  },
+  // CASELIST -> [CASESTAT1, CASESTAT2, CASESTAT3...]
+  Caselist {
+    cases: Vec<SemanticNode>,
+  },
+  // CASESTAT -> NUMEXPRESSION [STATELIST]
+  // CASESTAT -> [STATELIST]
+  //   Sem valor para o caso default. O valor é uma expressão constante, calculada durante a análise semântica
+  Casestat {
+    value: Option<Box<SemanticNode>>,
+    statelist: Option<Box<SemanticNode>>,
+  },
+  // CASTEXPRESSION -> var_type FACTOR
+  //   Conversão explícita para var_type, como (int) c para um enumerador
+  Castexpression {
+    var_type: Box<SemanticNode>,
+    factor: Box<SemanticNode>,
+  },
+  // EXPRESSION -> EXPRESSION question EXPRESSION colon EXPRESSION
+  //   A condição é uma EXPRESSION sem operador condicional; os ramos podem ser outras expressões condicionais
+  Condexpression {
+    condition: Box<SemanticNode>,
+    then_expression: Box<SemanticNode>,
+    else_expression: Box<SemanticNode>,
   },
   Constant {
     value: ConstType,
-    line: usize,
-    column: usize,
   },
-  // CONSTINDEX -> [CONSTANT1, CONSTANT2, CONSTANT3...]
+  // CONSTINDEX -> [NUMEXPRESSION1, NUMEXPRESSION2, NUMEXPRESSION3...]
+  //   Cada tamanho é uma expressão constante, calculada durante a análise semântica
   ConstIndex {
     index: Vec<SemanticNode>,
   },
+  // CONSTDECL -> var_type id EXPRESSION
+  Constdecl {
+    var_type: Box<SemanticNode>,
+    id: Box<SemanticNode>,
+    value: Box<SemanticNode>,
+  },
+  // DOWHILESTAT -> [label] STATELIST EXPRESSION
+  Dowhilestat {
+    label: Option<Box<SemanticNode>>,
+    body: Box<SemanticNode>,
+    condition: Box<SemanticNode>,
+  },
   // ELSESTAT -> STATEMENT
   Elsestat {
     statement: Box<SemanticNode>,
   },
+  // ENUMDEF -> id ENUMLIST
+  Enumdef {
+    id: Box<SemanticNode>,
+    enumlist: Box<SemanticNode>,
+  },
+  // ENUMLIST -> [id1, id2, id3...]
+  //   Os enumeradores valem 0, 1, 2..., na ordem em que foram declarados
+  Enumlist {
+    enumerators: Vec<SemanticNode>,
+  },
   // EXPRESSION -> NUMEXPRESSION op_expression numexpression2 
   // EXPRESSION -> NUMEXPRESSION 
   Expression {
@@ -45,13 +93,27 @@ pub enum SemanticNodeData {
   // FACTOR -> EXPRESSION
   // FACTOR -> LVALUE
   // FACTOR -> constant
+  // FACTOR -> FUNCCALL
   Factor {
     expression: Option<Box<SemanticNode>>,
     lvalue: Option<Box<SemanticNode>>,
     constant: Option<Box<SemanticNode>>,
+    funccall: Option<Box<SemanticNode>>,
   },
-  // FORSTAT -> ATRIBSTAT EXPRESSION ATRIBSTAT STATELIST
+  // FIELDLIST -> [VARDECL1, VARDECL2, VARDECL3...]
+  Fieldlist {
+    fields: Vec<SemanticNode>,
+  },
+  // FORINIT -> var_type id EXPRESSION
+  //   Variável declarada na inicialização do for, visível apenas no laço. FORINIT -> ATRIBSTAT guarda o próprio ATRIBSTAT
+  Forinit {
+    var_type: Box<SemanticNode>,
+    id: Box<SemanticNode>,
+    value: Box<SemanticNode>,
+  },
+  // FORSTAT -> [label] FORINIT EXPRESSION ATRIBSTAT STATELIST
   Forstat {
+    label: Option<Box<SemanticNode>>,
     init: Box<SemanticNode>,
     condition: Box<SemanticNode>,
     increment: Box<SemanticNode>,
@@ -59,13 +121,20 @@ pub enum SemanticNodeData {
   },
   // FUNCCALL -> id
   // FUNCCALL -> id PARAMLISTCALL
+  //   Chamada indireta (f(a), ops[i](a)): o id é um LVALUE cujo valor é do tipo de uma função
   Funccall {
     id: Box<SemanticNode>,
     paramlistcall: Option<Box<SemanticNode>>,
   },
-  // FUNCDEF -> func_id PARAMLIST STATELIST
-  // FUNCDEF -> func_id STATELIST
+  // FUNCREF -> func_id
+  //   Referência a uma função usada como valor, do tipo func(tipos dos parâmetros) -> tipo de retorno
+  Funcref {
+    id: Box<SemanticNode>,
+  },
+  // FUNCDEF -> FUNCTYPE func_id PARAMLIST STATELIST
+  // FUNCDEF -> FUNCTYPE func_id STATELIST
   Funcdef {
+    return_type: Option<Box<SemanticNode>>,
     func_id: Box<SemanticNode>,
     paramlist: Option<Box<SemanticNode>>,
     statelist: Box<SemanticNode>,
@@ -81,11 +150,31 @@ pub enum SemanticNodeData {
     then_branch: Box<SemanticNode>,
     else_branch: Option<Box<SemanticNode>>,
   },
+  // INITLIST -> [EXPRESSION1, EXPRESSION2, EXPRESSION3...]
+  //   Valores iniciais de um vetor, em ordem de linha (o último índice varia mais rápido)
+  Initlist {
+    values: Vec<SemanticNode>,
+  },
+  // INTERPOLATION -> [string_start, EXPRESSION1, string_middle, EXPRESSION2, ..., string_end]
+  //   String com expressões embutidas: "Resultado = {resultado}". Os trechos de texto são terminais,
+  //   com as chaves e aspas do código fonte, intercalados com as expressões
+  Interpolation {
+    segments: Vec<SemanticNode>,
+  },
+  // JUMPSTAT -> kw_break [label]
+  // JUMPSTAT -> kw_continue [label]
+  Jumpstat {
+    token: Token,
+    label: Option<Box<SemanticNode>>,
+  },
   // LVALUE -> id
   // LVALUE -> id VARINDEX
+  // LVALUE -> id [VARINDEX] LVALUE
+  //   Acesso a um campo (p.x): o LVALUE interno começa pelo nome do campo e pode acessar outros campos (p.pos.x)
   Lvalue {
     id: Box<SemanticNode>,
     var_index: Option<Box<SemanticNode>>,
+    field: Option<Box<SemanticNode>>,
   },
   // NUMEXPRESSION -> term
   // NUMEXPRESSION -> term op_numexpression term2
@@ -103,17 +192,24 @@ pub enum SemanticNodeData {
   OpTerm {
     op: TokenType
   },
-  // PARAMLIST -> [vartype1, id1, vartype2, id2, vartype3, id3...]
+  // PARAMLIST -> [vartype1, id1, vartype2, id2, PARAMINDEX2, vartype3, id3...]
+  //   O PARAMINDEX aparece logo após o id dos parâmetros que são vetores
+  //   O tipo de um parâmetro pode ser uma SIGNATURE no lugar do vartype
   Paramlist {
     paramlist: Vec<SemanticNode>,
   },
+  // PARAMINDEX -> [CONSTINDEX]
+  //   Parâmetro vetor (int v[], float m[][3]): a primeira dimensão não tem tamanho e as demais são constantes
+  ParamIndex {
+    const_index: Option<Box<SemanticNode>>,
+  },
   // PARAMLISTCALL -> [id1, id2, id3...]
   Paramlistcall {
     paramlist: Vec<SemanticNode>,
   },
-  // PRINTSTAT -> EXPRESSION
+  // PRINTSTAT -> [EXPRESSION1, EXPRESSION2, ...]
   Printstat {
-    expression: Box<SemanticNode>,
+    expressions: Vec<SemanticNode>,
   },
   // PROGRAM -> FUNCLIST
   // PROGRAM -> STATEMENT
@@ -125,8 +221,17 @@ pub enum SemanticNodeData {
   Readstat {
     lvalue: Box<SemanticNode>,
   },
+  // RETURNSTAT -> kw_return EXPRESSION
+  // RETURNSTAT -> kw_return
   Returnstat {
     token: Token,
+    expression: Option<Box<SemanticNode>>,
+  },
+  // SIGNATURE -> [TYPE1, TYPE2, TYPE3...] return_type
+  //   Tipo de uma função: func(int, float) -> int. Cada TYPE é um var_type ou outra SIGNATURE, e return_type é um var_type ou kw_void
+  Signature {
+    params: Vec<SemanticNode>,
+    return_type: Box<SemanticNode>,
   },
   // STATELIST -> [STATEMENT1, STATEMENT2, STATEMENT3...]
   Statelist {
@@ -136,18 +241,33 @@ pub enum SemanticNodeData {
   // STATEMENT -> ATRIBSTAT
   // STATEMENT -> IFSTAT
   // STATEMENT -> FORSTAT
+  // STATEMENT -> WHILESTAT
+  // STATEMENT -> DOWHILESTAT
   // STATEMENT -> STATELIST
   // STATEMENT -> PRINTSTAT
   // STATEMENT -> READSTAT
   // STATEMENT -> RETURNSTAT
+  // STATEMENT -> SWITCHSTAT
   Statement {
     vardecl: Option<Box<SemanticNode>>,
     atribstat: Option<Box<SemanticNode>>,
     ifstat: Option<Box<SemanticNode>>,
     forstat: Option<Box<SemanticNode>>,
+    whilestat: Option<Box<SemanticNode>>,
+    dowhilestat: Option<Box<SemanticNode>>,
     statelist: Option<Box<SemanticNode>>,
     commandstat: Option<Box<SemanticNode>>,
   },
+  // STRUCTDEF -> id FIELDLIST
+  Structdef {
+    id: Box<SemanticNode>,
+    fieldlist: Box<SemanticNode>,
+  },
+  // SWITCHSTAT -> EXPRESSION CASELIST
+  Switchstat {
+    expression: Box<SemanticNode>,
+    caselist: Box<SemanticNode>,
+  },
   // TERM -> UNARYEXPRESSION
   // TERM -> UNARYEXPRESSION op_term UNARYEXPRESSION
   Term {
@@ -163,16 +283,194 @@ pub enum SemanticNodeData {
   },
   //VARDECL -> var_type id
   //VARDECL -> var_type id CONSTINDEX
+  //VARDECL -> var_type id [CONSTINDEX] INITLIST
+  //  Vetor com valores iniciais: int v[3] = {1, 2, 3}
+  //VARDECL -> id id [CONSTINDEX]
+  //  Variável do tipo struct, cujo nome é o primeiro id
+  //VARDECL -> SIGNATURE id [CONSTINDEX] [INITLIST]
+  //  Variável do tipo de uma função
   Vardecl {
     var_type: Box<SemanticNode>,
     id: Box<SemanticNode>,
     const_index: Option<Box<SemanticNode>>,
+    init: Option<Box<SemanticNode>>,
   },
   // VARINDEX -> [NUMEXPRESSION1, NUMEXPRESSION2, NUMEXPRESSION3]
   VarIndex {
     index: Vec<SemanticNode>,
   },
+  // WHILESTAT -> [label] EXPRESSION STATELIST
+  Whilestat {
+    label: Option<Box<SemanticNode>>,
+    condition: Box<SemanticNode>,
+    body: Box<SemanticNode>,
+  },
   Terminal {
     value: Token
   }
 }
+
+impl SemanticNodeData {
+  /// Nós filhos, na ordem em que aparecem no código fonte.
+  pub fn children_mut(&mut self) -> Vec<&mut SemanticNode> {
+    let mut children: Vec<&mut SemanticNode> = vec![];
+    match self {
+      SemanticNodeData::Allocexpression { var_type, dimensions } => {
+        children.push(var_type);
+        children.push(dimensions);
+      },
+      SemanticNodeData::Atribstat { lvalue, op, value } => {
+        children.push(lvalue);
+        children.extend(op.as_deref_mut());
+        children.push(value);
+      },
+      SemanticNodeData::Atribstatevalue { expression, allocexpression } => {
+        children.extend(expression.as_deref_mut());
+        children.extend(allocexpression.as_deref_mut());
+      },
+      SemanticNodeData::Caselist { cases } => children.extend(cases.iter_mut()),
+      SemanticNodeData::Casestat { value, statelist } => {
+        children.extend(value.as_deref_mut());
+        children.extend(statelist.as_deref_mut());
+      },
+      SemanticNodeData::Castexpression { var_type, factor } => {
+        children.push(var_type);
+        children.push(factor);
+      },
+      SemanticNodeData::Condexpression { condition, then_expression, else_expression } => {
+        children.push(condition);
+        children.push(then_expression);
+        children.push(else_expression);
+      },
+      SemanticNodeData::ConstIndex { index } | SemanticNodeData::VarIndex { index } => children.extend(index.iter_mut()),
+      SemanticNodeData::Constdecl { var_type, id, value } => {
+        children.push(var_type);
+        children.push(id);
+        children.push(value);
+      },
+      SemanticNodeData::Dowhilestat { label, body, condition } => {
+        children.extend(label.as_deref_mut());
+        children.push(body);
+        children.push(condition);
+      },
+      SemanticNodeData::Elsestat { statement } => children.push(statement),
+      SemanticNodeData::Enumdef { id, enumlist } => {
+        children.push(id);
+        children.push(enumlist);
+      },
+      SemanticNodeData::Enumlist { enumerators } => children.extend(enumerators.iter_mut()),
+      SemanticNodeData::Expression { numexpression, op_expression, numexpression2 } => {
+        children.push(numexpression);
+        children.extend(op_expression.as_deref_mut());
+        children.extend(numexpression2.as_deref_mut());
+      },
+      SemanticNodeData::Factor { expression, lvalue, constant, funccall } => {
+        children.extend(expression.as_deref_mut());
+        children.extend(lvalue.as_deref_mut());
+        children.extend(constant.as_deref_mut());
+        children.extend(funccall.as_deref_mut());
+      },
+      SemanticNodeData::Fieldlist { fields } => children.extend(fields.iter_mut()),
+      SemanticNodeData::Forinit { var_type, id, value } => {
+        children.push(var_type);
+        children.push(id);
+        children.push(value);
+      },
+      SemanticNodeData::Forstat { label, init, condition, increment, body } => {
+        children.extend(label.as_deref_mut());
+        children.push(init);
+        children.push(condition);
+        children.push(increment);
+        children.push(body);
+      },
+      SemanticNodeData::Funccall { id, paramlistcall } => {
+        children.push(id);
+        children.extend(paramlistcall.as_deref_mut());
+      },
+      SemanticNodeData::Funcref { id } => children.push(id),
+      SemanticNodeData::Funcdef { return_type, func_id, paramlist, statelist } => {
+        children.extend(return_type.as_deref_mut());
+        children.push(func_id);
+        children.extend(paramlist.as_deref_mut());
+        children.push(statelist);
+      },
+      SemanticNodeData::Funclist { funclist } => children.extend(funclist.iter_mut()),
+      SemanticNodeData::Ifstat { condition, then_branch, else_branch } => {
+        children.push(condition);
+        children.push(then_branch);
+        children.extend(else_branch.as_deref_mut());
+      },
+      SemanticNodeData::Initlist { values } => children.extend(values.iter_mut()),
+      SemanticNodeData::Interpolation { segments } => children.extend(segments.iter_mut()),
+      SemanticNodeData::Jumpstat { label, .. } => children.extend(label.as_deref_mut()),
+      SemanticNodeData::Lvalue { id, var_index, field } => {
+        children.push(id);
+        children.extend(var_index.as_deref_mut());
+        children.extend(field.as_deref_mut());
+      },
+      SemanticNodeData::Numexpression { term, op_numexpression, term2 } => {
+        children.push(term);
+        children.extend(op_numexpression.as_deref_mut());
+        children.extend(term2.as_deref_mut());
+      },
+      SemanticNodeData::Paramlist { paramlist } | SemanticNodeData::Paramlistcall { paramlist } => children.extend(paramlist.iter_mut()),
+      SemanticNodeData::ParamIndex { const_index } => children.extend(const_index.as_deref_mut()),
+      SemanticNodeData::Printstat { expressions } => children.extend(expressions.iter_mut()),
+      SemanticNodeData::Program { funclist, statement } => {
+        children.extend(funclist.as_deref_mut());
+        children.extend(statement.as_deref_mut());
+      },
+      SemanticNodeData::Readstat { lvalue } => children.push(lvalue),
+      SemanticNodeData::Returnstat { expression, .. } => children.extend(expression.as_deref_mut()),
+      SemanticNodeData::Signature { params, return_type } => {
+        children.extend(params.iter_mut());
+        children.push(return_type);
+      },
+      SemanticNodeData::Statelist { statelist } => children.extend(statelist.iter_mut()),
+      SemanticNodeData::Statement { vardecl, atribstat, ifstat, forstat, whilestat, dowhilestat, statelist, commandstat } => {
+        children.extend(vardecl.as_deref_mut());
+        children.extend(atribstat.as_deref_mut());
+        children.extend(ifstat.as_deref_mut());
+        children.extend(forstat.as_deref_mut());
+        children.extend(whilestat.as_deref_mut());
+        children.extend(dowhilestat.as_deref_mut());
+        children.extend(statelist.as_deref_mut());
+        children.extend(commandstat.as_deref_mut());
+      },
+      SemanticNodeData::Structdef { id, fieldlist } => {
+        children.push(id);
+        children.push(fieldlist);
+      },
+      SemanticNodeData::Switchstat { expression, caselist } => {
+        children.push(expression);
+        children.push(caselist);
+      },
+      SemanticNodeData::Term { unaryexpression, op_term, unaryexpression2 } => {
+        children.push(unaryexpression);
+        children.extend(op_term.as_deref_mut());
+        children.extend(unaryexpression2.as_deref_mut());
+      },
+      SemanticNodeData::Unaryexpression { op, factor } => {
+        children.extend(op.as_deref_mut());
+        children.push(factor);
+      },
+      SemanticNodeData::Vardecl { var_type, id, const_index, init } => {
+        children.push(var_type);
+        children.push(id);
+        children.extend(const_index.as_deref_mut());
+        children.extend(init.as_deref_mut());
+      },
+      SemanticNodeData::Whilestat { label, condition, body } => {
+        children.extend(label.as_deref_mut());
+        children.push(condition);
+        children.push(body);
+      },
+      SemanticNodeData::Constant { .. }
+        | SemanticNodeData::OpExpression { .. }
+        | SemanticNodeData::OpNumexpression { .. }
+        | SemanticNodeData::OpTerm { .. }
+        | SemanticNodeData::Terminal { .. } => {},
+    }
+    children
+  }
+}
@@ -4,84 +4,134 @@ use crate::expression::Operator;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TokenType {
+  Arrow,
+  Colon,
   Comma,
   ConstFloat,
   ConstInt,
   ConstNull,
   ConstString,
+  Dot,
   Eof,
   FuncId,
   Id,
   KwBreak,
+  KwCase,
+  KwConst,
+  KwContinue,
   KwDef,
+  KwDefault,
+  KwDo,
   KwElse,
+  KwEnum,
   KwFor,
+  KwFunc,
   KwIf,
   KwNew,
   KwPrint,
   KwRead,
   KwReturn,
+  KwStruct,
+  KwSwitch,
+  KwVoid,
+  KwWhile,
   Lbrace,
   Lbracket,
   Lparenthesis,
   OpAssign,
+  OpDecrement,
   OpDivision,
+  OpDivisionAssign,
   OpEq,
   OpGe,
   OpGt,
+  OpIncrement,
   OpLe,
   OpLt,
   OpMinus,
+  OpMinusAssign,
   OpModular,
+  OpModularAssign,
   OpMultiply,
+  OpMultiplyAssign,
   OpNe,
   OpPlus,
+  OpPlusAssign,
+  Question,
   Rbrace,
   Rbracket,
   Rparenthesis,
   Semicolon,
+  StringEnd,
+  StringMiddle,
+  StringStart,
   VarType,
 }
 
 impl TokenType {
   pub fn from_str(s: &str) -> Result<TokenType, Box<dyn Error>> {
     match s {
+      "arrow" => Ok(TokenType::Arrow),
+      "colon" => Ok(TokenType::Colon),
       "comma" => Ok(TokenType::Comma),
       "const_float" => Ok(TokenType::ConstFloat),
       "const_int" => Ok(TokenType::ConstInt),
       "const_null" => Ok(TokenType::ConstNull),
       "const_string" => Ok(TokenType::ConstString),
+      "dot" => Ok(TokenType::Dot),
       "eof" => Ok(TokenType::Eof),
       "func_id" => Ok(TokenType::FuncId),
       "id" => Ok(TokenType::Id),
       "kw_break" => Ok(TokenType::KwBreak),
+      "kw_case" => Ok(TokenType::KwCase),
+      "kw_const" => Ok(TokenType::KwConst),
+      "kw_continue" => Ok(TokenType::KwContinue),
       "kw_def" => Ok(TokenType::KwDef),
+      "kw_default" => Ok(TokenType::KwDefault),
+      "kw_do" => Ok(TokenType::KwDo),
       "kw_else" => Ok(TokenType::KwElse),
+      "kw_enum" => Ok(TokenType::KwEnum),
       "kw_for" => Ok(TokenType::KwFor),
+      "kw_func" => Ok(TokenType::KwFunc),
       "kw_if" => Ok(TokenType::KwIf),
       "kw_new" => Ok(TokenType::KwNew),
       "kw_print" => Ok(TokenType::KwPrint),
       "kw_read" => Ok(TokenType::KwRead),
       "kw_return" => Ok(TokenType::KwReturn),
+      "kw_struct" => Ok(TokenType::KwStruct),
+      "kw_switch" => Ok(TokenType::KwSwitch),
+      "kw_void" => Ok(TokenType::KwVoid),
+      "kw_while" => Ok(TokenType::KwWhile),
       "lbrace" => Ok(TokenType::Lbrace),
       "lbracket" => Ok(TokenType::Lbracket),
       "lparenthesis" => Ok(TokenType::Lparenthesis),
       "op_assign" => Ok(TokenType::OpAssign),
+      "op_decrement" => Ok(TokenType::OpDecrement),
       "op_division" => Ok(TokenType::OpDivision),
+      "op_division_assign" => Ok(TokenType::OpDivisionAssign),
       "op_eq" => Ok(TokenType::OpEq),
       "op_ge" => Ok(TokenType::OpGe),
       "op_gt" => Ok(TokenType::OpGt),
+      "op_increment" => Ok(TokenType::OpIncrement),
       "op_le" => Ok(TokenType::OpLe),
       "op_lt" => Ok(TokenType::OpLt),
       "op_minus" => Ok(TokenType::OpMinus),
+      "op_minus_assign" => Ok(TokenType::OpMinusAssign),
       "op_modular" => Ok(TokenType::OpModular),
+      "op_modular_assign" => Ok(TokenType::OpModularAssign),
       "op_multiply" => Ok(TokenType::OpMultiply),
+      "op_multiply_assign" => Ok(TokenType::OpMultiplyAssign),
       "op_ne" => Ok(TokenType::OpNe),
       "op_plus" => Ok(TokenType::OpPlus),
+      "op_plus_assign" => Ok(TokenType::OpPlusAssign),
+      "question" => Ok(TokenType::Question),
       "rbrace" => Ok(TokenType::Rbrace),
       "rbracket" => Ok(TokenType::Rbracket),
       "rparenthesis" => Ok(TokenType::Rparenthesis),
       "semicolon" => Ok(TokenType::Semicolon),
+      "string_end" => Ok(TokenType::StringEnd),
+      "string_middle" => Ok(TokenType::StringMiddle),
+      "string_start" => Ok(TokenType::StringStart),
       "var_type" => Ok(TokenType::VarType),
       _ => Err(format!("Invalid TokenType: {}", s).into())
     }
@@ -89,7 +139,7 @@ impl TokenType {
 
   pub fn has_value(&self) -> bool {
     match self {
-      TokenType::ConstFloat | TokenType::ConstInt | TokenType::ConstString | TokenType::FuncId | TokenType::Id | TokenType::VarType => true,
+      TokenType::ConstFloat | TokenType::ConstInt | TokenType::ConstString | TokenType::FuncId | TokenType::Id | TokenType::StringEnd | TokenType::StringMiddle | TokenType::StringStart | TokenType::VarType => true,
       _ => false,
     }
   }
@@ -123,42 +173,67 @@ impl TokenType {
 impl std::fmt::Display for TokenType {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     match self {
+      TokenType::Arrow => write!(f, "->"),
+      TokenType::Colon => write!(f, ":"),
       TokenType::Comma => write!(f, ","),
       TokenType::ConstFloat => write!(f, "const_float"),
       TokenType::ConstInt => write!(f, "const_int"),
       TokenType::ConstNull => write!(f, "null"),
       TokenType::ConstString => write!(f, "const_string"),
+      TokenType::Dot => write!(f, "."),
       TokenType::Eof => write!(f, "eof"),
       TokenType::FuncId => write!(f, "func_id"),
       TokenType::Id => write!(f, "id"),
       TokenType::KwBreak => write!(f, "break"),
+      TokenType::KwCase => write!(f, "case"),
+      TokenType::KwConst => write!(f, "const"),
+      TokenType::KwContinue => write!(f, "continue"),
       TokenType::KwDef => write!(f, "def"),
+      TokenType::KwDefault => write!(f, "default"),
+      TokenType::KwDo => write!(f, "do"),
       TokenType::KwElse => write!(f, "else"),
+      TokenType::KwEnum => write!(f, "enum"),
       TokenType::KwFor => write!(f, "for"),
+      TokenType::KwFunc => write!(f, "func"),
       TokenType::KwIf => write!(f, "if"),
       TokenType::KwNew => write!(f, "new"),
       TokenType::KwPrint => write!(f, "print"),
       TokenType::KwRead => write!(f, "read"),
       TokenType::KwReturn => write!(f, "return"),
+      TokenType::KwStruct => write!(f, "struct"),
+      TokenType::KwSwitch => write!(f, "switch"),
+      TokenType::KwVoid => write!(f, "void"),
+      TokenType::KwWhile => write!(f, "while"),
       TokenType::Lbrace => write!(f, "{{"),
       TokenType::Lbracket => write!(f, "["),
       TokenType::Lparenthesis => write!(f, "("),
       TokenType::OpAssign => write!(f, "="),
+      TokenType::OpDecrement => write!(f, "--"),
       TokenType::OpDivision => write!(f, "/"),
+      TokenType::OpDivisionAssign => write!(f, "/="),
       TokenType::OpEq => write!(f, "=="),
       TokenType::OpGe => write!(f, ">="),
       TokenType::OpGt => write!(f, ">"),
+      TokenType::OpIncrement => write!(f, "++"),
       TokenType::OpLe => write!(f, "<="),
       TokenType::OpLt => write!(f, "<"),
       TokenType::OpMinus => write!(f, "-"),
+      TokenType::OpMinusAssign => write!(f, "-="),
       TokenType::OpModular => write!(f, "%"),
+      TokenType::OpModularAssign => write!(f, "%="),
       TokenType::OpMultiply => write!(f, "*"),
+      TokenType::OpMultiplyAssign => write!(f, "*="),
       TokenType::OpNe => write!(f, "!="),
       TokenType::OpPlus => write!(f, "+"),
+      TokenType::OpPlusAssign => write!(f, "+="),
+      TokenType::Question => write!(f, "?"),
       TokenType::Rbrace => write!(f, "}}"),
       TokenType::Rbracket => write!(f, "]"),
       TokenType::Rparenthesis => write!(f, ")"),
       TokenType::Semicolon => write!(f, ";"),
+      TokenType::StringEnd => write!(f, "string_end"),
+      TokenType::StringMiddle => write!(f, "string_middle"),
+      TokenType::StringStart => write!(f, "string_start"),
       TokenType::VarType => write!(f, "var_type"),
     }
   }
@@ -0,0 +1,373 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::error::Error;
+
+/// Representação da produção vazia nos arquivos de gramática
+const EPSILON: &str = "''";
+/// Token de fim de arquivo, adicionado ao FOLLOW do símbolo inicial
+const EOF: &str = "eof";
+
+/// Gramática livre de contexto no formato de `grammars/syntax.txt`, com os símbolos representados pelo nome.
+/// Não terminais são escritos em maiúsculo e terminais (tokens) em minúsculo.
+/// Diferente do enum `NonTerminal`, essa representação permite criar novos não terminais durante as transformações.
+/// Os nomes criados seguem o padrão `BASE_N` (ex: `NUMEXPRESSION_1`), que `scripts/consistency.py` converte para `Numexpression1`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grammar {
+  /// Não terminais na ordem em que aparecem no arquivo, cada um com as suas produções.
+  /// Um corpo vazio representa a produção vazia.
+  pub rules: Vec<(String, Vec<Vec<String>>)>,
+}
+
+/// Entrada da tabela LL(1): (não terminal, token, índice da produção)
+pub type ParseTableEntry = (String, String, usize);
+
+pub fn is_non_terminal(symbol: &str) -> bool {
+  symbol.chars().next().is_some_and(|c| c.is_ascii_uppercase())
+}
+
+impl Grammar {
+  /// Lê uma gramática no formato `CABEÇA,corpo`, uma produção por linha.
+  /// Linhas fora desse formato são ignoradas. As produções de um mesmo não terminal devem ser consecutivas,
+  /// já que o índice da produção (usado pela tabela LL(1)) é a sua posição no arquivo.
+  pub fn parse(content: &str) -> Result<Grammar, Box<dyn Error>> {
+    let mut rules: Vec<(String, Vec<Vec<String>>)> = vec![];
+    for line in content.lines() {
+      let parts: Vec<&str> = line.split(',').collect();
+      if parts.len() != 2 { continue; }
+      let head = parts[0].trim();
+      if !is_non_terminal(head) {
+        return Err(format!("Gramática inválida: '{}' não é um não terminal", head).into());
+      }
+      let body: Vec<String> = match parts[1].trim() {
+        EPSILON => vec![],
+        body => body.split_whitespace().map(|s| s.to_string()).collect(),
+      };
+      match rules.last_mut() {
+        Some((last, bodies)) if last == head => bodies.push(body),
+        _ => {
+          if rules.iter().any(|(name, _)| name == head) {
+            return Err(format!("Gramática inválida: produções de {} não são consecutivas", head).into());
+          }
+          rules.push((head.to_string(), vec![body]));
+        }
+      }
+    }
+    if rules.is_empty() {
+      return Err("Gramática vazia".into());
+    }
+    for symbol in rules.iter().flat_map(|(_, bodies)| bodies.iter().flatten()) {
+      if is_non_terminal(symbol) && !rules.iter().any(|(head, _)| head == symbol) {
+        return Err(format!("Gramática inválida: não terminal {} não possui produções", symbol).into());
+      }
+    }
+    Ok(Grammar { rules })
+  }
+
+  pub fn start(&self) -> &str {
+    &self.rules[0].0
+  }
+
+  /// Produções na ordem do arquivo. A posição de cada produção é o índice usado na tabela LL(1).
+  pub fn productions(&self) -> Vec<(&str, &[String])> {
+    self.rules.iter()
+      .flat_map(|(head, bodies)| bodies.iter().map(move |body| (head.as_str(), body.as_slice())))
+      .collect()
+  }
+
+  fn bodies(&self, head: &str) -> &Vec<Vec<String>> {
+    &self.rules.iter().find(|(name, _)| name == head).unwrap().1
+  }
+
+  fn bodies_mut(&mut self, head: &str) -> &mut Vec<Vec<String>> {
+    &mut self.rules.iter_mut().find(|(name, _)| name == head).unwrap().1
+  }
+
+  /// Cria um nome livre para um não terminal derivado de `head`: `HEAD_1`, `HEAD_2`...
+  /// Não terminais já derivados usam a mesma base (`HEAD_1` gera `HEAD_2`, e não `HEAD_1_1`).
+  /// A comparação ignora os `_`, já que `HEAD_1` e `HEAD1` geram a mesma variante do enum `NonTerminal`.
+  fn fresh_name(&self, head: &str) -> String {
+    let base = head.trim_end_matches(|c: char| c.is_ascii_digit() || c == '_');
+    let base = if base.is_empty() { head } else { base };
+    let used: HashSet<String> = self.rules.iter().map(|(name, _)| name.replace('_', "")).collect();
+    (1..).map(|n| format!("{}_{}", base, n)).find(|name| !used.contains(&name.replace('_', ""))).unwrap()
+  }
+
+  /// Insere um novo não terminal logo após `after` e os outros não terminais já derivados dele.
+  fn insert_after(&mut self, after: &str, name: String, bodies: Vec<Vec<String>>) {
+    let base = format!("{}_", after.trim_end_matches(|c: char| c.is_ascii_digit() || c == '_'));
+    let mut index = self.rules.iter().position(|(head, _)| head == after).unwrap() + 1;
+    while index < self.rules.len() && self.rules[index].0.starts_with(&base) {
+      index += 1;
+    }
+    self.rules.insert(index, (name, bodies));
+  }
+
+  /// Aplica todas as transformações necessárias para uma gramática LL(1):
+  /// remoção de símbolos inúteis, eliminação de recursão à esquerda e fatoração à esquerda.
+  pub fn transform(&mut self) {
+    self.remove_useless();
+    self.remove_left_recursion();
+    self.left_factor();
+    self.remove_useless();
+  }
+
+  /// Remove não terminais improdutivos (que não derivam nenhuma sentença) e inalcançáveis a partir do símbolo inicial.
+  pub fn remove_useless(&mut self) {
+    // Improdutivos: ponto fixo dos não terminais com alguma produção formada apenas por terminais e produtivos
+    let mut productive: HashSet<String> = HashSet::new();
+    loop {
+      let before = productive.len();
+      for (head, bodies) in &self.rules {
+        if bodies.iter().any(|body| body.iter().all(|s| !is_non_terminal(s) || productive.contains(s))) {
+          productive.insert(head.clone());
+        }
+      }
+      if productive.len() == before { break; }
+    }
+    self.rules.retain(|(head, _)| productive.contains(head));
+    for (_, bodies) in self.rules.iter_mut() {
+      bodies.retain(|body| body.iter().all(|s| !is_non_terminal(s) || productive.contains(s)));
+    }
+
+    // Inalcançáveis: busca a partir do símbolo inicial
+    let Some((start, _)) = self.rules.first() else { return; };
+    let mut reachable: HashSet<String> = HashSet::from([start.clone()]);
+    let mut stack = vec![start.clone()];
+    while let Some(head) = stack.pop() {
+      for symbol in self.bodies(&head).iter().flatten() {
+        if is_non_terminal(symbol) && reachable.insert(symbol.clone()) {
+          stack.push(symbol.clone());
+        }
+      }
+    }
+    self.rules.retain(|(head, _)| reachable.contains(head));
+  }
+
+  /// Verifica se `from` deriva uma forma sentencial que começa com `to` (A =>+ B...).
+  /// Prefixos anuláveis não são considerados.
+  fn left_derives(&self, from: &str, to: &str) -> bool {
+    let mut visited: HashSet<&str> = HashSet::new();
+    let mut stack = vec![from];
+    while let Some(head) = stack.pop() {
+      for body in self.bodies(head) {
+        let Some(first) = body.first() else { continue; };
+        if first == to { return true; }
+        if is_non_terminal(first) && visited.insert(first) {
+          stack.push(first);
+        }
+      }
+    }
+    false
+  }
+
+  /// Elimina a recursão à esquerda direta e indireta.
+  /// Para cada não terminal A_i, as produções A_i -> A_j γ com j < i são expandidas com as produções de A_j,
+  /// mas apenas quando A_j deriva A_i à esquerda, evitando expandir produções que não fazem parte de um ciclo.
+  /// Em seguida, a recursão direta A -> A α | β é substituída por A -> β A_N e A_N -> α A_N | ε.
+  pub fn remove_left_recursion(&mut self) {
+    let order: Vec<String> = self.rules.iter().map(|(head, _)| head.clone()).collect();
+    for i in 0..order.len() {
+      for j in 0..i {
+        if !self.left_derives(&order[j], &order[i]) { continue; }
+        let replacement = self.bodies(&order[j]).clone();
+        let bodies = std::mem::take(self.bodies_mut(&order[i]));
+        let mut expanded = vec![];
+        for body in bodies {
+          if body.first() == Some(&order[j]) {
+            for prefix in &replacement {
+              expanded.push(prefix.iter().chain(&body[1..]).cloned().collect());
+            }
+          } else {
+            expanded.push(body);
+          }
+        }
+        *self.bodies_mut(&order[i]) = expanded;
+      }
+      self.remove_direct_left_recursion(&order[i]);
+    }
+  }
+
+  fn remove_direct_left_recursion(&mut self, head: &str) {
+    let (recursive, others): (Vec<Vec<String>>, Vec<Vec<String>>) = self.bodies(head).iter()
+      .cloned()
+      .partition(|body| body.first().map(|s| s.as_str()) == Some(head));
+    if recursive.is_empty() { return; }
+    let tail = self.fresh_name(head);
+    // A -> A é um ciclo que não gera nenhuma sentença nova
+    let mut tail_bodies: Vec<Vec<String>> = recursive.into_iter()
+      .filter(|body| body.len() > 1)
+      .map(|body| body[1..].iter().cloned().chain([tail.clone()]).collect())
+      .collect();
+    tail_bodies.push(vec![]);
+    *self.bodies_mut(head) = others.into_iter().map(|body| body.into_iter().chain([tail.clone()]).collect()).collect();
+    self.insert_after(head, tail, tail_bodies);
+  }
+
+  /// Fatora à esquerda as produções que começam com o mesmo prefixo:
+  /// A -> α β1 | α β2 | γ vira A -> α A_N | γ e A_N -> β1 | β2.
+  /// Os novos não terminais também são fatorados, até que nenhuma produção compartilhe o primeiro símbolo.
+  pub fn left_factor(&mut self) {
+    let mut index = 0;
+    while index < self.rules.len() {
+      while let Some(prefix) = self.common_prefix(index) {
+        let head = self.rules[index].0.clone();
+        let tail = self.fresh_name(&head);
+        let bodies = std::mem::take(&mut self.rules[index].1);
+        let mut factored: Vec<Vec<String>> = vec![];
+        let mut tail_bodies: Vec<Vec<String>> = vec![];
+        for body in bodies {
+          if body.starts_with(&prefix) {
+            // A produção fatorada fica na posição da primeira alternativa com o prefixo
+            if tail_bodies.is_empty() {
+              factored.push(prefix.iter().cloned().chain([tail.clone()]).collect());
+            }
+            let suffix = body[prefix.len()..].to_vec();
+            if !tail_bodies.contains(&suffix) { tail_bodies.push(suffix); }
+          } else {
+            factored.push(body);
+          }
+        }
+        self.rules[index].1 = factored;
+        self.insert_after(&head, tail, tail_bodies);
+      }
+      index += 1;
+    }
+  }
+
+  /// Maior prefixo comum entre as produções de `rules[index]` que começam com o mesmo símbolo.
+  fn common_prefix(&self, index: usize) -> Option<Vec<String>> {
+    let bodies = &self.rules[index].1;
+    for (i, body) in bodies.iter().enumerate() {
+      let Some(first) = body.first() else { continue; };
+      let group: Vec<&Vec<String>> = bodies[i..].iter().filter(|other| other.first() == Some(first)).collect();
+      if group.len() < 2 { continue; }
+      let mut length = 1;
+      while group.iter().all(|other| other.len() > length && other[length] == group[0][length]) {
+        length += 1;
+      }
+      return Some(group[0][..length].to_vec());
+    }
+    None
+  }
+
+  /// Calcula os conjuntos FIRST dos não terminais e quais deles são anuláveis.
+  fn first_sets(&self) -> (BTreeMap<String, BTreeSet<String>>, HashSet<String>) {
+    let mut first: BTreeMap<String, BTreeSet<String>> = self.rules.iter().map(|(head, _)| (head.clone(), BTreeSet::new())).collect();
+    let mut nullable: HashSet<String> = HashSet::new();
+    let mut changed = true;
+    while changed {
+      changed = false;
+      for (head, bodies) in &self.rules {
+        for body in bodies {
+          let (body_first, body_nullable) = Self::first_of(body, &first, &nullable);
+          let entry = first.get_mut(head).unwrap();
+          let before = entry.len();
+          entry.extend(body_first);
+          changed |= entry.len() != before;
+          if body_nullable { changed |= nullable.insert(head.clone()); }
+        }
+      }
+    }
+    (first, nullable)
+  }
+
+  /// FIRST de uma sequência de símbolos e se ela é anulável.
+  fn first_of(symbols: &[String], first: &BTreeMap<String, BTreeSet<String>>, nullable: &HashSet<String>) -> (BTreeSet<String>, bool) {
+    let mut result = BTreeSet::new();
+    for symbol in symbols {
+      if !is_non_terminal(symbol) {
+        result.insert(symbol.clone());
+        return (result, false);
+      }
+      result.extend(first.get(symbol).cloned().unwrap_or_default());
+      if !nullable.contains(symbol) { return (result, false); }
+    }
+    (result, true)
+  }
+
+  /// Constrói a tabela LL(1) no formato de `grammars/parse-table.txt`: `CABEÇA,token,índice da produção`.
+  /// Retorna a lista de conflitos caso a gramática não seja LL(1).
+  pub fn parse_table(&self) -> Result<Vec<ParseTableEntry>, Box<dyn Error>> {
+    let (first, nullable) = self.first_sets();
+    // FOLLOW: ponto fixo sobre todas as produções
+    let mut follow: BTreeMap<String, BTreeSet<String>> = self.rules.iter().map(|(head, _)| (head.clone(), BTreeSet::new())).collect();
+    follow.get_mut(self.start()).unwrap().insert(EOF.to_string());
+    let mut changed = true;
+    while changed {
+      changed = false;
+      for (head, body) in self.productions() {
+        for (i, symbol) in body.iter().enumerate() {
+          if !is_non_terminal(symbol) { continue; }
+          let (mut rest_first, rest_nullable) = Self::first_of(&body[i + 1..], &first, &nullable);
+          if rest_nullable { rest_first.extend(follow[head].clone()); }
+          let entry = follow.get_mut(symbol).unwrap();
+          let before = entry.len();
+          entry.extend(rest_first);
+          changed |= entry.len() != before;
+        }
+      }
+    }
+
+    let mut table: BTreeMap<(String, String), usize> = BTreeMap::new();
+    let mut entries = vec![];
+    let mut conflicts = vec![];
+    for (index, (head, body)) in self.productions().into_iter().enumerate() {
+      let (mut lookahead, body_nullable) = Self::first_of(body, &first, &nullable);
+      if body_nullable { lookahead.extend(follow[head].clone()); }
+      for token in lookahead {
+        match table.get(&(head.to_string(), token.clone())) {
+          Some(previous) => conflicts.push(format!("{} com {}: produções {} e {}", head, token, previous, index)),
+          None => {
+            table.insert((head.to_string(), token.clone()), index);
+            entries.push((head.to_string(), token, index));
+          }
+        }
+      }
+    }
+    if !conflicts.is_empty() {
+      return Err(format!("A gramática não é LL(1). Conflitos: {}", conflicts.join("; ")).into());
+    }
+    Ok(entries)
+  }
+}
+
+impl std::fmt::Display for Grammar {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    for (head, body) in self.productions() {
+      if body.is_empty() {
+        writeln!(f, "{},{}", head, EPSILON)?;
+      } else {
+        writeln!(f, "{},{}", head, body.join(" "))?;
+      }
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parse_table_matches_grammar_file() {
+    let grammar = Grammar::parse(include_str!("../../grammars/syntax.txt")).unwrap();
+    let table: String = grammar.parse_table().unwrap().iter()
+      .map(|(head, token, rule_index)| format!("{},{},{}\n", head, token, rule_index))
+      .collect();
+    assert!(table == include_str!("../../grammars/parse-table.txt"), "grammars/parse-table.txt está desatualizada");
+  }
+
+  #[test]
+  fn remove_indirect_left_recursion() {
+    // S -> A a e A -> S c formam o ciclo S => A a => S c a
+    let mut grammar = Grammar::parse("S,A a\nS,b\nA,S c\nA,d\n").unwrap();
+    grammar.remove_left_recursion();
+    assert_eq!(grammar.to_string(), "S,A a\nS,b\nA,b c A_1\nA,d A_1\nA_1,a c A_1\nA_1,''\n");
+  }
+
+  #[test]
+  fn remove_useless_keeps_reachable_productive_rules() {
+    let mut grammar = Grammar::parse("S,a B\nS,C\nB,b\nC,C c\nD,d\n").unwrap();
+    grammar.remove_useless();
+    assert_eq!(grammar.to_string(), "S,a B\nB,b\n");
+  }
+}
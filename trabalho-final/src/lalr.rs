@@ -0,0 +1,1105 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use crate::token::Token;
+use crate::grammar::token_type::TokenType;
+use crate::grammar::non_terminals::NonTerminal;
+use crate::grammar::semantic_node::SemanticNodeData;
+use crate::grammar::const_type::ConstType;
+use crate::semantic::{SemanticAttrs, SemanticNode, SemanticTree, Span};
+use crate::scope_stack::ScopeStack;
+
+/// Símbolo da gramática LALR(1). Diferente de `syntax::Symbol`, não carrega o token lido,
+/// pois só é usado para construção das tabelas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum GrammarSymbol {
+  Terminal(TokenType),
+  NonTerminal(NonTerminal),
+}
+
+impl std::fmt::Display for GrammarSymbol {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      GrammarSymbol::Terminal(tt) => write!(f, "{:?}", tt),
+      GrammarSymbol::NonTerminal(nt) => write!(f, "{:?}", nt),
+    }
+  }
+}
+
+/// Produção da gramática. A produção aumentada S' -> PROGRAM é a única com `head` igual a None.
+struct Production {
+  head: Option<NonTerminal>,
+  body: Vec<GrammarSymbol>,
+}
+
+impl std::fmt::Display for Production {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self.head {
+      Some(head) => write!(f, "{:?} ->", head)?,
+      None => write!(f, "S' ->")?,
+    }
+    if self.body.is_empty() { return write!(f, " ''"); }
+    for symbol in &self.body { write!(f, " {}", symbol)?; }
+    Ok(())
+  }
+}
+
+/// Item LR(0): índice da produção e posição do ponto no corpo da produção.
+type Item = (usize, usize);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Action {
+  Shift(usize),
+  Reduce(usize),
+  Accept,
+}
+
+/// Conflito encontrado durante a construção da tabela ACTION.
+/// Assim como no yacc, shift/reduce é resolvido a favor do shift
+/// e reduce/reduce é resolvido a favor da produção que aparece primeiro na gramática.
+pub enum Conflict {
+  ShiftReduce { state: usize, token: TokenType, production: usize },
+  ReduceReduce { state: usize, token: TokenType, kept: usize, discarded: usize },
+}
+
+/// Valor associado a cada estado na pilha do analisador.
+/// Terminais guardam o token lido, não terminais guardam o nó semântico já construído.
+enum StackValue {
+  Token(Token),
+  Node(SemanticNode),
+}
+
+impl StackValue {
+  fn node(self) -> SemanticNode {
+    match self {
+      StackValue::Node(node) => node,
+      StackValue::Token(token) => SemanticNode { span: Span::from_token(&token), children: SemanticNodeData::Terminal { value: token }, attrs: SemanticAttrs::default() },
+    }
+  }
+
+  fn span(&self) -> Span {
+    match self {
+      StackValue::Node(node) => node.span,
+      StackValue::Token(token) => Span::from_token(token),
+    }
+  }
+
+  fn token(self) -> Token {
+    let StackValue::Token(token) = self else { panic!("Expected terminal on LALR stack"); };
+    token
+  }
+
+  fn boxed(self) -> Box<SemanticNode> {
+    Box::new(self.node())
+  }
+
+  /// Na gramática de compatibilidade as funções são identificadas por um `id` comum.
+  /// O token é convertido no `func_id` equivalente (`soma` -> `@soma`) para gerar a mesma árvore das outras gramáticas.
+  fn func_id(self) -> Box<SemanticNode> {
+    let mut token = self.token();
+    if token.token_type == TokenType::Id {
+      let Some(ConstType::String(name)) = token.value else { panic!("Expected id value"); };
+      token.token_type = TokenType::FuncId;
+      token.value = Some(ConstType::String(format!("@{}", name)));
+    }
+    StackValue::Token(token).boxed()
+  }
+
+  /// Na gramática de compatibilidade, o corpo de if, else e dos laços é um STATEMENT qualquer.
+  /// Um bloco `{ STATELIST }` vira a própria STATELIST e um comando isolado é envolvido em uma STATELIST,
+  /// gerando a mesma árvore da gramática com chaves obrigatórias.
+  fn body(self) -> Box<SemanticNode> {
+    let node = self.node();
+    match node.children {
+      SemanticNodeData::Statelist { .. } => Box::new(node),
+      SemanticNodeData::Statement { statelist: Some(statelist), .. } => statelist,
+      _ => Box::new(SemanticNode { span: node.span, children: SemanticNodeData::Statelist { statelist: vec![node] }, attrs: SemanticAttrs::default() }),
+    }
+  }
+}
+
+pub struct LalrParser {
+  productions: Vec<Production>,
+  action: HashMap<(usize, TokenType), Action>,
+  goto: HashMap<(usize, NonTerminal), usize>,
+  state_count: usize,
+  pub conflicts: Vec<Conflict>,
+  root: Option<SemanticNode>,
+}
+
+impl LalrParser {
+  /// Carrega a gramática de `grammars/syntax-lalr.txt` e constrói as tabelas LALR(1).
+  pub fn new() -> Result<Self, Box<dyn Error>> {
+    Self::from_grammar(include_str!("../grammars/syntax-lalr.txt"))
+  }
+
+  /// Carrega a gramática original da linguagem ConvCC-2025-1 (`grammars/syntax-compat.txt`),
+  /// sem as modificações descritas no README: funções sem `@`, if/else/for sem chaves obrigatórias e o else pendente.
+  /// O conflito shift/reduce do else pendente é resolvido pelo shift, associando o else ao if mais próximo.
+  pub fn compat() -> Result<Self, Box<dyn Error>> {
+    Self::from_grammar(include_str!("../grammars/syntax-compat.txt"))
+  }
+
+  /// Constrói as tabelas LALR(1) para uma gramática no mesmo formato de `grammars/syntax.txt`.
+  /// A primeira regra do arquivo define o símbolo inicial.
+  pub fn from_grammar(rule_content: &str) -> Result<Self, Box<dyn Error>> {
+    let mut productions = vec![];
+    for line in rule_content.lines() {
+      let parts: Vec<&str> = line.split(',').collect();
+      if parts.len() != 2 { continue; }
+      let head = NonTerminal::from_str(parts[0])?;
+      let mut body = vec![];
+      if parts[1] != "''" {
+        for s in parts[1].split_whitespace() {
+          if let Ok(token) = TokenType::from_str(s) { body.push(GrammarSymbol::Terminal(token)); }
+          else if let Ok(nt) = NonTerminal::from_str(s) { body.push(GrammarSymbol::NonTerminal(nt)); }
+          else { return Err(format!("Símbolo inválido na gramática LALR: {}", s).into()); }
+        }
+      }
+      productions.push(Production { head: Some(head), body });
+    }
+    let Some(start) = productions.first().and_then(|p| p.head) else {
+      return Err("Gramática LALR vazia".into());
+    };
+    // Produção aumentada S' -> PROGRAM, sempre na posição 0
+    productions.insert(0, Production { head: None, body: vec![GrammarSymbol::NonTerminal(start)] });
+
+    let mut parser = LalrParser {
+      productions,
+      action: HashMap::new(),
+      goto: HashMap::new(),
+      state_count: 0,
+      conflicts: vec![],
+      root: None,
+    };
+    parser.build_tables();
+    Ok(parser)
+  }
+
+  /// Calcula o conjunto de não terminais anuláveis e o FIRST de cada não terminal por ponto fixo.
+  fn first_sets(&self) -> (HashSet<NonTerminal>, HashMap<NonTerminal, HashSet<TokenType>>) {
+    let mut nullable = HashSet::new();
+    let mut first: HashMap<NonTerminal, HashSet<TokenType>> = HashMap::new();
+    let mut changed = true;
+    while changed {
+      changed = false;
+      for production in self.productions.iter().skip(1) {
+        let head = production.head.unwrap();
+        let mut all_nullable = true;
+        for symbol in &production.body {
+          match symbol {
+            GrammarSymbol::Terminal(tt) => {
+              changed |= first.entry(head).or_default().insert(*tt);
+              all_nullable = false;
+            },
+            GrammarSymbol::NonTerminal(nt) => {
+              let symbol_first = first.get(nt).cloned().unwrap_or_default();
+              let entry = first.entry(head).or_default();
+              for tt in symbol_first { changed |= entry.insert(tt); }
+              if !nullable.contains(nt) { all_nullable = false; }
+            },
+          }
+          if !all_nullable { break; }
+        }
+        if all_nullable { changed |= nullable.insert(head); }
+      }
+    }
+    (nullable, first)
+  }
+
+  /// FIRST de uma sequência de símbolos seguida do lookahead `la`.
+  /// `None` representa o símbolo artificial # usado para descobrir lookaheads propagados.
+  fn first_of_sequence(
+    sequence: &[GrammarSymbol],
+    la: Option<TokenType>,
+    nullable: &HashSet<NonTerminal>,
+    first: &HashMap<NonTerminal, HashSet<TokenType>>,
+  ) -> Vec<Option<TokenType>> {
+    let mut result = vec![];
+    for symbol in sequence {
+      match symbol {
+        GrammarSymbol::Terminal(tt) => {
+          result.push(Some(*tt));
+          return result;
+        },
+        GrammarSymbol::NonTerminal(nt) => {
+          if let Some(set) = first.get(nt) { result.extend(set.iter().map(|tt| Some(*tt))); }
+          if !nullable.contains(nt) { return result; }
+        },
+      }
+    }
+    result.push(la);
+    result
+  }
+
+  /// Fecho LR(1) de um conjunto de itens com lookahead.
+  fn closure_lr1(
+    &self,
+    items: Vec<(Item, Option<TokenType>)>,
+    nullable: &HashSet<NonTerminal>,
+    first: &HashMap<NonTerminal, HashSet<TokenType>>,
+  ) -> HashSet<(Item, Option<TokenType>)> {
+    let mut closure: HashSet<(Item, Option<TokenType>)> = items.iter().cloned().collect();
+    let mut stack = items;
+    while let Some(((prod, dot), la)) = stack.pop() {
+      let body = &self.productions[prod].body;
+      let Some(GrammarSymbol::NonTerminal(nt)) = body.get(dot) else { continue; };
+      let lookaheads = Self::first_of_sequence(&body[dot+1..], la, nullable, first);
+      for (index, production) in self.productions.iter().enumerate() {
+        if production.head != Some(*nt) { continue; }
+        for b in &lookaheads {
+          let item = ((index, 0), *b);
+          if closure.insert(item) { stack.push(item); }
+        }
+      }
+    }
+    closure
+  }
+
+  /// Fecho LR(0) de um conjunto de itens.
+  fn closure_lr0(&self, kernel: &[Item]) -> Vec<Item> {
+    let mut closure: Vec<Item> = kernel.to_vec();
+    let mut seen: HashSet<Item> = kernel.iter().cloned().collect();
+    let mut i = 0;
+    while i < closure.len() {
+      let (prod, dot) = closure[i];
+      i += 1;
+      let Some(GrammarSymbol::NonTerminal(nt)) = self.productions[prod].body.get(dot) else { continue; };
+      for (index, production) in self.productions.iter().enumerate() {
+        if production.head == Some(*nt) && seen.insert((index, 0)) {
+          closure.push((index, 0));
+        }
+      }
+    }
+    closure
+  }
+
+  /// Constrói a coleção canônica LR(0), calcula os lookaheads LALR(1) por propagação
+  /// (algoritmo do livro do dragão, seção 4.7.5) e preenche as tabelas ACTION e GOTO.
+  fn build_tables(&mut self) {
+    let (nullable, first) = self.first_sets();
+
+    // Coleção canônica LR(0), representada pelos kernels de cada estado
+    let mut kernels: Vec<Vec<Item>> = vec![vec![(0, 0)]];
+    let mut kernel_index: HashMap<Vec<Item>, usize> = HashMap::new();
+    kernel_index.insert(vec![(0, 0)], 0);
+    let mut transitions: HashMap<(usize, GrammarSymbol), usize> = HashMap::new();
+    let mut state = 0;
+    while state < kernels.len() {
+      let closure = self.closure_lr0(&kernels[state]);
+      // Agrupa os itens pelo símbolo após o ponto, preservando a ordem de aparição
+      let mut symbols: Vec<GrammarSymbol> = vec![];
+      let mut next_kernels: HashMap<GrammarSymbol, Vec<Item>> = HashMap::new();
+      for (prod, dot) in closure {
+        let Some(symbol) = self.productions[prod].body.get(dot) else { continue; };
+        if !next_kernels.contains_key(symbol) { symbols.push(*symbol); }
+        next_kernels.entry(*symbol).or_default().push((prod, dot+1));
+      }
+      for symbol in symbols {
+        let mut kernel = next_kernels.remove(&symbol).unwrap();
+        kernel.sort();
+        kernel.dedup();
+        let target = match kernel_index.get(&kernel) {
+          Some(target) => *target,
+          None => {
+            kernels.push(kernel.clone());
+            kernel_index.insert(kernel, kernels.len()-1);
+            kernels.len()-1
+          },
+        };
+        transitions.insert((state, symbol), target);
+      }
+      state += 1;
+    }
+    self.state_count = kernels.len();
+
+    // Lookaheads dos itens de kernel: gerados espontaneamente ou propagados
+    let mut lookaheads: HashMap<(usize, Item), HashSet<TokenType>> = HashMap::new();
+    let mut propagation: HashMap<(usize, Item), Vec<(usize, Item)>> = HashMap::new();
+    lookaheads.entry((0, (0, 0))).or_default().insert(TokenType::Eof);
+    for (state, kernel) in kernels.iter().enumerate() {
+      for kernel_item in kernel {
+        let closure = self.closure_lr1(vec![(*kernel_item, None)], &nullable, &first);
+        for ((prod, dot), la) in closure {
+          let Some(symbol) = self.productions[prod].body.get(dot) else { continue; };
+          let target = (transitions[&(state, *symbol)], (prod, dot+1));
+          match la {
+            Some(tt) => { lookaheads.entry(target).or_default().insert(tt); },
+            None => { propagation.entry((state, *kernel_item)).or_default().push(target); },
+          }
+        }
+      }
+    }
+    let mut changed = true;
+    while changed {
+      changed = false;
+      for (source, targets) in &propagation {
+        let Some(source_lookaheads) = lookaheads.get(source).cloned() else { continue; };
+        for target in targets {
+          let entry = lookaheads.entry(*target).or_default();
+          for tt in &source_lookaheads { changed |= entry.insert(*tt); }
+        }
+      }
+    }
+
+    // Tabelas ACTION e GOTO
+    for ((state, symbol), target) in &transitions {
+      match symbol {
+        GrammarSymbol::Terminal(tt) => { self.action.insert((*state, *tt), Action::Shift(*target)); },
+        GrammarSymbol::NonTerminal(nt) => { self.goto.insert((*state, *nt), *target); },
+      }
+    }
+    for (state, kernel) in kernels.iter().enumerate() {
+      let mut items = vec![];
+      for kernel_item in kernel {
+        for la in lookaheads.get(&(state, *kernel_item)).cloned().unwrap_or_default() {
+          items.push((*kernel_item, Some(la)));
+        }
+      }
+      let mut reductions: Vec<(usize, TokenType)> = self.closure_lr1(items, &nullable, &first).into_iter()
+        .filter(|((prod, dot), _)| *dot == self.productions[*prod].body.len())
+        .map(|((prod, _), la)| (prod, la.unwrap()))
+        .collect();
+      // Ordena as reduções para que a resolução de conflitos não dependa da ordem do HashSet
+      reductions.sort_by_key(|(prod, la)| (*prod, la.to_string()));
+      for (prod, la) in reductions {
+        if prod == 0 {
+          self.action.insert((state, la), Action::Accept);
+          continue;
+        }
+        match self.action.get(&(state, la)) {
+          None => { self.action.insert((state, la), Action::Reduce(prod)); },
+          Some(Action::Shift(_)) => {
+            self.conflicts.push(Conflict::ShiftReduce { state, token: la, production: prod });
+          },
+          Some(Action::Reduce(kept)) => {
+            self.conflicts.push(Conflict::ReduceReduce { state, token: la, kept: *kept, discarded: prod });
+          },
+          Some(Action::Accept) => {},
+        }
+      }
+    }
+  }
+
+  /// Executa a análise sintática ascendente sobre a lista de tokens,
+  /// construindo a árvore semântica a cada redução.
+  pub fn parse(&mut self, tokens: &[Token]) -> Result<(), Box<dyn Error>> {
+    let mut states: Vec<usize> = vec![0];
+    let mut values: Vec<StackValue> = vec![];
+    let mut index = 0;
+    loop {
+      let current_token = &tokens[index];
+      let state = *states.last().unwrap();
+      let Some(action) = self.action.get(&(state, current_token.token_type)) else {
+        return Err(format!("Erro sintático: token inesperado encontrado na linha {}, coluna {}: {}", current_token.line, current_token.column, current_token).into());
+      };
+      match *action {
+        Action::Shift(target) => {
+          states.push(target);
+          values.push(StackValue::Token(current_token.clone()));
+          index += 1;
+        },
+        Action::Reduce(prod) => {
+          let size = self.productions[prod].body.len();
+          states.truncate(states.len() - size);
+          let children = values.split_off(values.len() - size);
+          let head = self.productions[prod].head.unwrap();
+          let node = Self::reduce(head, children);
+          let Some(target) = self.goto.get(&(*states.last().unwrap(), head)) else {
+            return Err(format!("Erro sintático: desvio inexistente para {:?} na tabela LALR", head).into());
+          };
+          states.push(*target);
+          values.push(StackValue::Node(node));
+        },
+        Action::Accept => {
+          self.root = Some(values.pop().unwrap().node());
+          return Ok(());
+        },
+      }
+    }
+  }
+
+  /// Ações semânticas executadas em cada redução.
+  /// Os nós gerados são os mesmos que `Node::visit` gera para a gramática LL(1),
+  /// de forma que a análise semântica não precisa saber qual analisador foi usado.
+  fn reduce(head: NonTerminal, children: Vec<StackValue>) -> SemanticNode {
+    let size = children.len();
+    // O span do nó reduzido vai do início do primeiro filho ao fim do último, ignorando produções vazias
+    let span = children.iter().fold(Span::default(), |span, child| span.merge(&child.span()));
+    let mut children = children.into_iter();
+    let mut next = move || children.next().unwrap();
+    let data = match head {
+      // PROGRAM -> FUNCLIST
+      NonTerminal::Program => SemanticNode::program(next().node()),
+      // FUNCLIST -> ''
+      // FUNCLIST -> FUNCLIST FUNCDEF
+      // FUNCLIST -> FUNCLIST STATEMENT
+      // FUNCLIST -> FUNCLIST STRUCTDEF
+      //   FUNCLIST.ptr = FUNCLIST_1.ptr + [FUNCDEF.ptr | STATEMENT.ptr | STRUCTDEF.ptr]
+      NonTerminal::Funclist => {
+        if size == 0 { SemanticNodeData::Funclist { funclist: vec![] } }
+        else {
+          let SemanticNodeData::Funclist { mut funclist } = next().node().children else { panic!() };
+          funclist.push(next().node());
+          SemanticNodeData::Funclist { funclist }
+        }
+      },
+      // FUNCDEF -> kw_def [FUNCTYPE] func_id lparenthesis PARAMLIST rparenthesis lbrace STATELIST rbrace
+      // FUNCDEF -> kw_def [FUNCTYPE] func_id lparenthesis rparenthesis lbrace STATELIST rbrace
+      NonTerminal::Funcdef => {
+        next();
+        // O FUNCTYPE já foi reduzido para um nó, enquanto o nome da função ainda é um token
+        let (return_type, func_id) = match next() {
+          StackValue::Node(return_type) => (Some(Box::new(return_type)), next().func_id()),
+          token => (None, token.func_id()),
+        };
+        next();
+        let paramlist = if size - return_type.is_some() as usize == 8 { Some(next().boxed()) } else { None };
+        next();
+        next();
+        SemanticNodeData::Funcdef { return_type, func_id, paramlist, statelist: next().boxed() }
+      },
+      // FUNCTYPE -> var_type | kw_void
+      // SIGNATURE_1 -> var_type | kw_void
+      // OP_ATRIBSTAT -> op_plus_assign | op_minus_assign | op_multiply_assign | op_division_assign | op_modular_assign
+      NonTerminal::Functype | NonTerminal::Signature1 | NonTerminal::OpAtribstat => SemanticNodeData::Terminal { value: next().token() },
+      // STRUCTDEF -> kw_struct id lbrace FIELDLIST rbrace
+      NonTerminal::Structdef => {
+        next();
+        let id = next().boxed();
+        next();
+        SemanticNodeData::Structdef { id, fieldlist: next().boxed() }
+      },
+      // FIELDLIST -> VARDECL semicolon
+      // FIELDLIST -> FIELDLIST VARDECL semicolon
+      NonTerminal::Fieldlist => {
+        let mut fields = vec![];
+        if size == 3 {
+          let SemanticNodeData::Fieldlist { fields: previous } = next().node().children else { panic!() };
+          fields = previous;
+        }
+        fields.push(next().node());
+        SemanticNodeData::Fieldlist { fields }
+      },
+      // PARAMLIST -> TYPE id [PARAM_INDEX]
+      // PARAMLIST -> PARAMLIST comma TYPE id [PARAM_INDEX]
+      NonTerminal::Paramlist => {
+        let mut paramlist = vec![];
+        if size >= 4 {
+          let SemanticNodeData::Paramlist { paramlist: previous } = next().node().children else { panic!() };
+          paramlist = previous;
+          next();
+        }
+        paramlist.push(next().node());
+        paramlist.push(next().node());
+        if size % 2 == 1 {
+          paramlist.push(next().node());
+        }
+        SemanticNodeData::Paramlist { paramlist }
+      },
+      // PARAM_INDEX -> lbracket rbracket [CONST_INDEX]
+      NonTerminal::ParamIndex => {
+        next();
+        next();
+        SemanticNodeData::ParamIndex { const_index: if size == 3 { Some(next().boxed()) } else { None } }
+      },
+      // STATELIST -> STATEMENT
+      // STATELIST -> STATELIST STATEMENT
+      NonTerminal::Statelist => {
+        let first = next().node();
+        match first.children {
+          SemanticNodeData::Statelist { mut statelist } if size == 2 => {
+            statelist.push(next().node());
+            SemanticNodeData::Statelist { statelist }
+          },
+          _ => SemanticNodeData::Statelist { statelist: vec![first] },
+        }
+      },
+      NonTerminal::Statement => {
+        // Cada posição corresponde a um campo de SemanticNodeData::Statement:
+        // [vardecl, atribstat, ifstat, forstat, whilestat, dowhilestat, statelist, commandstat]
+        let mut fields: [Option<Box<SemanticNode>>; 8] = Default::default();
+        match next() {
+          // STATEMENT -> semicolon
+          StackValue::Token(token) if token.token_type == TokenType::Semicolon => {},
+          // STATEMENT -> lbrace STATELIST rbrace
+          StackValue::Token(token) if token.token_type == TokenType::Lbrace => { fields[6] = Some(next().boxed()); },
+          // STATEMENT -> id colon LOOPSTAT
+          //   O id é o rótulo do laço, guardado no próprio nó do laço
+          StackValue::Token(label) => {
+            next();
+            let SemanticNodeData::Statement { forstat, whilestat, dowhilestat, .. } = next().node().children else { panic!() };
+            [fields[3], fields[4], fields[5]] = [forstat, whilestat, dowhilestat];
+            let Some(node) = fields[3..6].iter_mut().flatten().next() else { panic!() };
+            let (SemanticNodeData::Forstat { label: loop_label, .. }
+              | SemanticNodeData::Whilestat { label: loop_label, .. }
+              | SemanticNodeData::Dowhilestat { label: loop_label, .. }) = &mut node.children else { panic!() };
+            *loop_label = Some(StackValue::Token(label).boxed());
+          },
+          StackValue::Node(node) => {
+            let position = match node.children {
+              // STATEMENT -> (VARDECL | CONSTDECL) semicolon
+              // STATEMENT -> ENUMDEF
+              SemanticNodeData::Vardecl { .. } | SemanticNodeData::Constdecl { .. } | SemanticNodeData::Enumdef { .. } => 0,
+              SemanticNodeData::Atribstat { .. } => 1,
+              SemanticNodeData::Ifstat { .. } => 2,
+              SemanticNodeData::Forstat { .. } => 3,
+              SemanticNodeData::Whilestat { .. } => 4,
+              SemanticNodeData::Dowhilestat { .. } => 5,
+              // STATEMENT -> (PRINTSTAT | READSTAT | RETURNSTAT | FUNCCALL | JUMPSTAT) semicolon
+              // STATEMENT -> SWITCHSTAT
+              _ => 7,
+            };
+            fields[position] = Some(Box::new(node));
+          },
+        }
+        let [vardecl, atribstat, ifstat, forstat, whilestat, dowhilestat, statelist, commandstat] = fields;
+        SemanticNodeData::Statement { vardecl, atribstat, ifstat, forstat, whilestat, dowhilestat, statelist, commandstat }
+      },
+      // VARDECL -> TYPE id
+      // VARDECL -> TYPE id CONST_INDEX
+      // VARDECL -> TYPE id [CONST_INDEX] op_assign lbrace INITLIST rbrace
+      // VARDECL -> id id [CONST_INDEX]
+      NonTerminal::Vardecl => {
+        let var_type = next().boxed();
+        let id = next().boxed();
+        let const_index = if size == 3 || size == 7 { Some(next().boxed()) } else { None };
+        let init = if size >= 6 {
+          next();
+          next();
+          Some(next().boxed())
+        } else {
+          None
+        };
+        SemanticNodeData::Vardecl { var_type, id, const_index, init }
+      },
+      // INITLIST -> EXPRESSION
+      // INITLIST -> INITLIST comma EXPRESSION
+      NonTerminal::Initlist => {
+        let mut values = vec![];
+        if size == 3 {
+          let SemanticNodeData::Initlist { values: previous } = next().node().children else { panic!() };
+          values = previous;
+          next();
+        }
+        values.push(next().node());
+        SemanticNodeData::Initlist { values }
+      },
+      // TYPE -> var_type | SIGNATURE
+      NonTerminal::Type => match next() {
+        StackValue::Token(token) => SemanticNodeData::Terminal { value: token },
+        StackValue::Node(signature) => signature.children,
+      },
+      // SIGNATURE -> kw_func lparenthesis [TYPELIST] rparenthesis arrow SIGNATURE_1
+      NonTerminal::Signature => {
+        next();
+        next();
+        let params = if size == 6 {
+          let SemanticNodeData::Paramlist { paramlist } = next().node().children else { panic!() };
+          paramlist
+        } else {
+          vec![]
+        };
+        next();
+        next();
+        SemanticNodeData::Signature { params, return_type: next().boxed() }
+      },
+      // TYPELIST -> TYPE
+      // TYPELIST -> TYPELIST comma TYPE
+      //   Os tipos ficam em um nó PARAMLIST, lido por SIGNATURE
+      NonTerminal::Typelist => {
+        let mut paramlist = vec![];
+        if size == 3 {
+          let SemanticNodeData::Paramlist { paramlist: previous } = next().node().children else { panic!() };
+          paramlist = previous;
+          next();
+        }
+        paramlist.push(next().node());
+        SemanticNodeData::Paramlist { paramlist }
+      },
+      // CONSTDECL -> kw_const var_type id op_assign EXPRESSION
+      NonTerminal::Constdecl => {
+        next();
+        let var_type = next().boxed();
+        let id = next().boxed();
+        next();
+        SemanticNodeData::Constdecl { var_type, id, value: next().boxed() }
+      },
+      // ENUMDEF -> kw_enum id lbrace ENUMLIST rbrace
+      NonTerminal::Enumdef => {
+        next();
+        let id = next().boxed();
+        next();
+        SemanticNodeData::Enumdef { id, enumlist: next().boxed() }
+      },
+      // ENUMLIST -> id
+      // ENUMLIST -> ENUMLIST comma id
+      NonTerminal::Enumlist => {
+        let mut enumerators = vec![];
+        if size == 3 {
+          let SemanticNodeData::Enumlist { enumerators: previous } = next().node().children else { panic!() };
+          enumerators = previous;
+          next();
+        }
+        enumerators.push(next().node());
+        SemanticNodeData::Enumlist { enumerators }
+      },
+      // CONST_INDEX -> lbracket NUMEXPRESSION rbracket
+      // CONST_INDEX -> CONST_INDEX lbracket NUMEXPRESSION rbracket
+      NonTerminal::ConstIndex => {
+        let mut index = vec![];
+        if size == 4 {
+          let SemanticNodeData::ConstIndex { index: previous } = next().node().children else { panic!() };
+          index = previous;
+        }
+        next();
+        index.push(next().node());
+        SemanticNodeData::ConstIndex { index }
+      },
+      // VAR_INDEX -> lbracket NUMEXPRESSION rbracket
+      // VAR_INDEX -> VAR_INDEX lbracket NUMEXPRESSION rbracket
+      NonTerminal::VarIndex => {
+        let mut index = vec![];
+        if size == 4 {
+          let SemanticNodeData::VarIndex { index: previous } = next().node().children else { panic!() };
+          index = previous;
+        }
+        next();
+        index.push(next().node());
+        SemanticNodeData::VarIndex { index }
+      },
+      // ATRIBSTAT -> LVALUE op_assign ATRIBSTATEVALUE
+      // ATRIBSTAT -> LVALUE OP_ATRIBSTAT EXPRESSION
+      // ATRIBSTAT -> LVALUE (op_increment | op_decrement)
+      NonTerminal::Atribstat => {
+        let lvalue = next().node();
+        match next() {
+          StackValue::Token(token) if token.token_type == TokenType::OpAssign => {
+            SemanticNodeData::Atribstat { lvalue: Box::new(lvalue), op: None, value: next().boxed() }
+          },
+          StackValue::Token(token) => SemanticNode::compound_atribstat(lvalue, &token, None),
+          // OP_ATRIBSTAT já foi reduzido para o nó terminal do operador
+          StackValue::Node(op) => SemanticNode::compound_atribstat(lvalue, op.token(), Some(next().node())),
+        }
+      },
+      // ATRIBSTATEVALUE -> EXPRESSION | ALLOCEXPRESSION
+      NonTerminal::Atribstatevalue => {
+        let node = next().boxed();
+        match node.children {
+          SemanticNodeData::Allocexpression { .. } => SemanticNodeData::Atribstatevalue { expression: None, allocexpression: Some(node) },
+          _ => SemanticNodeData::Atribstatevalue { expression: Some(node), allocexpression: None },
+        }
+      },
+      // FUNCCALL -> func_id lparenthesis PARAMLISTCALL rparenthesis
+      // FUNCCALL -> func_id lparenthesis rparenthesis
+      // FUNCCALL -> LVALUE lparenthesis [PARAMLISTCALL] rparenthesis
+      //   Chamada indireta, pelo valor do LVALUE
+      NonTerminal::Funccall => {
+        let id = match next() {
+          StackValue::Node(lvalue) => Box::new(lvalue),
+          token => token.func_id(),
+        };
+        next();
+        SemanticNodeData::Funccall { id, paramlistcall: if size == 4 { Some(next().boxed()) } else { None } }
+      },
+      // PARAMLISTCALL -> EXPRESSION
+      // PARAMLISTCALL -> PARAMLISTCALL comma EXPRESSION
+      NonTerminal::Paramlistcall => {
+        let mut paramlist = vec![];
+        if size == 3 {
+          let SemanticNodeData::Paramlistcall { paramlist: previous } = next().node().children else { panic!() };
+          paramlist = previous;
+          next();
+        }
+        paramlist.push(next().node());
+        SemanticNodeData::Paramlistcall { paramlist }
+      },
+      // PRINTSTAT -> kw_print EXPRESSION
+      // PRINTSTAT -> PRINTSTAT comma EXPRESSION
+      NonTerminal::Printstat => {
+        let mut expressions = vec![];
+        if size == 3 {
+          let SemanticNodeData::Printstat { expressions: previous } = next().node().children else { panic!() };
+          expressions = previous;
+        }
+        next();
+        expressions.push(next().node());
+        SemanticNodeData::Printstat { expressions }
+      },
+      // READSTAT -> kw_read LVALUE
+      NonTerminal::Readstat => {
+        next();
+        SemanticNodeData::Readstat { lvalue: next().boxed() }
+      },
+      // RETURNSTAT -> kw_return
+      // RETURNSTAT -> kw_return EXPRESSION
+      NonTerminal::Returnstat => SemanticNodeData::Returnstat {
+        token: next().token(),
+        expression: if size == 2 { Some(next().boxed()) } else { None },
+      },
+      // JUMPSTAT -> kw_break | kw_continue
+      // JUMPSTAT -> (kw_break | kw_continue) id
+      NonTerminal::Jumpstat => SemanticNodeData::Jumpstat {
+        token: next().token(),
+        label: if size == 2 { Some(next().boxed()) } else { None },
+      },
+      // IFSTAT -> kw_if lparenthesis EXPRESSION rparenthesis lbrace STATELIST rbrace
+      // IFSTAT -> kw_if lparenthesis EXPRESSION rparenthesis lbrace STATELIST rbrace ELSESTAT
+      // Gramática de compatibilidade:
+      // IFSTAT -> kw_if lparenthesis EXPRESSION rparenthesis STATEMENT
+      // IFSTAT -> kw_if lparenthesis EXPRESSION rparenthesis STATEMENT ELSESTAT
+      NonTerminal::Ifstat => {
+        next();
+        next();
+        let condition = next().boxed();
+        next();
+        if size >= 7 {
+          next();
+          let then_branch = next().boxed();
+          next();
+          SemanticNodeData::Ifstat { condition, then_branch, else_branch: if size == 8 { Some(next().boxed()) } else { None } }
+        } else {
+          let then_branch = next().body();
+          SemanticNodeData::Ifstat { condition, then_branch, else_branch: if size == 6 { Some(next().boxed()) } else { None } }
+        }
+      },
+      // ELSESTAT -> kw_else IFSTAT
+      // ELSESTAT -> kw_else lbrace STATELIST rbrace
+      // Gramática de compatibilidade:
+      // ELSESTAT -> kw_else STATEMENT
+      //   Um else seguido de outro if guarda o IFSTAT diretamente, assim como em `else if` na gramática com chaves
+      NonTerminal::Elsestat => {
+        next();
+        if size == 4 { next(); }
+        let statement = next();
+        match statement {
+          StackValue::Node(SemanticNode { children: SemanticNodeData::Statement { ifstat: Some(ifstat), .. }, .. }) => {
+            SemanticNodeData::Elsestat { statement: ifstat }
+          },
+          StackValue::Node(SemanticNode { children: SemanticNodeData::Statement { .. }, .. }) => {
+            SemanticNodeData::Elsestat { statement: statement.body() }
+          },
+          _ => SemanticNodeData::Elsestat { statement: statement.boxed() },
+        }
+      },
+      // LOOPSTAT -> FORSTAT | WHILESTAT | DOWHILESTAT semicolon
+      //   LOOPSTAT.ptr = Node(STATEMENT, forstat=FORSTAT.ptr | whilestat=WHILESTAT.ptr | dowhilestat=DOWHILESTAT.ptr)
+      NonTerminal::Loopstat => {
+        let node = next().boxed();
+        let (forstat, whilestat, dowhilestat) = match node.children {
+          SemanticNodeData::Forstat { .. } => (Some(node), None, None),
+          SemanticNodeData::Whilestat { .. } => (None, Some(node), None),
+          _ => (None, None, Some(node)),
+        };
+        SemanticNodeData::Statement { vardecl: None, atribstat: None, ifstat: None, forstat, whilestat, dowhilestat, statelist: None, commandstat: None }
+      },
+      // FORSTAT -> kw_for lparenthesis FORINIT semicolon EXPRESSION semicolon ATRIBSTAT rparenthesis lbrace STATELIST rbrace
+      // Gramática de compatibilidade:
+      // FORSTAT -> kw_for lparenthesis FORINIT semicolon EXPRESSION semicolon ATRIBSTAT rparenthesis STATEMENT
+      NonTerminal::Forstat => {
+        next();
+        next();
+        let init = next().boxed();
+        next();
+        let condition = next().boxed();
+        next();
+        let increment = next().boxed();
+        next();
+        if size == 11 {
+          next();
+          SemanticNodeData::Forstat { label: None, init, condition, increment, body: next().boxed() }
+        } else {
+          SemanticNodeData::Forstat { label: None, init, condition, increment, body: next().body() }
+        }
+      },
+      // FORINIT -> ATRIBSTAT
+      // FORINIT -> var_type id op_assign EXPRESSION
+      NonTerminal::Forinit => {
+        if size == 1 { next().node().children }
+        else {
+          let var_type = next().boxed();
+          let id = next().boxed();
+          next();
+          SemanticNodeData::Forinit { var_type, id, value: next().boxed() }
+        }
+      },
+      // WHILESTAT -> kw_while lparenthesis EXPRESSION rparenthesis lbrace STATELIST rbrace
+      // Gramática de compatibilidade:
+      // WHILESTAT -> kw_while lparenthesis EXPRESSION rparenthesis STATEMENT
+      NonTerminal::Whilestat => {
+        next();
+        next();
+        let condition = next().boxed();
+        next();
+        if size == 7 {
+          next();
+          SemanticNodeData::Whilestat { label: None, condition, body: next().boxed() }
+        } else {
+          SemanticNodeData::Whilestat { label: None, condition, body: next().body() }
+        }
+      },
+      // DOWHILESTAT -> kw_do lbrace STATELIST rbrace kw_while lparenthesis EXPRESSION rparenthesis
+      // Gramática de compatibilidade:
+      // DOWHILESTAT -> kw_do STATEMENT kw_while lparenthesis EXPRESSION rparenthesis
+      NonTerminal::Dowhilestat => {
+        next();
+        let body = if size == 8 {
+          next();
+          let body = next().boxed();
+          next();
+          body
+        } else {
+          next().body()
+        };
+        next();
+        next();
+        SemanticNodeData::Dowhilestat { label: None, body, condition: next().boxed() }
+      },
+      // SWITCHSTAT -> kw_switch lparenthesis EXPRESSION rparenthesis lbrace CASELIST rbrace
+      NonTerminal::Switchstat => {
+        next();
+        next();
+        let expression = next().boxed();
+        next();
+        next();
+        SemanticNodeData::Switchstat { expression, caselist: next().boxed() }
+      },
+      // CASELIST -> CASELIST CASESTAT
+      // CASELIST -> ''
+      NonTerminal::Caselist => {
+        if size == 0 { SemanticNodeData::Caselist { cases: vec![] } }
+        else {
+          let SemanticNodeData::Caselist { mut cases } = next().node().children else { panic!() };
+          cases.push(next().node());
+          SemanticNodeData::Caselist { cases }
+        }
+      },
+      // CASESTAT -> kw_case NUMEXPRESSION colon [STATELIST]
+      // CASESTAT -> kw_default colon [STATELIST]
+      NonTerminal::Casestat => {
+        let value = if next().token().token_type == TokenType::KwCase { Some(next().boxed()) } else { None };
+        next();
+        // Símbolos lidos até os dois pontos: kw_case NUMEXPRESSION colon ou kw_default colon
+        let header = if value.is_some() { 3 } else { 2 };
+        SemanticNodeData::Casestat { value, statelist: if size > header { Some(next().boxed()) } else { None } }
+      },
+      // ALLOCEXPRESSION -> kw_new ALLOCTYPE
+      // ALLOCEXPRESSION -> kw_new ALLOCTYPE VAR_INDEX
+      //   Sem índices, as dimensões são um VAR_INDEX vazio, assim como VAR_INDEX -> '' na gramática LL(1)
+      NonTerminal::Allocexpression => {
+        next();
+        let var_type = next().boxed();
+        let dimensions = if size == 3 { next().boxed() } else {
+          Box::new(SemanticNode { span: Span::default(), children: SemanticNodeData::VarIndex { index: vec![] }, attrs: SemanticAttrs::default() })
+        };
+        SemanticNodeData::Allocexpression { var_type, dimensions }
+      },
+      // ALLOCTYPE -> var_type | id
+      NonTerminal::Alloctype => SemanticNodeData::Terminal { value: next().token() },
+      // EXPRESSION -> NUMEXPRESSION
+      // EXPRESSION -> NUMEXPRESSION OP_EXPRESSION NUMEXPRESSION
+      // EXPRESSION -> NUMEXPRESSION question EXPRESSION colon EXPRESSION
+      // EXPRESSION -> NUMEXPRESSION OP_EXPRESSION NUMEXPRESSION question EXPRESSION colon EXPRESSION
+      //   A condição vira um nó EXPRESSION próprio, assim como EXPRESSION_2.inh na gramática LL(1)
+      NonTerminal::Expression => {
+        let numexpression = next().boxed();
+        let mut condition_span = numexpression.span;
+        let expression = if size == 3 || size == 7 {
+          let op_expression = next().boxed();
+          let numexpression2 = next().boxed();
+          condition_span = condition_span.merge(&numexpression2.span);
+          SemanticNodeData::Expression { numexpression, op_expression: Some(op_expression), numexpression2: Some(numexpression2) }
+        } else {
+          SemanticNodeData::Expression { numexpression, op_expression: None, numexpression2: None }
+        };
+        if size < 5 {
+          expression
+        } else {
+          let condition = Box::new(SemanticNode { span: condition_span, children: expression, attrs: SemanticAttrs::default() });
+          next();
+          let then_expression = next().boxed();
+          next();
+          SemanticNodeData::Condexpression { condition, then_expression, else_expression: next().boxed() }
+        }
+      },
+      // NUMEXPRESSION -> TERM
+      // NUMEXPRESSION -> NUMEXPRESSION OP_NUMEXPRESSION TERM
+      //   Um NUMEXPRESSION sem operador à esquerda é desembrulhado, assim como em NUMEXPRESSION_1 na gramática LL(1)
+      NonTerminal::Numexpression => {
+        let left = next().boxed();
+        if size == 3 {
+          let term = match left.children {
+            SemanticNodeData::Numexpression { term, op_numexpression: None, .. } => term,
+            _ => left,
+          };
+          SemanticNodeData::Numexpression { term, op_numexpression: Some(next().boxed()), term2: Some(next().boxed()) }
+        } else {
+          SemanticNodeData::Numexpression { term: left, op_numexpression: None, term2: None }
+        }
+      },
+      // TERM -> UNARYEXPRESSION
+      // TERM -> TERM OP_TERM UNARYEXPRESSION
+      NonTerminal::Term => {
+        let left = next().boxed();
+        if size == 3 {
+          let unaryexpression = match left.children {
+            SemanticNodeData::Term { unaryexpression, op_term: None, .. } => unaryexpression,
+            _ => left,
+          };
+          SemanticNodeData::Term { unaryexpression, op_term: Some(next().boxed()), unaryexpression2: Some(next().boxed()) }
+        } else {
+          SemanticNodeData::Term { unaryexpression: left, op_term: None, unaryexpression2: None }
+        }
+      },
+      // UNARYEXPRESSION -> FACTOR
+      // UNARYEXPRESSION -> OP_NUMEXPRESSION FACTOR
+      NonTerminal::Unaryexpression => {
+        if size == 2 {
+          SemanticNodeData::Unaryexpression { op: Some(next().boxed()), factor: next().boxed() }
+        } else {
+          SemanticNodeData::Unaryexpression { op: None, factor: next().boxed() }
+        }
+      },
+      // CASTEXPRESSION -> lparenthesis var_type rparenthesis FACTOR
+      NonTerminal::Castexpression => {
+        next();
+        let var_type = next().boxed();
+        next();
+        SemanticNodeData::Castexpression { var_type, factor: next().boxed() }
+      },
+      // FACTOR -> CONSTANT | LVALUE | FUNCCALL | lparenthesis NUMEXPRESSION rparenthesis
+      // FACTOR -> CASTEXPRESSION | func_id | INTERPOLATION
+      //   A conversão, a referência a uma função e a interpolação não são envolvidas em um nó FACTOR, assim como na gramática LL(1)
+      NonTerminal::Factor => {
+        if size == 3 {
+          next();
+          SemanticNodeData::Factor { expression: Some(next().boxed()), lvalue: None, constant: None, funccall: None }
+        } else {
+          let node = next().boxed();
+          match node.children {
+            SemanticNodeData::Lvalue { .. } => SemanticNodeData::Factor { expression: None, lvalue: Some(node), constant: None, funccall: None },
+            SemanticNodeData::Funccall { .. } => SemanticNodeData::Factor { expression: None, lvalue: None, constant: None, funccall: Some(node) },
+            SemanticNodeData::Castexpression { .. } | SemanticNodeData::Interpolation { .. } => node.children,
+            SemanticNodeData::Terminal { .. } => SemanticNodeData::Funcref { id: node },
+            _ => SemanticNodeData::Factor { expression: None, lvalue: None, constant: Some(node), funccall: None },
+          }
+        }
+      },
+      // INTERPOLATION -> INTERPOLATION_1 string_end
+      // INTERPOLATION_1 -> string_start EXPRESSION
+      // INTERPOLATION_1 -> INTERPOLATION_1 string_middle EXPRESSION
+      //   Os trechos de texto e as expressões são acumulados em um único nó INTERPOLATION
+      NonTerminal::Interpolation | NonTerminal::Interpolation1 => {
+        let mut segments = match next() {
+          StackValue::Node(node) => {
+            let SemanticNodeData::Interpolation { segments } = node.children else { panic!() };
+            segments
+          },
+          start => vec![start.node()],
+        };
+        for _ in 1..size {
+          segments.push(next().node());
+        }
+        SemanticNodeData::Interpolation { segments }
+      },
+      // LVALUE -> id
+      // LVALUE -> id VAR_INDEX
+      // LVALUE -> LVALUE dot id
+      // LVALUE -> LVALUE dot id VAR_INDEX
+      //   O campo é acrescentado ao fim da cadeia de acessos, gerando a mesma árvore de FIELD_ACCESS na gramática LL(1)
+      NonTerminal::Lvalue => {
+        match next() {
+          StackValue::Node(mut lvalue) => {
+            next();
+            let id = next().boxed();
+            let var_index = if size == 4 { Some(next().boxed()) } else { None };
+            let field_span = id.span.merge(&var_index.as_ref().map_or(Span::default(), |var_index| var_index.span));
+            lvalue.push_field(SemanticNode { span: field_span, children: SemanticNodeData::Lvalue { id, var_index, field: None }, attrs: SemanticAttrs::default() });
+            lvalue.children
+          },
+          id => SemanticNodeData::Lvalue { id: id.boxed(), var_index: if size == 2 { Some(next().boxed()) } else { None }, field: None },
+        }
+      },
+      // CONSTANT -> const_int | const_float | const_string | const_null
+      NonTerminal::Constant => {
+        let token = next().token();
+        SemanticNodeData::Constant { value: token.value.unwrap().unescape_braces() }
+      },
+      NonTerminal::OpExpression => SemanticNodeData::OpExpression { op: next().token().token_type },
+      NonTerminal::OpNumexpression => SemanticNodeData::OpNumexpression { op: next().token().token_type },
+      NonTerminal::OpTerm => SemanticNodeData::OpTerm { op: next().token().token_type },
+      _ => panic!("Não terminal {:?} não pertence à gramática LALR", head),
+    };
+    SemanticNode { span, children: data, attrs: SemanticAttrs::default() }
+  }
+
+  pub fn semantic_tree(&mut self) -> Result<SemanticTree, Box<dyn Error>> {
+    let Some(root) = self.root.take() else {
+      return Err("Análise sintática LALR não foi executada".into());
+    };
+    Ok(SemanticTree { root, scopes: ScopeStack::new() })
+  }
+
+  pub fn output_stats(&self, output: &mut String) {
+    output.push_str(&format!("Análise sintática LALR(1) concluída com sucesso. Tabela com {} estados e {} produções.\n", self.state_count, self.productions.len()-1));
+    output.push_str(&format!("Conflitos encontrados na construção da tabela: {} [\n", self.conflicts.len()));
+    for conflict in &self.conflicts {
+      output.push_str(&format!("  {}\n", self.describe_conflict(conflict)));
+    }
+    output.push_str("]\n");
+  }
+
+  pub fn describe_conflict(&self, conflict: &Conflict) -> String {
+    match conflict {
+      Conflict::ShiftReduce { state, token, production } => {
+        format!("shift/reduce no estado {} com {}: shift escolhido no lugar de reduzir por {}", state, token, self.productions[*production])
+      },
+      Conflict::ReduceReduce { state, token, kept, discarded } => {
+        format!("reduce/reduce no estado {} com {}: reduzir por {} escolhido no lugar de {}", state, token, self.productions[*kept], self.productions[*discarded])
+      },
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lexer::Lexer;
+
+  /// Indica, para cada if da árvore em pré-ordem, se ele possui um else.
+  fn else_branches(node: &mut SemanticNode, found: &mut Vec<bool>) {
+    if let SemanticNodeData::Ifstat { else_branch, .. } = &node.children {
+      found.push(else_branch.is_some());
+    }
+    for child in node.children.children_mut() { else_branches(child, found); }
+  }
+
+  #[test]
+  fn lalr_grammar_has_no_conflicts() {
+    let parser = LalrParser::new().unwrap();
+    let conflicts: Vec<String> = parser.conflicts.iter().map(|conflict| parser.describe_conflict(conflict)).collect();
+    assert!(conflicts.is_empty(), "{:?}", conflicts);
+  }
+
+  #[test]
+  fn compat_grammar_only_conflict_is_dangling_else() {
+    let parser = LalrParser::compat().unwrap();
+    let [Conflict::ShiftReduce { state, token, production }] = parser.conflicts.as_slice() else {
+      panic!("{:?}", parser.conflicts.iter().map(|conflict| parser.describe_conflict(conflict)).collect::<Vec<_>>());
+    };
+    assert_eq!(*token, TokenType::KwElse);
+    assert_eq!(parser.productions[*production].head, Some(NonTerminal::Ifstat));
+    assert!(matches!(parser.action.get(&(*state, TokenType::KwElse)), Some(Action::Shift(_))));
+  }
+
+  #[test]
+  fn dangling_else_binds_to_nearest_if() {
+    let mut lexer = Lexer::new();
+    lexer.parse("def main() { int x; if (1) if (0) x = 1; else x = 2; }").unwrap();
+    let mut parser = LalrParser::compat().unwrap();
+    parser.parse(&lexer.token_list).unwrap();
+    let mut tree = parser.semantic_tree().unwrap();
+    let mut found = vec![];
+    else_branches(&mut tree.root, &mut found);
+    assert_eq!(found, vec![false, true]);
+  }
+}
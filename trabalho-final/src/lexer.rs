@@ -9,13 +9,29 @@ pub struct Lexer {
   pub fda: FDA,
   pub token_list: TokenList,
   pub token_table: TokenTable,
+  pub comments: Vec<Comment>,
   line_count: usize,
   column_count: usize,
   token_value: String,
   string: bool,
+  /// Número de expressões embutidas em strings (`"a = {a}"`) sendo lidas, uma para cada string aninhada
+  interpolations: usize,
+  /// Lendo o texto de uma string após uma expressão embutida, até a próxima expressão ou o fim da string
+  text: bool,
+  comment: Option<Comment>,
   current_state: State
 }
 
+/// Comentário de linha (`// ...`) encontrado no código fonte.
+/// Comentários não geram tokens, mas são guardados para que o formatador possa reescrevê-los.
+#[derive(Debug, Clone)]
+pub struct Comment {
+  pub line: usize,
+  pub column: usize,
+  /// Texto do comentário, incluindo o `//` inicial e sem a quebra de linha
+  pub text: String,
+}
+
 pub type TokenList = Vec<Token>;
 pub type TokenTable = HashMap<String, TokenEntry>;
 pub type TokenEntry = Vec<(u32, u32)>;
@@ -27,17 +43,21 @@ impl Lexer {
       fda,
       token_list: vec![],
       token_table: HashMap::new(),
+      comments: vec![],
       line_count: 1,
       column_count: 0,
       token_value: String::new(),
       string: false,
+      interpolations: 0,
+      text: false,
+      comment: None,
       current_state: 0
     }
   }
 
   /// Retorna um erro léxico com a linha, coluna e o token inválido.
   fn lexical_error(&self) -> Result<(), Box<dyn Error>> {
-    Err(format!("Erro léxico: Caracter inválido na linha {}, coluna {}: '{}'", self.line_count, self.column_count-self.token_value.len(), self.token_value).into())
+    Err(format!("Erro léxico: Caracter inválido na linha {}, coluna {}: '{}'", self.line_count, self.column_count-self.token_value.chars().count(), self.token_value).into())
   }
 
   /// Verifica se o token construído até agora é válido.
@@ -54,7 +74,8 @@ impl Lexer {
           token_type: *token_type,
           value: if token_type.has_value() {Some(ConstType::from_str(&self.token_value))} else { None },
           line: self.line_count,
-          column: self.column_count-self.token_value.len(),
+          column: self.column_count-self.token_value.chars().count(),
+          length: self.token_value.chars().count(),
         };
         // Se for um identificador, adiciona-o à tabela de símbolos
         if token_type.is_id() {
@@ -86,6 +107,20 @@ impl Lexer {
     Ok(())
   }
 
+  /// Cria um token com um trecho de texto de uma string com expressões embutidas, que termina no caractere atual.
+  /// O valor do token mantém as aspas e as chaves do código fonte: `"a = {`, `}, b = {` e `}"`.
+  fn push_text_token(&mut self, token_type: TokenType) {
+    let length = self.token_value.chars().count();
+    self.token_list.push(Token {
+      token_type,
+      value: Some(ConstType::String(self.token_value.clone())),
+      line: self.line_count,
+      column: self.column_count + 1 - length,
+      length,
+    });
+    self.token_value.clear();
+  }
+
   /// Transita pelo autômato finito determinístico (AFD) com o estado atual e o caractere fornecido.
   /// Atualisa o estado atual e o valor do token (se must_push for true), se a transição for válida.
   /// Retorna true se a transição for válida, false caso contrário.
@@ -105,9 +140,86 @@ impl Lexer {
   /// Se encontrar um erro léxico, retorna um erro.
   pub fn parse(&mut self, input: &str) -> Result<(), Box<dyn Error>> {
     // Para cada caractere do input, realiza a análise léxica
-    for char in input.chars() {
+    let mut chars = input.chars().peekable();
+    while let Some(char) = chars.next() {
       // contagem da coluna onde o caractere está
       self.column_count += 1;
+      // Dentro de um comentário, todos os caracteres são guardados sem alteração até o fim da linha
+      if let Some(comment) = &mut self.comment {
+        if char != '\n' {
+          comment.text.push(char);
+          continue;
+        }
+        let mut comment = self.comment.take().unwrap();
+        comment.text.truncate(comment.text.trim_end().len());
+        self.comments.push(comment);
+        self.line_count += 1;
+        self.column_count = 0;
+        continue;
+      }
+      // O texto de uma string após uma expressão embutida é lido sem o autômato,
+      // já que começa com o '}' que fecha a expressão
+      if self.text {
+        self.token_value.push(char);
+        match char {
+          // "{{" é uma chave literal, mantida no texto
+          '{' if chars.peek() == Some(&'{') => {
+            self.token_value.push(chars.next().unwrap());
+            self.column_count += 1;
+          },
+          // Início da próxima expressão embutida
+          '{' => {
+            self.text = false;
+            self.push_text_token(TokenType::StringMiddle);
+          },
+          // Fim da string
+          '"' => {
+            self.text = false;
+            self.interpolations -= 1;
+            self.push_text_token(TokenType::StringEnd);
+          },
+          '\n' => {
+            self.line_count += 1;
+            self.column_count = 0;
+          },
+          _ => {},
+        }
+        continue;
+      }
+      // "{{" dentro de uma string é uma chave literal, e não inicia uma expressão embutida.
+      // As duas chaves são mantidas no valor do token, como "}}", e substituídas por uma ao gerar o código
+      if self.string && char == '{' && chars.peek() == Some(&'{') {
+        chars.next();
+        self.column_count += 1;
+        self.transition(self.current_state, char);
+        self.transition(self.current_state, char);
+        continue;
+      }
+      // Uma '{' dentro de uma string inicia uma expressão embutida, lida como o restante do código
+      if self.string && char == '{' {
+        self.token_value.push(char);
+        self.push_text_token(TokenType::StringStart);
+        self.current_state = self.fda.initial_state;
+        self.string = false;
+        self.interpolations += 1;
+        continue;
+      }
+      // Uma '}' fora de uma string fecha a expressão embutida, e a string continua
+      if !self.string && self.interpolations > 0 && char == '}' {
+        self.is_valid_token()?;
+        self.token_value = char.to_string();
+        self.current_state = self.fda.initial_state;
+        self.text = true;
+        continue;
+      }
+      // Início de um comentário: o token construído até agora termina antes do "//"
+      if !self.string && char == '/' && chars.peek() == Some(&'/') {
+        self.is_valid_token()?;
+        self.token_value.clear();
+        self.current_state = self.fda.initial_state;
+        self.comment = Some(Comment { line: self.line_count, column: self.column_count, text: char.to_string() });
+        continue;
+      }
       // Abrindo e fechando strings
       if char == '"' {
         self.string = !self.string;
@@ -148,6 +260,15 @@ impl Lexer {
         self.column_count = 0;
       }
     }
+    // Um comentário na última linha termina junto com o input
+    if let Some(mut comment) = self.comment.take() {
+      comment.text.truncate(comment.text.trim_end().len());
+      self.comments.push(comment);
+    }
+    // Uma string, ou uma expressão embutida em uma string, aberta até o fim do input não foi terminada
+    if self.string || self.text || self.interpolations > 0 {
+      return Err(format!("Erro léxico: string não terminada no fim do arquivo, na linha {}", self.line_count).into());
+    }
     // Depois de ler todo o input, verifica se o último token lido é válido
     self.is_valid_token()?;
     // Adiciona um token de fim de arquivo (EOF) à lista de tokens
@@ -156,6 +277,7 @@ impl Lexer {
       value: None,
       line: self.line_count,
       column: self.column_count,
+      length: 0,
     });
 
     Ok(())
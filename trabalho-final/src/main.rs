@@ -16,19 +16,69 @@ mod semantic;
 mod expression;
 mod scope_stack;
 mod code_attrs;
+mod lalr;
+mod formatter;
 
 use lexer::Lexer;
 use syntax::SyntaxTree;
+use lalr::LalrParser;
+
+use grammar::transform::Grammar;
 
 use std::error::Error;
 
 fn main() -> Result<(), Box<dyn Error>> {
   // Read the file to be compiled from command line arguments
+  // A flag --lalr troca o analisador sintático LL(1) pelo LALR(1)
+  // A flag --compat usa o analisador LALR(1) com a gramática original da linguagem ConvCC-2025-1
+  // As flags --transform e --parse-table recebem um arquivo de gramática no formato de grammars/syntax.txt
+  // A flag --fmt imprime o código formatado, e --check apenas verifica se o arquivo já está formatado
+  // A flag --print-sep=<texto> define o texto impresso entre os valores de um print com várias expressões (um espaço por padrão)
   let args: Vec<String> = std::env::args().collect();
-  if args.len() < 2 { return Err("Usage: <input_file>".into()); }
-  let input_file = &args[1];
+  let compat = args.iter().any(|arg| arg == "--compat");
+  let use_lalr = compat || args.iter().any(|arg| arg == "--lalr");
+  let check = args.iter().any(|arg| arg == "--check");
+  let fmt = check || args.iter().any(|arg| arg == "--fmt");
+  let transform = args.iter().any(|arg| arg == "--transform");
+  let parse_table = args.iter().any(|arg| arg == "--parse-table");
+  let print_separator = args.iter().find_map(|arg| arg.strip_prefix("--print-sep=")).unwrap_or(" ");
+  let Some(input_file) = args.iter().skip(1).find(|arg| !arg.starts_with("--")) else {
+    return Err("Usage: [--lalr | --compat] [--fmt [--check]] [--print-sep=<texto>] <input_file>\n       [--transform] [--parse-table] <grammar_file>".into());
+  };
   let input = std::fs::read_to_string(input_file)?;
 
+  // Grammar tools
+  // --transform imprime a gramática sem recursão à esquerda, fatorada e sem símbolos inúteis
+  // --parse-table imprime a tabela LL(1) da gramática (transformada, se usado junto com --transform)
+  if transform || parse_table {
+    let mut grammar = Grammar::parse(&input)?;
+    if transform {
+      grammar.transform();
+    }
+    if parse_table {
+      for (head, token, rule_index) in grammar.parse_table()? {
+        println!("{},{},{}", head, token, rule_index);
+      }
+    } else {
+      print!("{}", grammar);
+    }
+    return Ok(());
+  }
+
+  // Formatting
+  if fmt {
+    let formatted = formatter::format_source(&input)?;
+    if !check {
+      print!("{}", formatted);
+    } else if formatted != input {
+      println!("Arquivo '{}' não está formatado.", input_file);
+      std::process::exit(1);
+    } else {
+      println!("Arquivo '{}' está formatado.", input_file);
+    }
+    return Ok(());
+  }
+
   // Lexical analysis
   let mut output = String::with_capacity(512*1024); // 512 KB
   output.push_str(&format!("# INICIANDO ANÁLISE LÉXICA #\n"));
@@ -38,19 +88,26 @@ fn main() -> Result<(), Box<dyn Error>> {
 
   // Syntax analysis
   output.push_str(&format!("\n# INICIANDO ANÁLISE SINTÁTICA #\n"));
-  let mut syntax_tree = SyntaxTree::new()?;
-  syntax_tree.parse(&lexer.token_list)?;
-  syntax_tree.output_stats(&mut output);
+  let mut semantic_tree = if use_lalr {
+    let mut parser = if compat { LalrParser::compat()? } else { LalrParser::new()? };
+    parser.parse(&lexer.token_list)?;
+    parser.output_stats(&mut output);
+    parser.semantic_tree()?
+  } else {
+    let mut syntax_tree = SyntaxTree::new()?;
+    syntax_tree.parse(&lexer.token_list)?;
+    syntax_tree.output_stats(&mut output);
+    syntax_tree.semantic_tree()?
+  };
 
   // Semantic analysis
   output.push_str(&format!("\n# INICIANDO ANÁLISE SEMÂNTICA #\n"));
-  let mut semantic_tree = syntax_tree.semantic_tree()?;
   semantic_tree.semantic_analysis()?;
   semantic_tree.output_stats(&mut output);
 
   // Generate intermediate code
   output.push_str(&format!("\n# GERANDO CÓDIGO INTERMEDIÁRIO #\n"));
-  let intermediate_code = semantic_tree.generate_code();
+  let intermediate_code = semantic_tree.generate_code(print_separator);
   output.push_str(&format!("Código intermediário gerado:\n{}\n", intermediate_code));
 
   // print the output
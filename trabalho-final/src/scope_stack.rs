@@ -1,12 +1,16 @@
 use std::collections::HashMap;
 use std::error::Error;
-use crate::grammar::const_type::VarType;
+use crate::grammar::const_type::{ConstType, VarType};
+use crate::token::Token;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ScopeType {
+  // Escopo das funções pré-definidas, abaixo do escopo global
+  Prelude,
   Function,
   Loop,
   LoopInit,
+  Switch,
   If,
   Else,
   Any
@@ -17,21 +21,190 @@ pub struct SymbolEntry {
   pub appearances: Vec<(usize, usize)>, // (line, column)
   pub var_type: Vec<VarType>,
   pub const_index: Vec<u32>,
+  // Tipo de retorno declarado de uma função. None para variáveis e para funções sem tipo declarado
+  pub return_type: Option<VarType>,
+  // Valor de uma constante nomeada (`const int N = 10;`). Constantes não podem receber atribuições
+  pub const_value: Option<ConstType>,
+  // Enumeradores, na ordem de declaração, do símbolo que nomeia um enum (`enum Cor { VERMELHO, VERDE }`).
+  // Os enumeradores são constantes do tipo do enum, inseridas no mesmo escopo
+  pub enumerators: Option<Vec<String>>,
+  // Dimensões de cada parâmetro de uma função, vazias para os parâmetros que não são vetores.
+  // A primeira dimensão de um parâmetro vetor é 0, já que ele aceita vetores de qualquer tamanho
+  pub param_index: Vec<Vec<u32>>,
+  // Demais definições de uma função sobrecarregada, na ordem de declaração, com listas de parâmetros diferentes.
+  // A primeira definição é a própria entrada
+  pub overloads: Vec<SymbolEntry>,
+  // A variável já recebeu um valor alocado com new, que pode ter mais dimensões que as declaradas
+  pub allocated: bool,
 }
 
-type Scope = (ScopeType, HashMap<String, SymbolEntry>);
+impl SymbolEntry {
+  /// Definições de uma função: a própria entrada e as suas sobrecargas
+  pub fn definitions(&self) -> impl Iterator<Item = &SymbolEntry> {
+    std::iter::once(self).chain(self.overloads.iter())
+  }
+
+  /// Assinatura de uma definição de função, usada nas mensagens de erro: `@f(int, float[][3])`
+  pub fn signature(&self, name: &str) -> String {
+    format_signature(name, &self.var_type, &self.param_index)
+  }
+}
+
+/// Nome seguido dos tipos e dimensões dos parâmetros. Parâmetros sem dimensões informadas não são vetores
+pub fn format_signature(name: &str, var_type: &[VarType], param_index: &[Vec<u32>]) -> String {
+  let params: Vec<String> = var_type.iter().enumerate()
+    .map(|(position, var_type)| format!("{}{}", var_type, format_dimensions(param_index.get(position).map_or(&[], Vec::as_slice))))
+    .collect();
+  format!("{}({})", name, params.join(", "))
+}
+
+/// Dimensões de um vetor no formato da declaração. A primeira dimensão de um parâmetro vetor, 0, não tem tamanho
+pub fn format_dimensions(dimensions: &[u32]) -> String {
+  dimensions.iter().map(|size| if *size == 0 { "[]".to_string() } else { format!("[{}]", size) }).collect()
+}
+
+pub type Scope = (ScopeType, HashMap<String, SymbolEntry>);
+
+/// Funções pré-definidas da linguagem: nome, tipo e dimensões de cada parâmetro e tipo de retorno.
+/// Nomes repetidos são sobrecargas. `@size` recebe um vetor de uma dimensão, de qualquer tamanho
+fn prelude() -> HashMap<String, SymbolEntry> {
+  use VarType::{Int, Float};
+  let builtins = [
+    ("@sqrt", vec![(Float, vec![])], Float),
+    ("@pow", vec![(Float, vec![]), (Float, vec![])], Float),
+    ("@abs", vec![(Int, vec![])], Int),
+    ("@abs", vec![(Float, vec![])], Float),
+    ("@len", vec![(VarType::String, vec![])], Int),
+    ("@substr", vec![(VarType::String, vec![]), (Int, vec![]), (Int, vec![])], VarType::String),
+    ("@to_int", vec![(VarType::String, vec![])], Int),
+    ("@to_string", vec![(Int, vec![])], VarType::String),
+    ("@to_string", vec![(Float, vec![])], VarType::String),
+    ("@size", vec![(Int, vec![0])], Int),
+    ("@size", vec![(Float, vec![0])], Int),
+    ("@size", vec![(VarType::String, vec![0])], Int),
+  ];
+  let mut table: HashMap<String, SymbolEntry> = HashMap::new();
+  for (name, params, return_type) in builtins {
+    let (var_type, param_index) = params.into_iter().unzip();
+    let entry = SymbolEntry {
+      appearances: vec![],
+      var_type,
+      const_index: vec![],
+      return_type: Some(return_type),
+      const_value: None,
+      enumerators: None,
+      param_index,
+      overloads: vec![],
+      allocated: false,
+    };
+    match table.get_mut(name) {
+      Some(previous) => previous.overloads.push(entry),
+      None => { table.insert(name.to_string(), entry); },
+    }
+  }
+  table
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldEntry {
+  pub name: String,
+  pub var_type: VarType,
+  pub const_index: Vec<u32>,
+  // Deslocamento, em palavras, do campo a partir do início da struct
+  pub offset: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructEntry {
+  pub position: (usize, usize), // (line, column)
+  // Campos na ordem em que foram declarados, que é a ordem em que ficam na memória
+  pub fields: Vec<FieldEntry>,
+  // Tamanho, em palavras
+  pub size: u32,
+}
+
+impl StructEntry {
+  pub fn get_field(&self, name: &str) -> Option<&FieldEntry> {
+    self.fields.iter().find(|field| field.name == name)
+  }
+}
+
+/// Tabela dos tipos definidos pelo programa (structs).
+/// As structs são definidas apenas fora das funções, então a tabela não possui escopos.
+#[derive(Debug, Clone, Default)]
+pub struct TypeTable {
+  structs: HashMap<String, StructEntry>,
+}
+
+impl TypeTable {
+  /// Insere uma struct, calculando o deslocamento de cada campo e o tamanho total.
+  /// Os tipos dos campos já devem estar na tabela, o que impede uma struct de conter a si mesma.
+  pub fn insert_struct(&mut self, name: String, position: (usize, usize), mut fields: Vec<FieldEntry>) -> Result<(), Box<dyn Error>> {
+    if self.structs.contains_key(&name) {
+      return Err(format!("Erro semântico: Redefinição da struct '{}' na linha {}, coluna {}", name, position.0, position.1).into());
+    }
+    let mut size = 0;
+    for field in fields.iter_mut() {
+      field.offset = size;
+      size += self.size_of(&field.var_type) * field.const_index.iter().product::<u32>();
+    }
+    self.structs.insert(name, StructEntry { position, fields, size });
+    Ok(())
+  }
+
+  pub fn get_struct(&self, name: &str) -> Option<&StructEntry> {
+    self.structs.get(name)
+  }
+
+  /// Tamanho, em palavras, de um valor do tipo. Um valor int, float ou string (guardada por referência) ocupa
+  /// uma palavra, que é a unidade dos índices do código intermediário
+  pub fn size_of(&self, var_type: &VarType) -> u32 {
+    match var_type {
+      VarType::Struct(name) => self.structs[name].size,
+      _ => 1,
+    }
+  }
+
+  pub fn output(&self) -> String {
+    let mut names: Vec<&String> = self.structs.keys().collect();
+    names.sort();
+    let mut output = String::new();
+    for name in names {
+      let entry = &self.structs[name];
+      output.push_str(&format!("Struct: {}, Tamanho em palavras: {}, Posição: {:?}", name, entry.size, entry.position));
+      for field in &entry.fields {
+        output.push_str(&format!("\n  Campo: {}, Tipo: {:?}, Índices: {:?}, Deslocamento em palavras: {}", field.name, field.var_type, field.const_index, field.offset));
+      }
+      output.push('\n');
+    }
+    output
+  }
+}
 
 #[derive(Debug)]
 pub struct ScopeStack {
   pub stack: Vec<Scope>,
   pub output: String,
+  // Tipo de retorno declarado da função sendo analisada
+  pub return_type: Option<VarType>,
+  // Rótulos dos laços sendo analisados, do mais externo ao mais interno. Laços sem rótulo guardam None
+  pub loop_labels: Vec<Option<String>>,
+  // Structs definidas pelo programa
+  pub types: TypeTable,
+  // Avisos da análise semântica, que não impedem a compilação
+  pub warnings: Vec<String>,
 }
 
 impl ScopeStack {
   pub fn new() -> Self {
     ScopeStack { 
-      stack: vec![(ScopeType::Any, HashMap::new())],
+      // O escopo global fica acima do escopo das funções pré-definidas, que podem ser escondidas pelo programa
+      stack: vec![(ScopeType::Prelude, prelude()), (ScopeType::Any, HashMap::new())],
       output: String::new(),
+      return_type: None,
+      loop_labels: vec![],
+      types: TypeTable::default(),
+      warnings: vec![],
     }
   }
 
@@ -44,12 +217,22 @@ impl ScopeStack {
     let mut scope_display = String::new();
     if let Some((scope_type, table)) = &x {
       scope_display.push_str(&format!("Escopo: {:?}", scope_type));
-      for (name, entry) in table {
+      for (name, entry) in table.iter().flat_map(|(name, entry)| entry.definitions().map(move |entry| (name, entry))) {
         if entry.var_type.len() == 1 {
           scope_display.push_str(&format!("\n  Símbolo: {}, Tipo: {:?}, Índices: {:?}, Aparições: {:?}", name, entry.var_type[0], entry.const_index, entry.appearances));
         } else {
           scope_display.push_str(&format!("\n  Símbolo: {}, Tipo: {:?}, Índices: {:?}, Aparições: {:?}", name, entry.var_type, entry.const_index, entry.appearances));
         }
+        if entry.param_index.iter().any(|index| !index.is_empty()) {
+          scope_display.push_str(&format!(", Índices dos parâmetros: {:?}", entry.param_index));
+        }
+        if let Some(enumerators) = &entry.enumerators {
+          scope_display.push_str(&format!(", Enumeradores: {:?}", enumerators));
+        } else if let (Some(value), Some(VarType::Enum(_))) = (&entry.const_value, entry.var_type.first()) {
+          scope_display.push_str(&format!(", Enumerador: {}", value));
+        } else if let Some(value) = &entry.const_value {
+          scope_display.push_str(&format!(", Constante: {}", value));
+        }
       }
     }
     scope_display.push_str("\n");
@@ -71,6 +254,22 @@ impl ScopeStack {
     Ok(())
   }
 
+  pub fn insert_function(&mut self, name: String, entry: SymbolEntry) -> Result<(), Box<dyn Error>> {
+    // Uma função com o mesmo nome de outra já definida é uma sobrecarga, desde que a lista de parâmetros
+    // (tipos e dimensões) seja diferente da lista de todas as definições anteriores
+    let Some(current_scope) = self.stack.last_mut() else { panic!("No current scope to insert symbol"); };
+    let Some(previous) = current_scope.1.get_mut(&name) else {
+      current_scope.1.insert(name, entry);
+      return Ok(());
+    };
+    if let Some(definition) = previous.definitions().find(|definition| definition.var_type == entry.var_type && definition.param_index == entry.param_index) {
+      let (line, column) = entry.appearances.last().unwrap_or(&(0, 0));
+      return Err(format!("Erro semântico: Redefinição da função '{}' na linha {}, coluna {}, com os mesmos parâmetros da definição na linha {}", definition.signature(&name), line, column, definition.appearances[0].0).into());
+    }
+    previous.overloads.push(entry);
+    Ok(())
+  }
+
   pub fn get_symbol(&self, name: &str) -> Option<SymbolEntry> {
     // Procura o símbolo nos escopos, começando do mais interno (topo da pilha).
     for scope in self.stack.iter().rev() {
@@ -96,8 +295,55 @@ impl ScopeStack {
     Err(format!("Erro semântico: Símbolo não encontrado na linha {}, coluna {}: {}", line, column, name).into())
   }
 
+  pub fn mark_allocated(&mut self, name: &str) {
+    // Marca o símbolo visível com esse nome como alocado com new.
+    if let Some(entry) = self.stack.iter_mut().rev().find_map(|scope| scope.1.get_mut(name)) {
+      entry.allocated = true;
+    }
+  }
+
+  pub fn count_overload_appearance(&mut self, name: &str, overload: usize, line: usize, column: usize) {
+    // Conta a aparição na definição escolhida de uma função sobrecarregada (0 é a primeira definição).
+    let Some(entry) = self.stack.iter_mut().rev().find_map(|scope| scope.1.get_mut(name)) else { panic!("Function not found: {}", name) };
+    let entry = if overload == 0 { entry } else { &mut entry.overloads[overload - 1] };
+    entry.appearances.push((line, column));
+  }
+
+  pub fn in_global_scope(&self) -> bool {
+    // O escopo global é o segundo da pilha, logo acima do escopo das funções pré-definidas.
+    self.stack.len() == 2
+  }
+
+  pub fn is_builtin(&self, name: &str) -> bool {
+    // Verifica se o símbolo visível com esse nome é uma função pré-definida, que não foi escondida pelo programa.
+    self.stack.iter().rev().find(|scope| scope.1.contains_key(name)).is_some_and(|scope| scope.0 == ScopeType::Prelude)
+  }
+
   pub fn contains(&self, scope_type: ScopeType) -> bool {
     // Verifica se a pilha de escopo contém algum escopo do tipo especificado.
     self.stack.iter().rev().any(|(st, _)| *st == scope_type)
   }
+
+  pub fn push_loop(&mut self, label: Option<&Token>) -> Result<(), Box<dyn Error>> {
+    // Um laço não pode usar o mesmo rótulo de um laço que o contém, já que `break rotulo` seria ambíguo.
+    let Some(label) = label else {
+      self.loop_labels.push(None);
+      return Ok(());
+    };
+    let Some(ConstType::String(name)) = &label.value else { panic!("Expected id value"); };
+    if self.contains_label(name) {
+      return Err(format!("Erro semântico: Rótulo '{}' já utilizado por um laço externo na linha {}, coluna {}", name, label.line, label.column).into());
+    }
+    self.loop_labels.push(Some(name.clone()));
+    Ok(())
+  }
+
+  pub fn pop_loop(&mut self) {
+    self.loop_labels.pop();
+  }
+
+  pub fn contains_label(&self, name: &str) -> bool {
+    // Verifica se algum dos laços sendo analisados possui o rótulo.
+    self.loop_labels.iter().any(|label| label.as_deref() == Some(name))
+  }
 }
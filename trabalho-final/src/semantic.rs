@@ -1,44 +1,593 @@
 use core::panic;
 use std::error::Error;
 use crate::code_attrs::CodeAttrs;
+use crate::scope_stack::Scope;
 use crate::scope_stack::ScopeStack;
 use crate::scope_stack::ScopeType;
 use crate::scope_stack::SymbolEntry;
+use crate::scope_stack::FieldEntry;
+use crate::scope_stack::{format_dimensions, format_signature};
 use crate::grammar::semantic_node::SemanticNodeData;
-use crate::grammar::const_type::{ConstType, VarType};
+use crate::grammar::const_type::{unescape_braces, ConstType, VarType};
 use crate::grammar::token_type::TokenType;
 use crate::expression::*;
+use crate::token::Token;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct SemanticNode {
-  pub children: SemanticNodeData
+  pub children: SemanticNodeData,
+  pub span: Span,
+  pub attrs: SemanticAttrs,
+}
+
+/// Informações que a análise semântica registra no próprio nó, lidas pela geração de código,
+/// que não conhece os tipos dos valores. Os nós construídos pelos analisadores sintáticos começam sem nenhuma.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SemanticAttrs {
+  // Definição escolhida de uma função sobrecarregada (0 é a primeira), registrada no func_id
+  // de cada definição, chamada e referência
+  pub overload: Option<usize>,
+  // Nome no código gerado da definição escolhida, quando a função possui sobrecargas (`@f.2`).
+  // Preenchido ao fim da análise, quando todas as definições são conhecidas
+  pub function_name: Option<String>,
+  // O func_id de uma chamada é uma função pré-definida, gerada como uma operação própria
+  pub builtin: bool,
+  // O operador (concatenação ou comparação), o último índice de um LVALUE ou a expressão de um switch se aplica
+  // a uma string, e é gerado com uma chamada às funções de strings do ambiente de execução
+  pub string_operation: bool,
+  // Tipo para o qual o valor do nó é convertido: float nas promoções de int, int ou float nos casts
+  // e string nas expressões embutidas em strings. No LVALUE de um read, o tipo para o qual o texto lido é convertido
+  pub conversion: Option<VarType>,
+  // Dimensões não indexadas do valor de um LVALUE, que só é um vetor quando forma sozinho uma expressão.
+  // Usadas pela própria análise, que não aceita vetores como operandos
+  pub dimensions: Vec<u32>,
+}
+
+/// Trecho do código fonte ocupado por um nó da árvore semântica.
+/// `start` é a posição (linha, coluna) do primeiro caractere e `end` a posição logo após o último.
+/// Nós que não consomem nenhum token, como uma lista de funções vazia, possuem o span padrão (0, 0).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Span {
+  pub start: (usize, usize),
+  pub end: (usize, usize),
+}
+
+impl Span {
+  pub fn from_token(token: &Token) -> Span {
+    Span { start: (token.line, token.column), end: token.end() }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    *self == Span::default()
+  }
+
+  /// Span que começa no início de `self` e termina no fim de `other`.
+  pub fn merge(&self, other: &Span) -> Span {
+    if self.is_empty() { return *other; }
+    if other.is_empty() { return *self; }
+    Span { start: self.start, end: other.end }
+  }
+
+  /// Span de uma lista de nós, do primeiro ao último elemento.
+  pub fn of_list(list: &[SemanticNode]) -> Span {
+    match (list.first(), list.last()) {
+      (Some(first), Some(last)) => first.span.merge(&last.span),
+      _ => Span::default(),
+    }
+  }
+}
+
+impl std::fmt::Display for Span {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}:{}-{}:{}", self.start.0, self.start.1, self.end.0, self.end.1)
+  }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 enum ReturnSem {
   Dado {
     tipo: VarType,
-    pos: Option<(usize, usize)>,
   },
   TT(TokenType),
 }
 
 impl SemanticNode {
-  fn semantic_analysis(&self, scopes: &mut ScopeStack) -> Result<Option<ReturnSem>, Box<dyn Error>> {
+  /// Token de um nó terminal, como o id que dá nome a um laço.
+  pub fn token(&self) -> &Token {
+    let SemanticNodeData::Terminal { value } = &self.children else { panic!("Expected terminal node"); };
+    value
+  }
+
+  /// Nome do rótulo de um laço, usado para encontrar o destino de `break` e `continue` na geração de código.
+  fn label_name(&self) -> String {
+    self.token().value.as_ref().unwrap().to_string()
+  }
+
+  /// Atribuição composta (`x += e`) ou incremento (`x++`, `x--`), representada por um `Atribstat` com o operador aritmético
+  /// equivalente: `x += e` tem as mesmas verificações de `x = x + e`, e `x++` as de `x = x + 1`.
+  /// `value` é None para `++` e `--`.
+  pub fn compound_atribstat(lvalue: SemanticNode, op: &Token, value: Option<SemanticNode>) -> SemanticNodeData {
+    let span = Span::from_token(op);
+    let op = match op.token_type {
+      TokenType::OpPlusAssign | TokenType::OpIncrement => SemanticNodeData::OpNumexpression { op: TokenType::OpPlus },
+      TokenType::OpMinusAssign | TokenType::OpDecrement => SemanticNodeData::OpNumexpression { op: TokenType::OpMinus },
+      TokenType::OpMultiplyAssign => SemanticNodeData::OpTerm { op: TokenType::OpMultiply },
+      TokenType::OpDivisionAssign => SemanticNodeData::OpTerm { op: TokenType::OpDivision },
+      TokenType::OpModularAssign => SemanticNodeData::OpTerm { op: TokenType::OpModular },
+      _ => panic!("Expected compound assignment operator"),
+    };
+    let value = value.unwrap_or(SemanticNode { span, children: SemanticNodeData::Constant { value: ConstType::Int(1) }, attrs: SemanticAttrs::default() });
+    SemanticNodeData::Atribstat {
+      lvalue: Box::new(lvalue),
+      op: Some(Box::new(SemanticNode { span, children: op, attrs: SemanticAttrs::default() })),
+      value: Box::new(SemanticNode {
+        span: value.span,
+        children: SemanticNodeData::Atribstatevalue { expression: Some(Box::new(value)), allocexpression: None },
+        attrs: SemanticAttrs::default(),
+      }),
+    }
+  }
+
+  /// Programa formado pelos itens de FUNCLIST: definições de funções e declarações de variáveis globais.
+  /// Um programa com um único comando e nenhuma função é o `PROGRAM -> STATEMENT` da gramática original.
+  pub fn program(funclist: SemanticNode) -> SemanticNodeData {
+    let SemanticNodeData::Funclist { funclist: mut items } = funclist.children else { panic!("Expected FUNCLIST node"); };
+    if items.len() == 1 && matches!(items[0].children, SemanticNodeData::Statement { .. }) {
+      return SemanticNodeData::Program { funclist: None, statement: items.pop().map(Box::new) };
+    }
+    let funclist = SemanticNode { span: funclist.span, children: SemanticNodeData::Funclist { funclist: items }, attrs: SemanticAttrs::default() };
+    SemanticNodeData::Program { funclist: Some(Box::new(funclist)), statement: None }
+  }
+
+  /// Acrescenta o acesso a um campo ao fim da cadeia de acessos de um LVALUE (`p.pos` + `x` = `p.pos.x`).
+  /// Usado pelo analisador LALR, em que `LVALUE -> LVALUE dot id` lê os campos da esquerda para a direita.
+  pub fn push_field(&mut self, field: SemanticNode) {
+    self.span = self.span.merge(&field.span);
+    let SemanticNodeData::Lvalue { field: next, .. } = &mut self.children else { panic!("Expected LVALUE node"); };
+    match next {
+      Some(next) => next.push_field(field),
+      None => *next = Some(Box::new(field)),
+    }
+  }
+
+  /// Tipo de uma declaração: um var_type, uma SIGNATURE ou o id com o nome de uma struct ou de um enum visível no escopo.
+  fn declared_type(&self, scopes: &mut ScopeStack) -> Result<VarType, Box<dyn Error>> {
+    if let SemanticNodeData::Signature { .. } = &self.children {
+      return Ok(self.signature_type());
+    }
+    let token = self.token();
+    if token.token_type != TokenType::Id {
+      return Ok(token.value.as_ref().unwrap().get_keyword_type());
+    }
+    let name = token.value.as_ref().unwrap().to_string();
+    if scopes.types.get_struct(&name).is_some() {
+      return Ok(VarType::Struct(name));
+    }
+    if scopes.get_symbol(&name).is_some_and(|entry| entry.enumerators.is_some()) {
+      scopes.count_appearance(&name, token.line, token.column)?;
+      return Ok(VarType::Enum(name));
+    }
+    Err(format!("Erro semântico: tipo '{}' não definido na linha {}, coluna {}", name, token.line, token.column).into())
+  }
+
+  /// Tipo de função descrito por uma SIGNATURE: func(int, float) -> int.
+  /// Os tipos dos parâmetros são var_types ou outras SIGNATUREs, e não dependem do escopo
+  fn signature_type(&self) -> VarType {
+    let SemanticNodeData::Signature { params, return_type } = &self.children else { panic!("Expected SIGNATURE node"); };
+    let params = params.iter().map(|param| match &param.children {
+      SemanticNodeData::Signature { .. } => param.signature_type(),
+      _ => param.token().value.as_ref().unwrap().get_keyword_type(),
+    }).collect();
+    let return_type = match return_type.token().token_type {
+      TokenType::KwVoid => VarType::Void,
+      _ => return_type.token().value.as_ref().unwrap().get_keyword_type(),
+    };
+    VarType::Func(params, Box::new(return_type))
+  }
+
+  /// Dimensões de um vetor declarado com CONST_INDEX.
+  /// Cada tamanho é uma expressão constante do tipo int, maior que zero
+  fn array_dimensions(const_index: &mut Option<Box<SemanticNode>>, id_name: &str, scopes: &mut ScopeStack) -> Result<Vec<u32>, Box<dyn Error>> {
+    let mut dimensions = vec![];
+    if let Some(const_index) = const_index {
+      const_index.semantic_analysis(scopes)?;
+      let SemanticNodeData::ConstIndex { index } = &const_index.children else { panic!() };
+      let constants = |name: &str| scopes.get_symbol(name).and_then(|entry| entry.const_value);
+      for child in index.iter() {
+        match child.const_value(&constants)? {
+          ConstType::Int(size) if size > 0 && size <= u32::MAX as i64 => dimensions.push(size as u32),
+          _ => return Err(format!("Erro semântico: o tamanho do vetor '{}' deve ser um inteiro positivo na linha {}, coluna {}", id_name, child.span.start.0, child.span.start.1).into()),
+        }
+      }
+    }
+    Ok(dimensions)
+  }
+
+  /// Tipo do valor acessado por um LVALUE, a partir do tipo e das dimensões do id (ou campo) com que ele começa,
+  /// e as dimensões que não foram indexadas (vazias quando o valor acessado não é um vetor).
+  /// LVALUE -> id VAR_INDEX
+  ///  Cada índice é do tipo int
+  /// LVALUE -> id VAR_INDEX LVALUE
+  ///  LVALUE_1.tipo é uma struct com o campo LVALUE_2.id, e todas as dimensões de id são indexadas
+  ///  LVALUE_1.tipo = LVALUE_2.tipo, a partir do tipo e das dimensões do campo
+  /// `allocated` indica que o id já recebeu um valor alocado com new, que pode ter mais dimensões que as declaradas.
+  fn access_type(&mut self, tipo: VarType, dimensions: &[u32], allocated: bool, scopes: &mut ScopeStack) -> Result<(VarType, Vec<u32>), Box<dyn Error>> {
+    let SemanticNodeData::Lvalue { id, var_index, field } = &mut self.children else { panic!("Expected LVALUE node"); };
+    let mut indexes = 0;
+    if let Some(var_index) = var_index {
+      let SemanticNodeData::VarIndex { index } = &mut var_index.children else { panic!("{:?}", var_index.children) };
+      for child in index.iter_mut() {
+        let Some(ReturnSem::Dado { tipo }) = child.semantic_analysis(scopes)? else { panic!(); };
+        if tipo != VarType::Int {
+          return Err(format!("Erro semântico: índice de variável deve ser do tipo 'int', encontrado '{:?}' na linha {}, coluna {}", tipo, child.span.start.0, child.span.start.1).into());
+        }
+      }
+      indexes = index.len();
+    }
+    let Some(field) = field else {
+      // LVALUE -> id VAR_INDEX, com exatamente um índice a mais que as dimensões de uma string
+      //  O último índice acessa um caractere da string: LVALUE.tipo = string
+      //  Com mais índices, o valor é um vetor alocado com new, como para os outros tipos, e
+      //  uma string que não recebeu um valor alocado com new não pode ter mais índices
+      if tipo == VarType::String && indexes > dimensions.len() {
+        let Some(SemanticNode { children: SemanticNodeData::VarIndex { index }, .. }) = var_index.as_deref_mut() else { panic!("Expected VARINDEX node"); };
+        if let Some(extra) = index.get(dimensions.len() + 1).filter(|_| !allocated) {
+          return Err(format!("Erro semântico: a string '{}' aceita no máximo {} índice(s), já que não recebeu um valor alocado com new, na linha {}, coluna {}", id.token().value.as_ref().unwrap(), dimensions.len() + 1, extra.span.start.0, extra.span.start.1).into());
+        }
+        if indexes == dimensions.len() + 1 {
+          index.last_mut().unwrap().attrs.string_operation = true;
+        }
+      }
+      return Ok((tipo, dimensions.iter().skip(indexes).copied().collect()));
+    };
+    let SemanticNodeData::Lvalue { id, .. } = &field.children else { panic!("Expected LVALUE node"); };
+    let token = id.token();
+    let name = token.value.as_ref().unwrap().to_string();
+    let VarType::Struct(struct_name) = &tipo else {
+      return Err(format!("Erro semântico: acesso ao campo '{}' de um valor do tipo '{}' na linha {}, coluna {}", name, tipo, token.line, token.column).into());
+    };
+    if indexes != dimensions.len() {
+      return Err(format!("Erro semântico: acesso ao campo '{}' de um vetor de '{}' sem indexar todas as suas dimensões na linha {}, coluna {}", name, struct_name, token.line, token.column).into());
+    }
+    let Some(entry) = scopes.types.get_struct(struct_name).and_then(|entry| entry.get_field(&name)).cloned() else {
+      return Err(format!("Erro semântico: struct '{}' não possui o campo '{}' na linha {}, coluna {}", struct_name, name, token.line, token.column).into());
+    };
+    field.access_type(entry.var_type, &entry.const_index, false, scopes)
+  }
+
+  /// Tipo e dimensões não indexadas do valor acessado por um LVALUE, contando a aparição do seu id.
+  fn lvalue_type(&mut self, scopes: &mut ScopeStack) -> Result<(VarType, Vec<u32>), Box<dyn Error>> {
+    let SemanticNodeData::Lvalue { id, .. } = &mut self.children else { panic!("Expected LVALUE node"); };
+    let Some(ReturnSem::Dado { tipo }) = id.semantic_analysis(scopes)? else { panic!(); };
+    let entry = scopes.get_symbol(&id.token().value.as_ref().unwrap().to_string()).unwrap();
+    self.access_type(tipo, &entry.const_index, entry.allocated, scopes)
+  }
+
+  /// Token do id com que um LVALUE começa.
+  fn lvalue_id(&self) -> &Token {
+    let SemanticNodeData::Lvalue { id, .. } = &self.children else { panic!("Expected LVALUE node"); };
+    id.token()
+  }
+
+  /// LVALUE sem o último índice, que acessa um caractere de uma string, e esse índice.
+  /// Como strings não possuem campos, o índice é o último do último campo acessado.
+  fn without_string_index(&self) -> (SemanticNode, SemanticNode) {
+    let mut base = self.clone();
+    let mut lvalue = &mut base;
+    while let SemanticNodeData::Lvalue { field: Some(_), .. } = &lvalue.children {
+      let SemanticNodeData::Lvalue { field: Some(field), .. } = &mut lvalue.children else { unreachable!() };
+      lvalue = field;
+    }
+    let SemanticNodeData::Lvalue { var_index, .. } = &mut lvalue.children else { panic!("Expected LVALUE node"); };
+    let Some(SemanticNode { children: SemanticNodeData::VarIndex { index }, .. }) = var_index.as_deref_mut() else { panic!("Expected VARINDEX node"); };
+    let last = index.pop().unwrap();
+    if index.is_empty() {
+      *var_index = None;
+    }
+    (base, last)
+  }
+
+  /// Verifica se o LVALUE acessa um caractere de uma string, a partir do índice marcado pela análise semântica.
+  fn indexes_string(&self) -> bool {
+    let SemanticNodeData::Lvalue { var_index, field, .. } = &self.children else { panic!("Expected LVALUE node"); };
+    if let Some(field) = field {
+      return field.indexes_string();
+    }
+    let Some(SemanticNode { children: SemanticNodeData::VarIndex { index }, .. }) = var_index.as_deref() else { return false; };
+    index.last().is_some_and(|last| last.attrs.string_operation)
+  }
+
+  /// LVALUE de uma expressão formada apenas por ele (sem operadores), como um argumento `v` ou `m[i]`.
+  /// Apenas essas expressões podem ter um vetor como valor.
+  fn bare_lvalue(&self) -> Option<&SemanticNode> {
+    match &self.children {
+      SemanticNodeData::Expression { numexpression, op_expression: None, .. } => numexpression.bare_lvalue(),
+      SemanticNodeData::Numexpression { term, op_numexpression: None, .. } => term.bare_lvalue(),
+      SemanticNodeData::Term { unaryexpression, op_term: None, .. } => unaryexpression.bare_lvalue(),
+      SemanticNodeData::Unaryexpression { op: None, factor } => factor.bare_lvalue(),
+      SemanticNodeData::Factor { expression: Some(expression), .. } => expression.bare_lvalue(),
+      SemanticNodeData::Factor { lvalue: Some(lvalue), .. } => Some(lvalue),
+      SemanticNodeData::Lvalue { .. } => Some(self),
+      SemanticNodeData::Atribstatevalue { expression: Some(expression), .. } => expression.bare_lvalue(),
+      _ => None,
+    }
+  }
+
+  /// Dimensões não indexadas do valor de uma expressão, vazias quando ele não é um vetor.
+  /// Apenas uma expressão formada por um LVALUE pode ter um vetor como valor.
+  fn dimensions(&self) -> &[u32] {
+    self.bare_lvalue().map_or(&[], |lvalue| &lvalue.attrs.dimensions)
+  }
+
+  /// LVALUE de uma expressão formada apenas por ele, como em `bare_lvalue`, para a análise semântica do LVALUE.
+  fn bare_lvalue_mut(&mut self) -> Option<&mut SemanticNode> {
+    if let SemanticNodeData::Lvalue { .. } = &self.children {
+      return Some(self);
+    }
+    match &mut self.children {
+      SemanticNodeData::Expression { numexpression, op_expression: None, .. } => numexpression.bare_lvalue_mut(),
+      SemanticNodeData::Numexpression { term, op_numexpression: None, .. } => term.bare_lvalue_mut(),
+      SemanticNodeData::Term { unaryexpression, op_term: None, .. } => unaryexpression.bare_lvalue_mut(),
+      SemanticNodeData::Unaryexpression { op: None, factor } => factor.bare_lvalue_mut(),
+      SemanticNodeData::Factor { expression: Some(expression), .. } => expression.bare_lvalue_mut(),
+      SemanticNodeData::Factor { lvalue: Some(lvalue), .. } => Some(lvalue),
+      _ => None,
+    }
+  }
+
+  /// Um argumento é aceito por um parâmetro com o mesmo número de dimensões e os mesmos tamanhos a partir da segunda.
+  /// A primeira dimensão de um parâmetro vetor aceita vetores de qualquer tamanho
+  fn check_argument(func_id: &str, position: usize, expected: &[u32], found: &[u32], tipo: &VarType, span: &Span) -> Result<(), Box<dyn Error>> {
+    if Self::accepts_dimensions(expected, found) {
+      return Ok(());
+    }
+    if expected.is_empty() {
+      return Err(format!("Erro semântico: vetor passado ao parâmetro {} da função '{}', que não é um vetor, na linha {}, coluna {}", position + 1, func_id, span.start.0, span.start.1).into());
+    }
+    Err(format!("Erro semântico: o argumento {} da função '{}' deve ser um vetor '{}{}' na linha {}, coluna {}", position + 1, func_id, tipo, format_dimensions(expected), span.start.0, span.start.1).into())
+  }
+
+  /// As dimensões de um argumento são aceitas por um parâmetro com o mesmo número de dimensões e os mesmos tamanhos
+  /// após a primeira, que não tem tamanho no parâmetro.
+  fn accepts_dimensions(expected: &[u32], found: &[u32]) -> bool {
+    expected.len() == found.len() && expected.iter().zip(found).skip(1).all(|(expected, found)| expected == found)
+  }
+
+  /// Valores do tipo struct ou de uma função podem ser atribuídos, mas não são operandos de operações aritméticas ou de comparação.
+  fn check_operand(tipo: &VarType, span: &Span) -> Result<(), Box<dyn Error>> {
+    match tipo {
+      VarType::Struct(name) => Err(format!("Erro semântico: valor do tipo struct '{}' usado em uma operação na linha {} coluna {}", name, span.start.0, span.start.1).into()),
+      VarType::Func(..) => Err(format!("Erro semântico: valor do tipo '{}' usado em uma operação na linha {} coluna {}", tipo, span.start.0, span.start.1).into()),
+      _ => Ok(()),
+    }
+  }
+
+  /// Tipo de uma operação entre dois valores: o tipo dos dois, quando iguais, ou float, quando um deles é int
+  /// e o outro float. A conversão do operando int é registrada. None quando os tipos não são compatíveis
+  /// ou quando o operando int é um vetor, que não pode ser convertido.
+  fn promote(left: &mut SemanticNode, tipo1: &VarType, right: &mut SemanticNode, tipo2: &VarType, scopes: &mut ScopeStack) -> Option<VarType> {
+    match (tipo1, tipo2) {
+      _ if tipo1 == tipo2 => Some(tipo1.clone()),
+      (VarType::Int, VarType::Float) | (VarType::Float, VarType::Int) if !left.dimensions().is_empty() || !right.dimensions().is_empty() => None,
+      (VarType::Int, VarType::Float) => {
+        left.widen(scopes);
+        Some(VarType::Float)
+      },
+      (VarType::Float, VarType::Int) => {
+        right.widen(scopes);
+        Some(VarType::Float)
+      },
+      _ => None,
+    }
+  }
+
+  /// Um valor pode ser atribuído a uma variável do mesmo tipo, e um int a uma variável float, com a conversão registrada.
+  /// A conversão de float para int perde a parte fracionária, então precisa ser explícita: `(int) x`.
+  /// Um vetor de int não é convertido.
+  fn assignable(value: &mut SemanticNode, tipo: &VarType, target: &VarType, scopes: &mut ScopeStack) -> bool {
+    if (tipo, target) == (&VarType::Int, &VarType::Float) && value.dimensions().is_empty() {
+      value.widen(scopes);
+      return true;
+    }
+    tipo == target
+  }
+
+  /// Registra a conversão implícita do valor int para float, com um aviso quando ela pode perder precisão:
+  /// um float representa exatamente apenas os inteiros de até 24 bits, então apenas constantes pequenas não geram aviso.
+  fn widen(&mut self, scopes: &mut ScopeStack) {
+    let constants = |name: &str| scopes.get_symbol(name).and_then(|entry| entry.const_value);
+    let exact = matches!(self.const_value(&constants), Ok(ConstType::Int(value)) if value.abs() <= 1 << 24);
+    if !exact {
+      scopes.warnings.push(format!("Aviso: a conversão implícita de int para float na linha {}, coluna {} pode perder precisão", self.span.start.0, self.span.start.1));
+    }
+    self.attrs.conversion = Some(VarType::Float);
+  }
+
+  /// Um único valor int, float, string ou enum pode ser impresso. Structs, funções e vetores com dimensões
+  /// não indexadas não podem.
+  fn printable(&self, tipo: &VarType) -> bool {
+    self.dimensions().is_empty() && matches!(tipo, VarType::Int | VarType::Float | VarType::String | VarType::Enum(_))
+  }
+
+  /// Registra a conversão para string de um valor int, float ou enum, feita com to_string.
+  /// Retorna falso para os valores que não podem ser impressos, e portanto convertidos.
+  fn stringify(&mut self, tipo: &VarType) -> bool {
+    if !self.printable(tipo) {
+      return false;
+    }
+    if *tipo != VarType::String {
+      self.attrs.conversion = Some(VarType::String);
+    }
+    true
+  }
+
+  /// Valor constante convertido para o tipo de destino de uma conversão numérica.
+  fn convert_constant(value: ConstType, target: &VarType) -> ConstType {
+    match (value, target) {
+      (ConstType::Int(value), VarType::Float) => ConstType::Float(value as f64),
+      (ConstType::Float(value), VarType::Int) => ConstType::Int(value as i64),
+      (value, _) => value,
+    }
+  }
+
+  /// Strings só aceitam a concatenação (+) e as comparações, feitas em ordem lexicográfica.
+  fn check_string_operator(op: &SemanticNode, unary: bool) -> Result<(), Box<dyn Error>> {
+    let operator = op.operator();
+    if !unary && matches!(operator, TokenType::OpPlus | TokenType::OpEq | TokenType::OpNe | TokenType::OpLt | TokenType::OpLe | TokenType::OpGt | TokenType::OpGe) {
+      return Ok(());
+    }
+    Err(format!("Erro semântico: operador '{}' usado com strings na linha {} coluna {}", operator, op.span.start.0, op.span.start.1).into())
+  }
+
+  /// Os operandos de uma operação com strings são strings, e não vetores de strings com dimensões não indexadas.
+  fn check_string_operand(dimensions: &[u32], span: &Span) -> Result<(), Box<dyn Error>> {
+    if dimensions.is_empty() {
+      return Ok(());
+    }
+    Err(format!("Erro semântico: vetor 'string{}' usado em uma operação com strings na linha {} coluna {}", format_dimensions(dimensions), span.start.0, span.start.1).into())
+  }
+
+  /// Enumeradores só podem ser comparados. Para usá-los em uma operação aritmética, é preciso convertê-los para int.
+  fn check_arithmetic_operand(tipo: &VarType, span: &Span) -> Result<(), Box<dyn Error>> {
+    Self::check_operand(tipo, span)?;
+    if let VarType::Enum(name) = tipo {
+      return Err(format!("Erro semântico: valor do tipo enum '{}' usado em uma operação aritmética na linha {} coluna {}. Use (int) para convertê-lo", name, span.start.0, span.start.1).into());
+    }
+    Ok(())
+  }
+
+  /// Operador de um nó OP_EXPRESSION, OP_NUMEXPRESSION ou OP_TERM.
+  fn operator(&self) -> TokenType {
     match &self.children {
+      SemanticNodeData::OpExpression { op } | SemanticNodeData::OpNumexpression { op } | SemanticNodeData::OpTerm { op } => *op,
+      SemanticNodeData::Terminal { value } => value.token_type,
+      _ => panic!("Expected operator node"),
+    }
+  }
+
+  /// Valor de uma expressão constante, calculado em tempo de compilação.
+  /// `constants` retorna o valor de uma constante nomeada (`const int N = 10;`) a partir do seu nome.
+  /// A expressão já passou pela verificação de tipos, então os dois operandos de cada operação possuem o mesmo tipo.
+  pub fn const_value(&self, constants: &dyn Fn(&str) -> Option<ConstType>) -> Result<ConstType, Box<dyn Error>> {
+    let (line, column) = self.span.start;
+    let not_constant = || format!("Erro semântico: expressão não constante na linha {}, coluna {}", line, column);
+    let binary = |left: &SemanticNode, op: &Option<Box<SemanticNode>>, right: &Option<Box<SemanticNode>>| -> Result<ConstType, Box<dyn Error>> {
+      let value = left.const_value(constants)?;
+      let (Some(op), Some(right)) = (op, right) else { return Ok(value); };
+      let right = right.const_value(constants)?;
+      // Em uma operação entre int e float, o int é convertido para float
+      let (value, right) = match (value, right) {
+        (ConstType::Int(value), ConstType::Float(right)) => (ConstType::Float(value as f64), ConstType::Float(right)),
+        (ConstType::Float(value), ConstType::Int(right)) => (ConstType::Float(value), ConstType::Float(right as f64)),
+        operands => operands,
+      };
+      Self::fold_constants(op.operator(), value, right)
+        .ok_or_else(|| format!("Erro semântico: não é possível calcular a expressão constante na linha {}, coluna {}", line, column).into())
+    };
+    match &self.children {
+      SemanticNodeData::Constant { value } => Ok(value.clone()),
+      // Apenas constantes nomeadas podem ser usadas em uma expressão constante
+      SemanticNodeData::Lvalue { id, var_index: None, field: None } => {
+        constants(&id.token().value.as_ref().unwrap().to_string()).ok_or_else(|| not_constant().into())
+      },
+      SemanticNodeData::Factor { expression, lvalue, constant, .. } => {
+        match expression.as_deref().or(lvalue.as_deref()).or(constant.as_deref()) {
+          Some(child) => child.const_value(constants),
+          // FACTOR -> FUNCCALL
+          None => Err(not_constant().into()),
+        }
+      },
+      SemanticNodeData::Unaryexpression { op, factor } => {
+        match (op.as_deref().map(SemanticNode::operator), factor.const_value(constants)?) {
+          (Some(TokenType::OpMinus), ConstType::Int(value)) => Ok(ConstType::Int(-value)),
+          (Some(TokenType::OpMinus), ConstType::Float(value)) => Ok(ConstType::Float(-value)),
+          (Some(TokenType::OpMinus), _) => Err(not_constant().into()),
+          (_, value) => Ok(value),
+        }
+      },
+      SemanticNodeData::Term { unaryexpression, op_term, unaryexpression2 } => binary(unaryexpression, op_term, unaryexpression2),
+      SemanticNodeData::Numexpression { term, op_numexpression, term2 } => binary(term, op_numexpression, term2),
+      SemanticNodeData::Expression { numexpression, op_expression, numexpression2 } => binary(numexpression, op_expression, numexpression2),
+      SemanticNodeData::Atribstatevalue { expression: Some(expression), .. } => expression.const_value(constants),
+      // A conversão de um enumerador para int mantém o seu valor, e as conversões numéricas convertem o valor
+      SemanticNodeData::Castexpression { var_type, factor } => {
+        let target = var_type.token().value.as_ref().unwrap().get_keyword_type();
+        Ok(Self::convert_constant(factor.const_value(constants)?, &target))
+      },
+      SemanticNodeData::Condexpression { condition, then_expression, else_expression } => {
+        match condition.const_value(constants)? {
+          ConstType::Int(0) | ConstType::Float(0.0) => else_expression.const_value(constants),
+          _ => then_expression.const_value(constants),
+        }
+      },
+      _ => Err(not_constant().into()),
+    }
+  }
+
+  /// Um switch usa uma tabela de saltos quando possui ao menos 3 casos e a tabela, com uma entrada para cada valor
+  /// entre o menor e o maior caso, tem no máximo o dobro do número de casos.
+  fn is_dense(values: &[i64]) -> bool {
+    let (Some(min), Some(max)) = (values.iter().min(), values.iter().max()) else { return false; };
+    values.len() >= 3 && max.abs_diff(*min) < 2 * values.len() as u64
+  }
+
+  /// Resultado de uma operação entre dois valores constantes.
+  /// Comparações resultam em um int (0 ou 1), como na geração de código. None para divisão por zero, estouro
+  /// e operações que não são calculadas em tempo de compilação.
+  fn fold_constants(op: TokenType, left: ConstType, right: ConstType) -> Option<ConstType> {
+    let compare = |ordering: std::cmp::Ordering| {
+      let result = match op {
+        TokenType::OpEq => ordering.is_eq(),
+        TokenType::OpNe => ordering.is_ne(),
+        TokenType::OpGt => ordering.is_gt(),
+        TokenType::OpGe => ordering.is_ge(),
+        TokenType::OpLt => ordering.is_lt(),
+        TokenType::OpLe => ordering.is_le(),
+        _ => return None,
+      };
+      Some(ConstType::Int(result as i64))
+    };
+    match (left, right) {
+      (ConstType::Int(left), ConstType::Int(right)) => match op {
+        TokenType::OpPlus => left.checked_add(right).map(ConstType::Int),
+        TokenType::OpMinus => left.checked_sub(right).map(ConstType::Int),
+        TokenType::OpMultiply => left.checked_mul(right).map(ConstType::Int),
+        TokenType::OpDivision => left.checked_div(right).map(ConstType::Int),
+        TokenType::OpModular => left.checked_rem(right).map(ConstType::Int),
+        _ => compare(left.cmp(&right)),
+      },
+      (ConstType::Float(left), ConstType::Float(right)) => match op {
+        TokenType::OpPlus => Some(ConstType::Float(left + right)),
+        TokenType::OpMinus => Some(ConstType::Float(left - right)),
+        TokenType::OpMultiply => Some(ConstType::Float(left * right)),
+        TokenType::OpDivision | TokenType::OpModular if right == 0.0 => None,
+        TokenType::OpDivision => Some(ConstType::Float(left / right)),
+        TokenType::OpModular => Some(ConstType::Float(left % right)),
+        _ => compare(left.partial_cmp(&right)?),
+      },
+      (ConstType::String(left), ConstType::String(right)) => compare(left.cmp(&right)),
+      _ => None,
+    }
+  }
+
+  fn semantic_analysis(&mut self, scopes: &mut ScopeStack) -> Result<Option<ReturnSem>, Box<dyn Error>> {
+    match &mut self.children {
       SemanticNodeData::Allocexpression {var_type, dimensions} => {
-        let tipo = var_type.semantic_analysis(scopes)?;
+        // ALLOCEXPRESSION -> kw_new (var_type | id) VAR_INDEX
+        //  ALLOCEXPRESSION.tipo = var_type ou a struct com o nome id
+        let tipo = var_type.declared_type(scopes)?;
         dimensions.semantic_analysis(scopes)?;
-        Ok(tipo)
+        Ok(Some(ReturnSem::Dado { tipo }))
       },
-      SemanticNodeData::Atribstat {lvalue, value} => {
+      SemanticNodeData::Atribstat {lvalue, op, value} => {
         // ATRIBSTAT -> LVALUE ATRIBSTATVALUE
         //  if LVALUE.tipo != ATRIBSTATVALUE.tipo: ERRO 
+        // ATRIBSTAT -> LVALUE op ATRIBSTATVALUE
+        //  LVALUE op ATRIBSTATVALUE tem o tipo de LVALUE, então a verificação é a mesma
 
         // get lvalue id
         // 
-        let SemanticNodeData::Lvalue { id, var_index } = &lvalue.children else { panic!() };
-        let SemanticNodeData::Terminal { value: id_token } = &id.children else { panic!() };
+        let id_token = lvalue.lvalue_id().clone();
         let ConstType::String(id_name) = id_token.value.clone().unwrap() else { panic!() };
 
         // Insert id appearance in the current scope
@@ -48,29 +597,41 @@ impl SemanticNode {
         let Some(symbol_entry) = scopes.get_symbol(&id_name) else {
           return Err(format!("Erro semântico: variável '{}' não declarada no escopo atual na linha {}, coluna {}", id_name, id_token.line, id_token.column).into());
         };
-
-        let Some(ReturnSem::Dado{tipo: value_type, pos: Some((value_line, value_column))}) = value.semantic_analysis(scopes)? else { panic!(); };
-        if value_type != symbol_entry.var_type[0] {
-          return Err(format!("Erro semântico: tipo incompatível na atribuição de '{}' na linha {} coluna {}", id_name, value_line, value_column).into());
+        if symbol_entry.enumerators.is_some() {
+          return Err(format!("Erro semântico: '{}' é um tipo e não pode receber uma atribuição na linha {}, coluna {}", id_name, id_token.line, id_token.column).into());
         }
-        // Check if the variable index is valid
-        // LVALUE -> id VAR_INDEX
-        if let Some(var_index) = var_index {
-          let SemanticNodeData::VarIndex { index } = &var_index.children else { panic!("{:?}", var_index.children) };
-          for child in index.iter() {
-            let tipo = child.semantic_analysis(scopes)?;
-            if let Some(ReturnSem::Dado { tipo, pos: Some(index_pos) }) = tipo {
-              if tipo != VarType::Int {
-                return Err(format!("Erro semântico: índice de variável deve ser do tipo 'int', encontrado '{:?}' na linha {}, coluna {}", tipo, index_pos.0, index_pos.1).into());
-              }
-            } else {
-              panic!(); 
-            }
+        if symbol_entry.const_value.is_some() {
+          return Err(format!("Erro semântico: atribuição à constante '{}' na linha {}, coluna {}", id_name, id_token.line, id_token.column).into());
+        }
+
+        let Some(ReturnSem::Dado{tipo: value_type}) = value.semantic_analysis(scopes)? else { panic!(); };
+        // Check if the variable index and the accessed fields are valid
+        // LVALUE -> id VAR_INDEX [LVALUE]
+        let (lvalue_type, lvalue_dimensions) = lvalue.access_type(symbol_entry.var_type[0].clone(), &symbol_entry.const_index, symbol_entry.allocated, scopes)?;
+        if lvalue.indexes_string() {
+          return Err(format!("Erro semântico: caractere da string '{}' não pode receber uma atribuição na linha {}, coluna {}", id_name, id_token.line, id_token.column).into());
+        }
+        if !Self::assignable(value, &value_type, &lvalue_type, scopes) {
+          // Um float só é atribuído a uma variável int com uma conversão explícita, que descarta a parte fracionária
+          let hint = if (&value_type, &lvalue_type) == (&VarType::Float, &VarType::Int) { ". Use (int) para convertê-lo" } else { "" };
+          return Err(format!("Erro semântico: tipo incompatível na atribuição de '{}' na linha {} coluna {}{}", id_name, value.span.start.0, value.span.start.1, hint).into());
+        }
+        // O operador de uma atribuição composta é verificado como em LVALUE op ATRIBSTATVALUE
+        if let Some(op) = op {
+          Self::check_arithmetic_operand(&lvalue_type, &lvalue.span)?;
+          if lvalue_type == VarType::String {
+            Self::check_string_operator(op, false)?;
+            Self::check_string_operand(&lvalue_dimensions, &lvalue.span)?;
+            Self::check_string_operand(value.dimensions(), &value.span)?;
+            op.attrs.string_operation = true;
           }
         }
+        if let SemanticNodeData::Atribstatevalue { allocexpression: Some(_), .. } = &value.children {
+          scopes.mark_allocated(&id_name);
+        }
         Ok(None)
       },
-      SemanticNodeData::Atribstatevalue {expression, allocexpression, funccall} => {
+      SemanticNodeData::Atribstatevalue {expression, allocexpression} => {
 
         // ATRIBSTATEVALUE -> EXPRESSION
         // ATRIBSTATEVALUE.tipo = EXPRESSION.tipo
@@ -83,22 +644,89 @@ impl SemanticNode {
         if let Some(allocexpression) = allocexpression {
           return Ok(allocexpression.semantic_analysis(scopes)?);
         }
-
-        // ATRIBSTATEVALUE -> FUNCCALL
-        // ATRIBSTATEVALUE.tipo -> FUNCCALL.tipo
-        if let Some(funccall) = funccall {
-          return Ok(funccall.semantic_analysis(scopes)?);
+        Ok(None)
+      },
+      SemanticNodeData::Caselist {cases} => {
+        for case in cases.iter_mut() {
+          case.semantic_analysis(scopes)?;
+        }
+        Ok(None)
+      },
+      SemanticNodeData::Casestat {statelist, ..} => {
+        // O valor do caso é verificado em SWITCHSTAT
+        if let Some(statelist) = statelist {
+          statelist.semantic_analysis(scopes)?;
         }
         Ok(None)
       },
-      SemanticNodeData::Constant {value, line, column} => {
+      SemanticNodeData::Condexpression {condition, then_expression, else_expression} => {
+        // EXPRESSION -> EXPRESSION question EXPRESSION colon EXPRESSION
+        //  EXPRESSION_2.tipo == EXPRESSION_3.tipo
+        //  EXPRESSION.tipo = EXPRESSION_2.tipo
+        condition.semantic_analysis(scopes)?;
+        let ReturnSem::Dado { tipo: tipo1 } = then_expression.semantic_analysis(scopes)?.unwrap() else { panic!(); };
+        let ReturnSem::Dado { tipo: tipo2 } = else_expression.semantic_analysis(scopes)?.unwrap() else { panic!(); };
+        if tipo1 != tipo2 {
+          let else_pos = else_expression.span.start;
+          return Err(format!("Erro semântico: Tipos incompátiveis nos ramos da expressão condicional na linha {} coluna {}", else_pos.0, else_pos.1).into());
+        }
+        Ok(Some(ReturnSem::Dado { tipo: tipo1, }))
+      },
+      SemanticNodeData::Castexpression { var_type, factor } => {
+        // CASTEXPRESSION -> lparenthesis var_type rparenthesis FACTOR
+        //  FACTOR.tipo == var_type, ou FACTOR.tipo é um enum e var_type == int,
+        //  ou uma conversão numérica entre int e float, registrada para a geração de código
+        //  CASTEXPRESSION.tipo = var_type
+        let target = var_type.token().value.as_ref().unwrap().get_keyword_type();
+        let Some(ReturnSem::Dado { tipo }) = factor.semantic_analysis(scopes)? else { panic!(); };
+        // As conversões se aplicam a um único valor, e não a um vetor com dimensões não indexadas
+        let dimensions = factor.dimensions();
+        if !dimensions.is_empty() && tipo != target {
+          return Err(format!("Erro semântico: conversão do vetor '{}{}' para '{}' não permitida na linha {} coluna {}", tipo, format_dimensions(dimensions), target, self.span.start.0, self.span.start.1).into());
+        }
+        match (&tipo, &target) {
+          (VarType::Int, VarType::Float) | (VarType::Float, VarType::Int) => { factor.attrs.conversion = Some(target.clone()); },
+          (VarType::Enum(_), VarType::Int) => {},
+          _ if tipo == target => {},
+          _ => return Err(format!("Erro semântico: conversão de '{}' para '{}' não permitida na linha {} coluna {}", tipo, target, self.span.start.0, self.span.start.1).into()),
+        }
+        Ok(Some(ReturnSem::Dado { tipo: target }))
+      },
+      SemanticNodeData::Constdecl {var_type, id, value} => {
+        // CONSTDECL -> kw_const var_type id op_assign EXPRESSION
+        //  EXPRESSION.tipo == var_type
+        //  CONSTDECL.valor = valor de EXPRESSION, calculado em tempo de compilação
+        let var_type = var_type.token().value.as_ref().unwrap().get_keyword_type();
+        let id_node = id.token();
+        let ConstType::String(id_name) = id_node.value.clone().unwrap() else { panic!() };
+        let Some(ReturnSem::Dado { tipo }) = value.semantic_analysis(scopes)? else { panic!(); };
+        if !Self::assignable(value, &tipo, &var_type, scopes) {
+          return Err(format!("Erro semântico: tipo incompatível na declaração da constante '{}' na linha {} coluna {}", id_name, value.span.start.0, value.span.start.1).into());
+        }
+        let constants = |name: &str| scopes.get_symbol(name).and_then(|entry| entry.const_value);
+        let const_value = Self::convert_constant(value.const_value(&constants)?, &var_type);
+        let entry = SymbolEntry {
+          appearances: vec![(id_node.line, id_node.column)],
+          var_type: vec![var_type],
+          const_index: vec![],
+          return_type: None,
+          const_value: Some(const_value),
+          enumerators: None,
+          param_index: vec![],
+          overloads: vec![],
+          allocated: false,
+        };
+        scopes.insert_symbol(id_name, entry)?;
+        Ok(None)
+      },
+      SemanticNodeData::Constant {value} => {
         // CONSTANT -> const_int
         //  CONSTANT.tipo = "int"
-        return Ok(Some(ReturnSem::Dado{ tipo: value.get_type(), pos: Some((*line, *column)) }));
+        return Ok(Some(ReturnSem::Dado{ tipo: value.get_type() }));
       },
       SemanticNodeData::ConstIndex { index } => {
         // CONSTINDEX -> [CONSTANT1, CONSTANT2, CONSTANT3...]
-        for i in index.iter() {
+        for i in index.iter_mut() {
           i.semantic_analysis(scopes)?;
         }
         Ok(None)
@@ -111,45 +739,160 @@ impl SemanticNode {
         scopes.pop_scope();
         result
       },
-      SemanticNodeData::Expression {numexpression, numexpression2, ..} => {
+      SemanticNodeData::Expression {numexpression, op_expression, numexpression2} => {
         // EXPRESSION.children {
         //   [NUMEXPRESSION] => Ok,
-        //   [NUMEXPRESSION, _, NUMEXPRESSION] => children[0].tipo == children[2].tipo
+        //   [NUMEXPRESSION, _, NUMEXPRESSION] => children[0].tipo == children[2].tipo, ou um int e o outro float
         //   _ => panic!()
         // }
         // EXPRESSION.tipo = children[0].tipo
-        let ReturnSem::Dado { tipo: tipo1, pos: exp1_pos} = numexpression.semantic_analysis(scopes)?.unwrap() else { panic!(); };
+        let ReturnSem::Dado { tipo: tipo1 } = numexpression.semantic_analysis(scopes)?.unwrap() else { panic!(); };
         if let Some(numexpression2) = numexpression2 {
-          let ReturnSem::Dado { tipo: tipo2, pos: exp2_pos} = numexpression2.semantic_analysis(scopes)?.unwrap() else { panic!(); };
-          let exp2_pos = exp2_pos.unwrap_or((0, 0));
-          if tipo1 != tipo2 {
+          let ReturnSem::Dado { tipo: tipo2 } = numexpression2.semantic_analysis(scopes)?.unwrap() else { panic!(); };
+          let exp2_pos = numexpression2.span.start;
+          let Some(tipo1) = Self::promote(numexpression, &tipo1, numexpression2, &tipo2, scopes) else {
             return Err(format!("Erro semântico: Tipos incompátiveis na expressão na linha {} coluna {}", exp2_pos.0, exp2_pos.1).into());
-          } else {
-            // Sempre que uma expressão possui uma operação (de comparação), o valor retornado será uma int
-            // falso: 0
-            // verdadeiro: 1
-            return Ok(Some(ReturnSem::Dado { tipo: VarType::Int, pos: exp1_pos }));
+          };
+          Self::check_operand(&tipo1, &numexpression.span)?;
+          // Strings são comparadas pelo ambiente de execução
+          if tipo1 == VarType::String {
+            Self::check_string_operand(numexpression.dimensions(), &numexpression.span)?;
+            Self::check_string_operand(numexpression2.dimensions(), &numexpression2.span)?;
+            op_expression.as_mut().unwrap().attrs.string_operation = true;
           }
+          // Sempre que uma expressão possui uma operação (de comparação), o valor retornado será uma int
+          // falso: 0
+          // verdadeiro: 1
+          return Ok(Some(ReturnSem::Dado { tipo: VarType::Int, }));
         }
-        Ok(Some(ReturnSem::Dado { tipo: tipo1, pos: exp1_pos }))
+        Ok(Some(ReturnSem::Dado { tipo: tipo1, }))
       },
-      SemanticNodeData::Factor {expression, lvalue, constant} => {
+      SemanticNodeData::Factor {expression, lvalue, constant, funccall} => {
         // FACTOR -> CONSTANT
         //  FACTOR.tipo = CONSTANT.tipo
 
         // FACTOR -> LVALUE
         //  FACTOR.tipo = LVALUE.tipo
 
+        // FACTOR -> FUNCCALL
+        //  FACTOR.tipo = FUNCCALL.tipo
+
         // FACTOR -> lparenthesis NUMEXPRESSION rparenthesis
         //  FACTOR.tipo = NUMEXPRESSION.tipo
         if let Some(expression) = expression { return expression.semantic_analysis(scopes); }
         if let Some(lvalue) = lvalue { return lvalue.semantic_analysis(scopes); }
         if let Some(constant) = constant { return constant.semantic_analysis(scopes); }
+        if let Some(funccall) = funccall {
+          let tipo = funccall.semantic_analysis(scopes)?;
+          if let Some(ReturnSem::Dado { tipo: VarType::Void }) = tipo {
+            return Err(format!("Erro semântico: função do tipo 'void' usada como valor na linha {}, coluna {}", funccall.span.start.0, funccall.span.start.1).into());
+          }
+          return Ok(tipo);
+        }
         panic!();
       },
-      SemanticNodeData::Forstat {init, condition, increment, body} => {
-        // FORSTAT -> kw_for lparenthesis ATRIBSTAT semicolon EXPRESSION semicolon ATRIBSTAT rparenthesis lbrace STATELIST rbrace
+      SemanticNodeData::Dowhilestat {label, body, condition} => {
+        // DOWHILESTAT -> kw_do lbrace STATELIST rbrace kw_while lparenthesis EXPRESSION rparenthesis
         //  STATELIST.scopes.push(ScopeType::Loop)
+        scopes.push_loop(label.as_deref().map(SemanticNode::token))?;
+        scopes.push_scope(ScopeType::Loop);
+        body.semantic_analysis(scopes)?;
+        scopes.pop_scope();
+        scopes.pop_loop();
+        // A condição é avaliada fora do corpo, então não enxerga as variáveis declaradas nele
+        condition.semantic_analysis(scopes)?;
+        Ok(None)
+      },
+      SemanticNodeData::Enumdef { id, enumlist } => {
+        // ENUMDEF -> kw_enum id lbrace ENUMLIST rbrace
+        //  O enum e os seus enumeradores são inseridos no escopo atual
+        //  Cada enumerador é uma constante do tipo do enum, cujo valor é a sua posição em ENUMLIST
+        let id_node = id.token();
+        let enum_name = id_node.value.as_ref().unwrap().to_string();
+        if scopes.types.get_struct(&enum_name).is_some() {
+          return Err(format!("Erro semântico: Redefinição do tipo '{}' na linha {}, coluna {}", enum_name, id_node.line, id_node.column).into());
+        }
+        let SemanticNodeData::Enumlist { enumerators } = &mut enumlist.children else { panic!() };
+        let names = enumerators.iter().map(|enumerator| enumerator.token().value.as_ref().unwrap().to_string()).collect();
+        let entry = SymbolEntry {
+          appearances: vec![(id_node.line, id_node.column)],
+          var_type: vec![VarType::Enum(enum_name.clone())],
+          const_index: vec![],
+          return_type: None,
+          const_value: None,
+          enumerators: Some(names),
+          param_index: vec![],
+          overloads: vec![],
+          allocated: false,
+        };
+        scopes.insert_symbol(enum_name.clone(), entry)?;
+        for (value, enumerator) in enumerators.iter().enumerate() {
+          let token = enumerator.token();
+          let entry = SymbolEntry {
+            appearances: vec![(token.line, token.column)],
+            var_type: vec![VarType::Enum(enum_name.clone())],
+            const_index: vec![],
+            return_type: None,
+            const_value: Some(ConstType::Int(value as i64)),
+            enumerators: None,
+            param_index: vec![],
+            overloads: vec![],
+            allocated: false,
+          };
+          scopes.insert_symbol(token.value.as_ref().unwrap().to_string(), entry)?;
+        }
+        Ok(None)
+      },
+      SemanticNodeData::Enumlist {..} => {
+        // Os enumeradores são inseridos em ENUMDEF
+        panic!();
+      },
+      SemanticNodeData::Initlist {..} => {
+        // Os valores iniciais são verificados em VARDECL
+        panic!();
+      },
+      SemanticNodeData::ParamIndex {..} => {
+        // As dimensões dos parâmetros são verificadas em FUNCDEF
+        panic!();
+      },
+      SemanticNodeData::Signature {..} => {
+        // O tipo descrito é lido pela declaração (ver declared_type)
+        panic!();
+      },
+      SemanticNodeData::Fieldlist {..} => {
+        // Os campos são verificados em STRUCTDEF
+        panic!();
+      },
+      SemanticNodeData::Forinit {var_type, id, value} => {
+        // FORINIT -> var_type id op_assign EXPRESSION
+        //  EXPRESSION.tipo == var_type
+        //  id é inserido no escopo LoopInit, aberto por FORSTAT, e não é visível após o laço
+        let var_type = var_type.token().value.as_ref().unwrap().get_keyword_type();
+        let id_node = id.token();
+        let ConstType::String(id_name) = id_node.value.clone().unwrap() else { panic!() };
+        // O valor inicial é verificado antes da declaração, portanto não pode usar a própria variável
+        let Some(ReturnSem::Dado { tipo }) = value.semantic_analysis(scopes)? else { panic!(); };
+        if !Self::assignable(value, &tipo, &var_type, scopes) {
+          return Err(format!("Erro semântico: tipo incompatível na atribuição de '{}' na linha {} coluna {}", id_name, value.span.start.0, value.span.start.1).into());
+        }
+        let entry = SymbolEntry {
+          appearances: vec![(id_node.line, id_node.column)],
+          var_type: vec![var_type],
+          const_index: vec![],
+          return_type: None,
+          const_value: None,
+          enumerators: None,
+          param_index: vec![],
+          overloads: vec![],
+          allocated: false,
+        };
+        scopes.insert_symbol(id_name, entry)?;
+        Ok(None)
+      },
+      SemanticNodeData::Forstat {label, init, condition, increment, body} => {
+        // FORSTAT -> kw_for lparenthesis FORINIT semicolon EXPRESSION semicolon ATRIBSTAT rparenthesis lbrace STATELIST rbrace
+        //  STATELIST.scopes.push(ScopeType::Loop)
+        scopes.push_loop(label.as_deref().map(SemanticNode::token))?;
         // Escopo das operações do laço (atribstat, expression, atribstat)
         scopes.push_scope(ScopeType::LoopInit);
         init.semantic_analysis(scopes)?;
@@ -161,68 +904,183 @@ impl SemanticNode {
         body.semantic_analysis(scopes)?;
         scopes.pop_scope(); // Corpo
         scopes.pop_scope(); // Operações do laço
+        scopes.pop_loop();
         Ok(None)
       },
       SemanticNodeData::Funccall {id, paramlistcall} => {
         // FUNCCALL -> id
         // FUNCCALL -> id PARAMLISTCALL
-        let SemanticNodeData::Terminal { value } = &id.children else { panic!() };
-        let (ConstType::String(func_id), func_line, func_col) = (value.value.clone().unwrap(), value.line, value.column) else { panic!() };
-        let Some(func_types) = scopes.get_symbol(&func_id) else { return Err("Erro Semântico: função não definida nesse escopo".into()); };
-        
+        //  As definições candidatas são (tipos dos parâmetros, dimensões dos parâmetros, tipo de retorno)
+        let (func_id, func_line, func_col, candidates) = match &mut id.children {
+          SemanticNodeData::Terminal { value } => {
+            let (ConstType::String(func_id), func_line, func_col) = (value.value.clone().unwrap(), value.line, value.column) else { panic!() };
+            let Some(entry) = scopes.get_symbol(&func_id) else {
+              return Err(format!("Erro Semântico: função '{}' não definida nesse escopo na linha {}, coluna {}", func_id, func_line, func_col).into());
+            };
+            // Funções sem tipo declarado retornam int
+            let candidates: Vec<(Vec<VarType>, Vec<Vec<u32>>, VarType)> = entry.definitions()
+              .map(|definition| (definition.var_type.clone(), definition.param_index.clone(), definition.return_type.clone().unwrap_or(VarType::Int)))
+              .collect();
+            (func_id, func_line, func_col, candidates)
+          },
+          // FUNCCALL -> LVALUE PARAMLISTCALL
+          //  Chamada indireta: LVALUE.tipo é o tipo de uma função, que não possui parâmetros vetores
+          _ => {
+            let SemanticNodeData::Lvalue { id: lvalue_id, .. } = &mut id.children else { panic!() };
+            let func_id = lvalue_id.token().value.as_ref().unwrap().to_string();
+            let (func_line, func_col) = id.span.start;
+            let (tipo, dimensions) = id.lvalue_type(scopes)?;
+            let VarType::Func(func_types, return_type) = tipo else {
+              return Err(format!("Erro semântico: '{}' não é uma função na linha {}, coluna {}", func_id, func_line, func_col).into());
+            };
+            if !dimensions.is_empty() {
+              return Err(format!("Erro semântico: vetor '{}' chamado como uma função na linha {}, coluna {}", func_id, func_line, func_col).into());
+            }
+            let param_index = vec![vec![]; func_types.len()];
+            (func_id, func_line, func_col, vec![(func_types, param_index, *return_type)])
+          },
+        };
         let mut called_types: Vec<VarType> = vec![];
+        let mut called_index: Vec<Vec<u32>> = vec![];
         // verificar tipo (id PARAMLISTCALL)
-        match paramlistcall {
-          None => {},
-          Some(paramlistcall) => {
-            let SemanticNodeData::Paramlistcall { paramlist } = &paramlistcall.children else { panic!(); };
-            for param in paramlist.iter() {
-              let SemanticNodeData::Terminal { value } = &param.children else { panic!(); };
-              let ConstType::String(param_value) = value.value.clone().unwrap() else { panic!(); };
-              let id_type = scopes.get_symbol(&param_value);
-              match id_type {
-                Some(symbol_entry) => {
-                  let var_type = symbol_entry.var_type.clone();
-                  if var_type.len() > 1 {
-                    // Esse erro é tratado na análise sintática
-                    return Err(format!("Erro semântico: função '{}' não pode ser passada como parâmetro de outra função", param_value).into());
-                  }
-                  called_types.push(var_type.first().unwrap().clone());
-                },
-                None => return Err(format!("Erro semântico: variável '{}' não definida no escopo atual na linha {}, coluna {}", param_value, value.line, value.column).into()),
+        // Cada argumento é uma expressão, que é analisada (e tem suas variáveis contadas) normalmente
+        // Um argumento formado apenas por um LVALUE pode ser um vetor, cujas dimensões não indexadas
+        // devem ser compatíveis com as do parâmetro
+        if let Some(paramlistcall) = paramlistcall {
+          let SemanticNodeData::Paramlistcall { paramlist } = &mut paramlistcall.children else { panic!(); };
+          for (position, param) in paramlist.iter_mut().enumerate() {
+            let (tipo, dimensions) = match param.bare_lvalue_mut() {
+              Some(lvalue) => lvalue.lvalue_type(scopes)?,
+              None => {
+                let Some(ReturnSem::Dado { tipo }) = param.semantic_analysis(scopes)? else { panic!(); };
+                (tipo, vec![])
+              },
+            };
+            // Uma função sem sobrecargas aponta o argumento incompatível
+            if let [(_, param_index, _)] = candidates.as_slice() {
+              if let Some(expected) = param_index.get(position) {
+                Self::check_argument(&func_id, position, expected, &dimensions, &tipo, &param.span)?;
               }
-              // Count the appearance of the parameter
-              scopes.count_appearance(&param_value, value.line, value.column)?;
             }
-
+            called_types.push(tipo);
+            called_index.push(dimensions);
           }
         }
 
         // Check if called_types matches func_types
-        if func_types.var_type != called_types {
-          return Err(format!("Erro semântico: tipos de parâmetros incompatíveis na chamada da função '{}' na linha {}, coluna {}", func_id, func_line, func_col).into());
+        //  Uma função sobrecarregada é resolvida pela definição cujos tipos e dimensões dos parâmetros são iguais aos dos argumentos
+        let overload = match candidates.as_slice() {
+          [(func_types, ..)] if *func_types == called_types => 0,
+          [_] => return Err(format!("Erro semântico: tipos de parâmetros incompatíveis na chamada da função '{}' na linha {}, coluna {}", func_id, func_line, func_col).into()),
+          _ => {
+            let matching: Vec<usize> = candidates.iter().enumerate()
+              .filter(|(_, (func_types, param_index, _))| *func_types == called_types && param_index.iter().zip(&called_index).all(|(expected, found)| Self::accepts_dimensions(expected, found)))
+              .map(|(overload, _)| overload)
+              .collect();
+            let arguments = format_signature("", &called_types, &called_index);
+            let definitions: Vec<String> = candidates.iter().map(|(func_types, param_index, _)| format_signature(&func_id, func_types, param_index)).collect();
+            match matching.as_slice() {
+              [overload] => *overload,
+              [] => return Err(format!("Erro semântico: nenhuma definição da função '{}' aceita os argumentos {} na linha {}, coluna {}. Definições: {}", func_id, arguments, func_line, func_col, definitions.join(", ")).into()),
+              _ => {
+                let matching: Vec<&str> = matching.iter().map(|overload| definitions[*overload].as_str()).collect();
+                return Err(format!("Erro semântico: chamada ambígua da função '{}' com os argumentos {} na linha {}, coluna {}. Definições possíveis: {}", func_id, arguments, func_line, func_col, matching.join(", ")).into());
+              },
+            }
+          },
+        };
+        // Count the appearance of the function (the id of an indirect call was counted by LVALUE)
+        //  A aparição é contada na definição escolhida, que dá o nome da função no código gerado
+        if let SemanticNodeData::Terminal { .. } = &id.children {
+          scopes.count_overload_appearance(&func_id, overload, func_line, func_col);
+          id.attrs.overload = Some(overload);
+          // Uma função pré-definida tem código próprio, em vez de uma chamada
+          id.attrs.builtin = scopes.is_builtin(&func_id);
+        }
+        // FUNCCALL.tipo = FUNCDEF.tipo_retorno
+        Ok(Some(ReturnSem::Dado { tipo: candidates[overload].2.clone() }))
+      },
+      SemanticNodeData::Funcref { id } => {
+        // FACTOR -> func_id
+        //  func_id é uma função já declarada, sem sobrecargas e sem parâmetros vetores
+        //  FACTOR.tipo = func(FUNCDEF.tipos) -> FUNCDEF.tipo_retorno
+        let token = id.token();
+        let func_id = token.value.as_ref().unwrap().to_string();
+        let Some(entry) = scopes.get_symbol(&func_id) else {
+          return Err(format!("Erro Semântico: função '{}' não definida nesse escopo na linha {}, coluna {}", func_id, token.line, token.column).into());
+        };
+        if scopes.is_builtin(&func_id) {
+          return Err(format!("Erro semântico: a função pré-definida '{}' não pode ser usada como valor na linha {}, coluna {}", func_id, token.line, token.column).into());
+        }
+        if !entry.overloads.is_empty() {
+          let definitions: Vec<String> = entry.definitions().map(|definition| definition.signature(&func_id)).collect();
+          return Err(format!("Erro semântico: a função sobrecarregada '{}' não pode ser usada como valor na linha {}, coluna {}. Definições: {}", func_id, token.line, token.column, definitions.join(", ")).into());
+        }
+        if entry.param_index.iter().any(|dimensions| !dimensions.is_empty()) {
+          return Err(format!("Erro semântico: a função '{}' possui parâmetros vetores e não pode ser usada como valor na linha {}, coluna {}", func_id, token.line, token.column).into());
+        }
+        scopes.count_appearance(&func_id, token.line, token.column)?;
+        let return_type = entry.return_type.unwrap_or(VarType::Int);
+        Ok(Some(ReturnSem::Dado { tipo: VarType::Func(entry.var_type, Box::new(return_type)) }))
+      },
+      SemanticNodeData::Interpolation { segments } => {
+        // INTERPOLATION -> string_start EXPRESSION (string_middle EXPRESSION)* string_end
+        //  Cada EXPRESSION é um único valor, de tipo int, float, string ou um enum. Os valores que não são strings
+        //  são convertidos com to_string, e a conversão é registrada para a geração de código
+        //  INTERPOLATION.tipo = string
+        for expression in segments.iter_mut().skip(1).step_by(2) {
+          let Some(ReturnSem::Dado { tipo }) = expression.semantic_analysis(scopes)? else { panic!(); };
+          if !expression.stringify(&tipo) {
+            return Err(format!("Erro semântico: valor do tipo '{}{}' não pode ser embutido em uma string na linha {} coluna {}", tipo, format_dimensions(expression.dimensions()), expression.span.start.0, expression.span.start.1).into());
+          }
         }
-        // Count the appearance of the function
-        scopes.count_appearance(&func_id, value.line, value.column)?;
-        Ok(Some(ReturnSem::Dado { tipo: VarType::Int, pos: Some((func_line, func_col)) }))  // Assuming all function calls return an int
+        Ok(Some(ReturnSem::Dado { tipo: VarType::String }))
       },
-      SemanticNodeData::Funcdef {func_id, paramlist, statelist} => {
+      SemanticNodeData::Funcdef {return_type, func_id: func_node, paramlist, statelist} => {
         // Get function name
         // PARAMLIST.inh = func_id
-        let SemanticNodeData::Terminal { value } = &func_id.children else { panic!() };
+        let SemanticNodeData::Terminal { value } = &func_node.children else { panic!() };
         let ConstType::String(func_id) = value.value.clone().unwrap() else { panic!(); };
+
+        // FUNCTYPE -> var_type | kw_void
+        //  FUNCDEF.tipo_retorno = FUNCTYPE.tipo
+        // FUNCTYPE -> ''
+        //  FUNCDEF.tipo_retorno = None (a função retorna int, como na gramática original)
+        let return_type = return_type.as_ref().map(|return_type| {
+          let SemanticNodeData::Terminal { value: token } = &return_type.children else { panic!(); };
+          match token.token_type {
+            TokenType::KwVoid => VarType::Void,
+            _ => token.value.as_ref().unwrap().get_keyword_type(),
+          }
+        });
         
         // Read function parameters
         // PARAMLIST
         let mut func_params_types: Vec<VarType> = vec![];
-        let mut func_params: Vec<(VarType, String, (usize, usize))> = vec![];
+        let mut func_params: Vec<(String, SymbolEntry)> = vec![];
         let mut prev_param = None;
-        // PARAMLIST -> (vartype id)+
+        // PARAMLIST -> (TYPE id [PARAMINDEX])+
         //   PARAMLIST.tipos = [vartype1, id1, vartype2, id2 ...]
-        if let Some(paramlist) = &paramlist {
-          let SemanticNodeData::Paramlist { paramlist } = &paramlist.children else { panic!(); };
-          for child in paramlist.iter() {
-            let SemanticNodeData::Terminal { value: token } = &child.children else { panic!(); };
+        if let Some(paramlist) = paramlist {
+          let SemanticNodeData::Paramlist { paramlist } = &mut paramlist.children else { panic!(); };
+          for child in paramlist.iter_mut() {
+            // PARAMINDEX -> lbracket rbracket CONSTINDEX
+            //   O parâmetro anterior é um vetor. A primeira dimensão, sem tamanho, é guardada como 0
+            if let SemanticNodeData::ParamIndex { const_index } = &mut child.children {
+              let name = func_params.last().unwrap().0.clone();
+              let mut dimensions = vec![0];
+              dimensions.extend(Self::array_dimensions(const_index, &name, scopes)?);
+              func_params.last_mut().unwrap().1.const_index = dimensions;
+              continue;
+            }
+            // SIGNATURE id: parâmetro do tipo de uma função
+            if let SemanticNodeData::Signature { .. } = &child.children {
+              let var_type = child.signature_type();
+              func_params_types.push(var_type.clone());
+              prev_param = Some(var_type);
+              continue;
+            }
+            let SemanticNodeData::Terminal { value: token } = &mut child.children else { panic!(); };
             match token.token_type {
               TokenType::VarType => {
                 // Get the type of the parameter
@@ -233,7 +1091,17 @@ impl SemanticNode {
               TokenType::Id => {
                 // Get the name of the parameter
                 let ConstType::String(func_name) = token.clone().value.unwrap().clone() else { panic!(); };
-                func_params.push((prev_param.clone().unwrap(), func_name, (token.line, token.column)));
+                func_params.push((func_name, SymbolEntry {
+                  appearances: vec![(token.line, token.column)],
+                  var_type: vec![prev_param.clone().unwrap()],
+                  const_index: vec![],
+                  return_type: None,
+                  const_value: None,
+                  enumerators: None,
+                  param_index: vec![],
+                  overloads: vec![],
+                  allocated: false,
+                }));
               },
               _ => panic!(),
             }
@@ -249,27 +1117,42 @@ impl SemanticNode {
           appearances: vec![(value.line, value.column)],
           var_type: func_params_types,
           const_index: vec![],
+          return_type: return_type.clone(),
+          const_value: None,
+          enumerators: None,
+          param_index: func_params.iter().map(|(_, entry)| entry.const_index.clone()).collect(),
+          overloads: vec![],
+          allocated: false,
         };
-        scopes.insert_symbol(func_id.clone(), entry)?;
+        // Uma definição com o nome de uma função já definida é uma sobrecarga,
+        // e a primeira definição com o nome de uma função pré-definida a esconde
+        if scopes.is_builtin(&func_id) {
+          scopes.warnings.push(format!("Aviso: a função '{}' na linha {}, coluna {} esconde a função pré-definida de mesmo nome", func_id, value.line, value.column));
+        }
+        scopes.insert_function(func_id.clone(), entry)?;
+        // A definição é a última inserida entre as definições da função
+        func_node.attrs.overload = scopes.get_symbol(&func_id).map(|entry| entry.overloads.len());
         // Push a new scope for the function body
         // And insert the function parameters into the scope
         scopes.push_scope(ScopeType::Function);
-        for (param_type, param_name, pos) in func_params {
-          let entry = SymbolEntry {
-            appearances: vec![pos],
-            var_type: vec![param_type],
-            const_index: vec![],
-          };
+        for (param_name, entry) in func_params {
           scopes.insert_symbol(param_name, entry)?;
         }
 
         // Analyze the function body
+        scopes.return_type = return_type;
         statelist.semantic_analysis(scopes)?;
+        scopes.return_type = None;
         scopes.pop_scope(); // Pop the function scope
         Ok(None)
       },
       SemanticNodeData::Funclist {funclist} => {
-        for func in funclist.iter() {
+        // FUNCLIST -> (FUNCDEF | VARDECL)*
+        //  As variáveis globais são inseridas no escopo mais externo, visível em todas as funções definidas depois delas
+        for func in funclist.iter_mut() {
+          if let SemanticNodeData::Statement { vardecl: None, .. } = &func.children {
+            return Err(format!("Erro semântico: comando fora de uma função na linha {}, coluna {}. Fora das funções, o programa pode ter apenas declarações de variáveis globais ou um único comando", func.span.start.0, func.span.start.1).into());
+          }
           func.semantic_analysis(scopes)?;
         }
         Ok(None)
@@ -286,25 +1169,38 @@ impl SemanticNode {
         // STATELIST.scopes.push(ScopeType::Any)
         Ok(None)
       },
-      SemanticNodeData::Lvalue {id, var_index} => {
-        let tipo = id.semantic_analysis(scopes)?.unwrap();
-        if let Some(var_index) = var_index {
-          var_index.semantic_analysis(scopes)?;
-        }
+      // JUMPSTAT -> kw_break [id] | kw_continue [id]
+      //  O laço de destino é verificado em STATEMENT
+      SemanticNodeData::Jumpstat {..} => Ok(None),
+      SemanticNodeData::Lvalue {..} => {
         // LVALUE -> id VAR_INDEX
         //  LVALUE.tipo = LVALUE.scopes.get(id)
-        return Ok(Some(tipo));
+        // LVALUE -> id VAR_INDEX LVALUE
+        //  LVALUE.tipo = tipo do campo acessado
+        let (tipo, dimensions) = self.lvalue_type(scopes)?;
+        self.attrs.dimensions = dimensions;
+        return Ok(Some(ReturnSem::Dado { tipo }));
       },
       SemanticNodeData::Numexpression {term, op_numexpression, term2} => {
-        let ReturnSem::Dado { tipo: tipo1, pos: exp1_pos } = term.semantic_analysis(scopes)?.unwrap() else { panic!(); };
+        let ReturnSem::Dado { tipo: mut tipo1 } = term.semantic_analysis(scopes)?.unwrap() else { panic!(); };
         if let Some(op_numexpression) = op_numexpression {
           op_numexpression.semantic_analysis(scopes)?;
         }
         if let Some(term2) = term2 {
-          let ReturnSem::Dado { tipo: tipo2, pos: exp2_pos } = term2.semantic_analysis(scopes)?.unwrap() else { panic!(); };
-          let exp2_pos = exp2_pos.unwrap_or((0, 0));
-          if tipo1 != tipo2 {
+          let ReturnSem::Dado { tipo: tipo2 } = term2.semantic_analysis(scopes)?.unwrap() else { panic!(); };
+          let exp2_pos = term2.span.start;
+          let Some(tipo) = Self::promote(term, &tipo1, term2, &tipo2, scopes) else {
             return Err(format!("Erro semântico: tipos incompatíveis na expressão numérica na linha {} coluna {}", exp2_pos.0, exp2_pos.1).into());
+          };
+          tipo1 = tipo;
+          Self::check_arithmetic_operand(&tipo1, &term.span)?;
+          // Strings são concatenadas com + pelo ambiente de execução
+          if tipo1 == VarType::String {
+            let op = op_numexpression.as_mut().unwrap();
+            Self::check_string_operator(op, false)?;
+            Self::check_string_operand(term.dimensions(), &term.span)?;
+            Self::check_string_operand(term2.dimensions(), &term2.span)?;
+            op.attrs.string_operation = true;
           }
         }
         // NUMEXPRESSION.children { 
@@ -313,7 +1209,7 @@ impl SemanticNode {
         //   _ => panic!()
         // }
         // NUMEXPRESSION.tipo = children[0].tipo
-        return Ok(Some(ReturnSem::Dado { tipo: tipo1, pos: exp1_pos }));
+        return Ok(Some(ReturnSem::Dado { tipo: tipo1, }));
       },
       SemanticNodeData::OpExpression {op} => {
         Ok(Some(ReturnSem::TT(op.clone())))
@@ -328,13 +1224,26 @@ impl SemanticNode {
         panic!();
       },
       SemanticNodeData::Paramlistcall { paramlist } => {
-        for i in paramlist.iter() {
+        for i in paramlist.iter_mut() {
           i.semantic_analysis(scopes)?;
         }
         Ok(None)
       },
-      SemanticNodeData::Printstat { expression } => {
-        expression.semantic_analysis(scopes)
+      SemanticNodeData::Printstat { expressions } => {
+        // PRINTSTAT -> kw_print EXPRESSION
+        //  EXPRESSION.tipo é int, float, string ou um enum, e o valor é impresso como está
+        // PRINTSTAT -> kw_print EXPRESSION (comma EXPRESSION)+
+        //  Cada EXPRESSION.tipo é int, float, string ou um enum. Os valores são convertidos para string
+        //  e impressos em uma única linha, separados pelo separador de impressão
+        let single = expressions.len() == 1;
+        for expression in expressions.iter_mut() {
+          let Some(ReturnSem::Dado { tipo }) = expression.semantic_analysis(scopes)? else { panic!(); };
+          let printable = if single { expression.printable(&tipo) } else { expression.stringify(&tipo) };
+          if !printable {
+            return Err(format!("Erro semântico: valor do tipo '{}{}' não pode ser impresso na linha {} coluna {}", tipo, format_dimensions(expression.dimensions()), expression.span.start.0, expression.span.start.1).into());
+          }
+        }
+        Ok(None)
       },
       SemanticNodeData::Program { funclist, statement } => {
         if let Some(funclist) = funclist { return funclist.semantic_analysis(scopes); }
@@ -343,8 +1252,7 @@ impl SemanticNode {
       },
       SemanticNodeData::Readstat { lvalue } => {
         // get value of lvalue
-        let SemanticNodeData::Lvalue { id, .. } = &lvalue.children else { panic!() };
-        let SemanticNodeData::Terminal { value: id_token } = &id.children else { panic!() };
+        let id_token = lvalue.lvalue_id().clone();
         let ConstType::String(id_name) = id_token.value.clone().unwrap() else { panic!(); };
         // Count the appearance of the variable
         scopes.count_appearance(&id_name, id_token.line, id_token.column)?;
@@ -352,25 +1260,72 @@ impl SemanticNode {
         let Some(symbol_entry) = scopes.get_symbol(&id_name) else {
           return Err(format!("Erro semântico: variável '{}' não declarada no escopo atual na linha {} coluna {}", id_name, id_token.line, id_token.column).into());
         };
-        if symbol_entry.var_type[0] != VarType::String {
-          return Err(format!("Erro semântico: comando Read deve atribuir valor a uma variável de tipo string, mas tipo {:?} foi encontrado na linha {} coluna {}", symbol_entry.var_type[0], id_token.line, id_token.column).into());
+        if symbol_entry.enumerators.is_some() {
+          return Err(format!("Erro semântico: '{}' é um tipo e não pode receber uma atribuição na linha {}, coluna {}", id_name, id_token.line, id_token.column).into());
         }
+        if symbol_entry.const_value.is_some() {
+          return Err(format!("Erro semântico: atribuição à constante '{}' na linha {}, coluna {}", id_name, id_token.line, id_token.column).into());
+        }
+        // READSTAT -> kw_read LVALUE
+        //  LVALUE.tipo é int, float ou string, e os índices de LVALUE acessam um único elemento.
+        //  O texto lido é convertido para LVALUE.tipo, que é registrado para a geração de código
+        let (tipo, dimensions) = lvalue.access_type(symbol_entry.var_type[0].clone(), &symbol_entry.const_index, symbol_entry.allocated, scopes)?;
+        if lvalue.indexes_string() {
+          return Err(format!("Erro semântico: caractere da string '{}' não pode receber uma atribuição na linha {}, coluna {}", id_name, id_token.line, id_token.column).into());
+        }
+        if !dimensions.is_empty() {
+          return Err(format!("Erro semântico: comando read deve atribuir valor a um único elemento do vetor '{}' na linha {} coluna {}", id_name, id_token.line, id_token.column).into());
+        }
+        if !matches!(tipo, VarType::Int | VarType::Float | VarType::String) {
+          return Err(format!("Erro semântico: comando read deve atribuir valor a uma variável do tipo int, float ou string, mas tipo '{}' foi encontrado na linha {} coluna {}", tipo, id_token.line, id_token.column).into());
+        }
+        lvalue.attrs.conversion = Some(tipo);
         Ok(None)
       },
-      SemanticNodeData::Returnstat { .. } => {
-        Ok(None)
+      SemanticNodeData::Returnstat { token, expression } => {
+        // RETURNSTAT -> kw_return EXPRESSION
+        //  EXPRESSION.tipo == FUNCDEF.tipo_retorno, ou um int retornado por uma função float,
+        //  convertido como em uma atribuição
+        // RETURNSTAT -> kw_return
+        //  FUNCDEF.tipo_retorno == void
+        // Funções sem tipo declarado aceitam as duas formas, retornando int
+        let tipo = match expression {
+          Some(expression) => {
+            let Some(ReturnSem::Dado { tipo }) = expression.semantic_analysis(scopes)? else { panic!(); };
+            Some(tipo)
+          },
+          None => None,
+        };
+        match (scopes.return_type.clone(), tipo) {
+          (Some(VarType::Void), None) | (None, None) => Ok(None),
+          (Some(VarType::Void), Some(_)) => {
+            Err(format!("Erro semântico: função do tipo 'void' não pode retornar um valor na linha {} coluna {}", token.line, token.column).into())
+          },
+          (Some(expected), None) => {
+            Err(format!("Erro semântico: comando 'return' sem valor em função do tipo '{}' na linha {} coluna {}", expected, token.line, token.column).into())
+          },
+          (expected, Some(found)) => {
+            let expected = expected.unwrap_or(VarType::Int);
+            if !Self::assignable(expression.as_mut().unwrap(), &found, &expected, scopes) {
+              return Err(format!("Erro semântico: tipo de retorno incompatível, esperado '{}' e encontrado '{}' na linha {} coluna {}", expected, found, token.line, token.column).into());
+            }
+            Ok(None)
+          },
+        }
       },
       SemanticNodeData::Statelist { statelist } => {
-        for statement in statelist.iter() {
+        for statement in statelist.iter_mut() {
           statement.semantic_analysis(scopes)?;
         }
         Ok(None)
       },
-      SemanticNodeData::Statement { vardecl,  atribstat, ifstat, forstat, statelist, commandstat } => {
+      SemanticNodeData::Statement { vardecl,  atribstat, ifstat, forstat, whilestat, dowhilestat, statelist, commandstat } => {
         if let Some(vardecl) = vardecl { return vardecl.semantic_analysis(scopes); }
         if let Some(atribstat) = atribstat { return atribstat.semantic_analysis(scopes); }
         if let Some(ifstat) = ifstat { return ifstat.semantic_analysis(scopes); }
         if let Some(forstat) = forstat { return forstat.semantic_analysis(scopes); }
+        if let Some(whilestat) = whilestat { return whilestat.semantic_analysis(scopes); }
+        if let Some(dowhilestat) = dowhilestat { return dowhilestat.semantic_analysis(scopes); }
         if let Some(statelist) = statelist {
           scopes.push_scope(ScopeType::Any);
           statelist.semantic_analysis(scopes)?;
@@ -378,16 +1333,25 @@ impl SemanticNode {
           return Ok(None);
         }
         if let Some(commandstat) = commandstat {
-          match &commandstat.children {
-            SemanticNodeData::Returnstat { token } => {
-              if !scopes.contains(ScopeType::Function) { return Err(format!("Erro semântico: Comando 'return' fora de um laço de repetição na linha {} coluna {}", token.line, token.column).into()); }
+          match &mut commandstat.children {
+            SemanticNodeData::Returnstat { token, .. } => {
+              if !scopes.contains(ScopeType::Function) { return Err(format!("Erro semântico: Comando 'return' fora de uma função na linha {} coluna {}", token.line, token.column).into()); }
             },
-            // STATEMENT -> kw_break semicolon
-            //  if !STATEMENT.scopes.contains(ScopeType::Loop) { ERRO("Break keyword usada fora de um laço de repetição"); }
-            SemanticNodeData::Terminal { value } => {
-              if value.token_type == TokenType::KwBreak {
-                if !scopes.contains(ScopeType::Loop) {
-                  return Err(format!("Erro semântico: Comando 'break' fora de um laço de repetição na linha {} coluna {}", value.line, value.column).into());
+            // STATEMENT -> JUMPSTAT semicolon
+            //  if !STATEMENT.scopes.contains(ScopeType::Loop) { ERRO("Comando usado fora de um laço de repetição"); }
+            //  Um break sem rótulo também pode sair de um switch
+            //  if JUMPSTAT.label && !STATEMENT.scopes.contains_label(label) { ERRO("Rótulo não definido"); }
+            SemanticNodeData::Jumpstat { token, label } => {
+              let in_switch = token.token_type == TokenType::KwBreak && label.is_none() && scopes.contains(ScopeType::Switch);
+              if !scopes.contains(ScopeType::Loop) && !in_switch {
+                let context = if token.token_type == TokenType::KwBreak && label.is_none() { "de um laço de repetição ou switch" } else { "de um laço de repetição" };
+                return Err(format!("Erro semântico: Comando '{}' fora {} na linha {} coluna {}", token.token_type, context, token.line, token.column).into());
+              }
+              if let Some(label) = label {
+                let label = label.token();
+                let Some(ConstType::String(name)) = &label.value else { panic!() };
+                if !scopes.contains_label(name) {
+                  return Err(format!("Erro semântico: Rótulo '{}' não pertence a nenhum laço que contém o comando '{}' na linha {} coluna {}", name, token.token_type, label.line, label.column).into());
                 }
               }
             },
@@ -398,38 +1362,138 @@ impl SemanticNode {
         // Statement -> ;
         Ok(None)
       },
-      SemanticNodeData::Term { unaryexpression, unaryexpression2, .. } => {
-        let ReturnSem::Dado { tipo: tipo1, pos: exp1_pos } = unaryexpression.semantic_analysis(scopes)?.unwrap() else { panic!(); };
+      SemanticNodeData::Structdef { id, fieldlist } => {
+        // STRUCTDEF -> kw_struct id lbrace FIELDLIST rbrace
+        //  Os campos são declarações de variáveis, cujos tipos podem ser structs definidas antes desta
+        //  Os nomes dos campos não se repetem
+        //  tipos.insert(id, FIELDLIST.campos)
+        let id_node = id.token();
+        let struct_name = id_node.value.as_ref().unwrap().to_string();
+        let SemanticNodeData::Fieldlist { fields } = &mut fieldlist.children else { panic!() };
+        let mut entries: Vec<FieldEntry> = vec![];
+        for field in fields.iter_mut() {
+          let SemanticNodeData::Vardecl { var_type, id, const_index, init } = &mut field.children else { panic!() };
+          let var_type = var_type.declared_type(scopes)?;
+          let field_node = id.token();
+          let name = field_node.value.as_ref().unwrap().to_string();
+          if init.is_some() {
+            return Err(format!("Erro semântico: o campo '{}' da struct '{}' não pode ter valores iniciais na linha {}, coluna {}", name, struct_name, field_node.line, field_node.column).into());
+          }
+          if entries.iter().any(|entry| entry.name == name) {
+            return Err(format!("Erro semântico: campo '{}' repetido na struct '{}' na linha {}, coluna {}", name, struct_name, field_node.line, field_node.column).into());
+          }
+          let const_index = Self::array_dimensions(const_index, &name, scopes)?;
+          entries.push(FieldEntry { name, var_type, const_index, offset: 0 });
+        }
+        if scopes.get_symbol(&struct_name).is_some_and(|entry| entry.enumerators.is_some()) {
+          return Err(format!("Erro semântico: Redefinição do tipo '{}' na linha {}, coluna {}", struct_name, id_node.line, id_node.column).into());
+        }
+        scopes.types.insert_struct(struct_name, (id_node.line, id_node.column), entries)?;
+        Ok(None)
+      },
+      SemanticNodeData::Switchstat { expression, caselist } => {
+        // SWITCHSTAT -> kw_switch lparenthesis EXPRESSION rparenthesis lbrace CASELIST rbrace
+        //  CASESTAT.valor é constante e CASESTAT.tipo == EXPRESSION.tipo para cada caso
+        //  Os valores dos casos (e o default) não se repetem
+        //  CASELIST.scopes.push(ScopeType::Switch)
+        let ReturnSem::Dado { tipo } = expression.semantic_analysis(scopes)?.unwrap() else { panic!(); };
+        let SemanticNodeData::Caselist { cases } = &mut caselist.children else { panic!() };
+        // Os valores são verificados antes dos comandos dos casos, que não podem declarar constantes usadas por eles
+        let mut values: Vec<ConstType> = vec![];
+        let mut has_default = false;
+        for case in cases.iter_mut() {
+          let SemanticNodeData::Casestat { value, .. } = &mut case.children else { panic!() };
+          let Some(value) = value else {
+            if has_default {
+              return Err(format!("Erro semântico: mais de um caso 'default' no switch na linha {} coluna {}", case.span.start.0, case.span.start.1).into());
+            }
+            has_default = true;
+            continue;
+          };
+          let (line, column) = value.span.start;
+          let ReturnSem::Dado { tipo: case_tipo } = value.semantic_analysis(scopes)?.unwrap() else { panic!(); };
+          if case_tipo != tipo {
+            return Err(format!("Erro semântico: tipo do caso incompatível com a expressão do switch na linha {} coluna {}", line, column).into());
+          }
+          let constants = |name: &str| scopes.get_symbol(name).and_then(|entry| entry.const_value);
+          let case_value = value.const_value(&constants)?;
+          if values.contains(&case_value) {
+            return Err(format!("Erro semântico: caso '{}' repetido no switch na linha {} coluna {}", case_value, line, column).into());
+          }
+          values.push(case_value);
+        }
+        // Strings são comparadas com os casos pelo ambiente de execução
+        if tipo == VarType::String {
+          expression.attrs.string_operation = true;
+        }
+        scopes.push_scope(ScopeType::Switch);
+        caselist.semantic_analysis(scopes)?;
+        scopes.pop_scope();
+        Ok(None)
+      },
+      SemanticNodeData::Term { unaryexpression, op_term, unaryexpression2 } => {
+        let ReturnSem::Dado { tipo: mut tipo1 } = unaryexpression.semantic_analysis(scopes)?.unwrap() else { panic!(); };
         if let Some(unaryexpression2) = unaryexpression2 {
-          let ReturnSem::Dado { tipo: tipo2, pos: exp2_pos } = unaryexpression2.semantic_analysis(scopes)?.unwrap() else { panic!(); };
-          let exp2_pos = exp2_pos.unwrap_or((0, 0));
+          let ReturnSem::Dado { tipo: tipo2 } = unaryexpression2.semantic_analysis(scopes)?.unwrap() else { panic!(); };
+          let exp2_pos = unaryexpression2.span.start;
 
-          if tipo1 != tipo2 {
+          let Some(tipo) = Self::promote(unaryexpression, &tipo1, unaryexpression2, &tipo2, scopes) else {
             return Err(format!("Erro semântico: tipos incompatíveis na expressão numérica na linha {} coluna {}", exp2_pos.0, exp2_pos.1).into());
+          };
+          tipo1 = tipo;
+          Self::check_arithmetic_operand(&tipo1, &unaryexpression.span)?;
+          if tipo1 == VarType::String {
+            Self::check_string_operator(op_term.as_ref().unwrap(), false)?;
           }
         }
-        return Ok(Some(ReturnSem::Dado { tipo: tipo1, pos: exp1_pos }));
+        return Ok(Some(ReturnSem::Dado { tipo: tipo1, }));
       },
-      SemanticNodeData::Unaryexpression { factor, .. } => {
-        factor.semantic_analysis(scopes)
+      SemanticNodeData::Unaryexpression { op, factor } => {
+        let tipo = factor.semantic_analysis(scopes)?;
+        if let (Some(op), Some(ReturnSem::Dado { tipo })) = (op, &tipo) {
+          Self::check_arithmetic_operand(tipo, &factor.span)?;
+          if *tipo == VarType::String {
+            Self::check_string_operator(op, true)?;
+          }
+        }
+        Ok(tipo)
       },
-      SemanticNodeData::Vardecl {var_type, id, const_index} => {
+      SemanticNodeData::Vardecl {var_type, id, const_index, init} => {
         // Declared variable type
-        let SemanticNodeData::Terminal { value: var_type_node } = &var_type.children else { panic!() };
-        let var_type = var_type_node.value.clone().unwrap().get_keyword_type();
+        // VARDECL -> id id [CONST_INDEX]
+        //  O primeiro id é o nome de uma struct já definida
+        let var_type = var_type.declared_type(scopes)?;
 
         // Declared variable name
-        let SemanticNodeData::Terminal { value: id_node } = &id.children else { panic!() };
+        let SemanticNodeData::Terminal { value: id_node } = &mut id.children else { panic!() };
         let ConstType::String(id_name) = id_node.value.clone().unwrap() else { panic!() };
 
         // Declared variable dimensions
-        let mut token_index = vec![];
-        if let Some(const_index) = const_index {
-          let SemanticNodeData::ConstIndex { index } = &const_index.children else { panic!() };
-          for child in index.iter() {
-            let SemanticNodeData::Terminal { value: token } = &child.children else { panic!(); };
-            if let Some(ConstType::Int(index_value)) = &token.value { token_index.push(*index_value as u32); } 
-            else { panic!();}
+        // CONST_INDEX -> [NUMEXPRESSION]+
+        let token_index = Self::array_dimensions(const_index, &id_name, scopes)?;
+
+        // VARDECL -> var_type id CONST_INDEX INITLIST
+        //  Apenas vetores possuem valores iniciais, no máximo um para cada elemento
+        //  Cada valor tem o tipo dos elementos e, em uma variável global, é uma expressão constante
+        //  Os valores são analisados antes da inserção da variável, então não podem usá-la
+        if let Some(init) = init {
+          let SemanticNodeData::Initlist { values } = &mut init.children else { panic!() };
+          if token_index.is_empty() {
+            return Err(format!("Erro semântico: valores iniciais para a variável '{}', que não é um vetor, na linha {}, coluna {}", id_name, id_node.line, id_node.column).into());
+          }
+          let size = token_index.iter().fold(1u64, |size, dimension| size.saturating_mul(*dimension as u64));
+          if values.len() as u64 > size {
+            return Err(format!("Erro semântico: {} valores iniciais para o vetor '{}' de {} elementos na linha {}, coluna {}", values.len(), id_name, size, init.span.start.0, init.span.start.1).into());
+          }
+          for value in values.iter_mut() {
+            let Some(ReturnSem::Dado { tipo }) = value.semantic_analysis(scopes)? else { panic!(); };
+            if !Self::assignable(value, &tipo, &var_type, scopes) {
+              return Err(format!("Erro semântico: tipo incompatível no valor inicial do vetor '{}' na linha {} coluna {}", id_name, value.span.start.0, value.span.start.1).into());
+            }
+            if scopes.in_global_scope() {
+              let constants = |name: &str| scopes.get_symbol(name).and_then(|entry| entry.const_value);
+              value.const_value(&constants)?;
+            }
           }
         }
 
@@ -438,16 +1502,33 @@ impl SemanticNode {
           appearances: vec![(id_node.line, id_node.column)],
           var_type: vec![var_type.clone()],
           const_index: token_index,
+          return_type: None,
+          const_value: None,
+          enumerators: None,
+          param_index: vec![],
+          overloads: vec![],
+          allocated: false,
         };
         scopes.insert_symbol(id_name, entry)?;
 
         Ok(None)
       },
       SemanticNodeData::VarIndex {index} => {
-        for i in index.iter() {
+        for i in index.iter_mut() {
           i.semantic_analysis(scopes)?;
         }
         Ok(None)
+      },
+      SemanticNodeData::Whilestat {label, condition, body} => {
+        // WHILESTAT -> kw_while lparenthesis EXPRESSION rparenthesis lbrace STATELIST rbrace
+        //  STATELIST.scopes.push(ScopeType::Loop)
+        condition.semantic_analysis(scopes)?;
+        scopes.push_loop(label.as_deref().map(SemanticNode::token))?;
+        scopes.push_scope(ScopeType::Loop);
+        body.semantic_analysis(scopes)?;
+        scopes.pop_scope();
+        scopes.pop_loop();
+        Ok(None)
       }, 
       SemanticNodeData::Terminal { value: token } => {
         match token.token_type {
@@ -455,7 +1536,7 @@ impl SemanticNode {
             // # Pop global scope
             // EOF
             // EOF.scopes.pop()
-            if scopes.stack.len() == 1 {
+            if scopes.in_global_scope() {
               scopes.pop_scope();
               Ok(Some(ReturnSem::TT(TokenType::Eof)))
             } else {
@@ -467,7 +1548,7 @@ impl SemanticNode {
             // #  CONSTANT.tipo = "int"
             if let Some(value) = &token.value {
               if let ConstType::Int(_) = value {
-                Ok(Some(ReturnSem::Dado{ tipo: VarType::Int, pos: Some((token.line, token.column)) }))
+                Ok(Some(ReturnSem::Dado{ tipo: VarType::Int }))
               } else {
                 Err("Expected integer constant".into())
               }
@@ -480,7 +1561,7 @@ impl SemanticNode {
             // #  CONSTANT.tipo = "float"
             if let Some(value) = &token.value {
               if let ConstType::Float(_) = value {
-                Ok(Some(ReturnSem::Dado{ tipo: VarType::Float, pos: Some((token.line, token.column)) }))
+                Ok(Some(ReturnSem::Dado{ tipo: VarType::Float }))
               } else {
                 Err("Expected float constant".into())
               }
@@ -493,7 +1574,7 @@ impl SemanticNode {
             // #  CONSTANT.tipo = "string"
             if let Some(value) = &token.value {
               if let ConstType::String(_) = value {
-                Ok(Some(ReturnSem::Dado{ tipo: VarType::String, pos: Some((token.line, token.column)) }))
+                Ok(Some(ReturnSem::Dado{ tipo: VarType::String }))
               } else {
                 Err("Expected string constant".into())
               }
@@ -510,11 +1591,14 @@ impl SemanticNode {
             let Some(symbol_entry) = scopes.get_symbol(&id_name) else {
               return Err(format!("Erro semântico: variável '{}' não declarada no escopo atual na linha {} columna {}", id_name, token.line, token.column).into());
             };
+            if symbol_entry.enumerators.is_some() {
+              return Err(format!("Erro semântico: '{}' é um tipo e não pode ser usado como valor na linha {} coluna {}", id_name, token.line, token.column).into());
+            }
             let tipo = symbol_entry.var_type[0].clone();
-            Ok(Some(ReturnSem::Dado { tipo, pos: Some((token.line, token.column)) }))
+            Ok(Some(ReturnSem::Dado { tipo }))
           },
           TokenType::VarType => {
-            Ok(Some(ReturnSem::Dado{ tipo: token.get_type(), pos: Some((token.line, token.column)) }))
+            Ok(Some(ReturnSem::Dado{ tipo: token.get_type() }))
           },
           // Comma | ConstNull | FuncId | Id
           //   | KwBreak | KwDef | KwElse | KwFor | KwIf | KwNew | KwPrint | KwRead
@@ -530,10 +1614,111 @@ impl SemanticNode {
   }
 
   /// generate TAC (Three Address Code) for the semantic node
+  /// Name of a declared type in the generated code: the keyword type, or the name of the struct
+  fn type_name(&self) -> String {
+    if let SemanticNodeData::Signature { .. } = &self.children {
+      return self.signature_type().to_string();
+    }
+    let token = self.token();
+    if token.token_type == TokenType::Id {
+      token.value.as_ref().unwrap().to_string()
+    } else {
+      format!("{}", token.get_type())
+    }
+  }
+
+  /// Keeps track of the variables of struct type visible in the block, whose fields are accessed by offset.
+  /// A declaration of any other type hides a struct variable with the same name
+  fn declare_variable(var_type: &SemanticNode, name: &str, dimensions: Vec<u32>, inh: &mut CodeAttrs) {
+    if matches!(&var_type.children, SemanticNodeData::Terminal { value } if value.token_type == TokenType::Id) {
+      inh.structs.insert(name.to_string(), (var_type.type_name(), dimensions));
+    } else {
+      inh.structs.remove(name);
+    }
+  }
+
+  /// Sizes of the dimensions of a declared array, already checked to be constant by the semantic analysis
+  fn const_dimensions(const_index: &Option<Box<SemanticNode>>, inh: &CodeAttrs) -> Vec<u32> {
+    let lookup = |name: &str| inh.constants.get(name).cloned();
+    match const_index.as_deref().map(|const_index| &const_index.children) {
+      Some(SemanticNodeData::ConstIndex { index }) => index.iter().map(|size| match size.const_value(&lookup).unwrap() {
+        ConstType::Int(size) => size as u32,
+        _ => panic!("Expected int array size"),
+      }).collect(),
+      _ => vec![],
+    }
+  }
+
+  /// Offset, in words, of the value accessed by an LVALUE from the start of the variable (or field) it starts with.
+  /// Words are the unit of every index in the TAC, so `v[1]` and `p[1]` both address the second word of the variable.
+  /// Returns the temporary with the part computed at run time, if any, and the constant part
+  fn access_offset(&self, var_type: &VarType, dimensions: &[u32], inh: &mut CodeAttrs) -> (Option<String>, u32) {
+    let SemanticNodeData::Lvalue { var_index, field, .. } = &self.children else { panic!("Expected LVALUE node"); };
+    let index: &[SemanticNode] = match var_index.as_deref().map(|var_index| &var_index.children) {
+      Some(SemanticNodeData::VarIndex { index }) => index,
+      _ => &[],
+    };
+    let mut offset = None;
+    if !index.is_empty() {
+      // the indexes are linearized in row-major order, then scaled by the size of the indexed element
+      let tmp = inh.create_temp();
+      let first = index[0].generate_code(inh);
+      inh.code.push_str(&format!("{} = {}\n", tmp, first));
+      for (child, size) in index.iter().zip(dimensions.iter()).skip(1) {
+        let tmp2 = child.generate_code(inh);
+        inh.code.push_str(&format!("{} = {} * {}\n", tmp, tmp, size));
+        inh.code.push_str(&format!("{} = {} + {}\n", tmp, tmp, tmp2));
+      }
+      let stride = inh.types.size_of(var_type) * dimensions.iter().skip(index.len()).product::<u32>();
+      inh.code.push_str(&format!("{} = {} * {}\n", tmp, tmp, stride));
+      offset = Some(tmp);
+    }
+    let Some(field) = field else { return (offset, 0); };
+    let VarType::Struct(struct_name) = var_type else { panic!("Expected struct type"); };
+    let SemanticNodeData::Lvalue { id, .. } = &field.children else { panic!("Expected LVALUE node"); };
+    let name = id.token().value.as_ref().unwrap().to_string();
+    let entry = inh.types.get_struct(struct_name).and_then(|entry| entry.get_field(&name)).cloned().unwrap();
+    let (field_offset, constant) = field.access_offset(&entry.var_type, &entry.const_index, inh);
+    let offset = match (offset, field_offset) {
+      (Some(tmp), Some(tmp2)) => {
+        inh.code.push_str(&format!("{} = {} + {}\n", tmp, tmp, tmp2));
+        Some(tmp)
+      },
+      (tmp, tmp2) => tmp.or(tmp2),
+    };
+    (offset, entry.offset + constant)
+  }
+
+  /// Address of an array passed by reference to a function.
+  /// An array parameter already holds the address of the array it received, so it is passed as is
+  fn array_reference(&self, inh: &mut CodeAttrs) -> String {
+    let is_reference = match &self.children {
+      SemanticNodeData::Lvalue { id, var_index: None, field: None } => inh.references.contains(&id.token().value.as_ref().unwrap().to_string()),
+      _ => false,
+    };
+    let lval = self.generate_code(inh);
+    let tmp = inh.create_temp();
+    let address = if is_reference { "" } else { "&" };
+    inh.code.push_str(&format!("{} = {}{}\n", tmp, address, lval));
+    tmp
+  }
+
+  /// Indexes of the element at a position of an array, in row-major order: position 4 of int m[2][3] is m[1][1]
+  fn element_index(position: usize, dimensions: &[u32]) -> String {
+    let mut rest = position as u32;
+    let mut index = String::new();
+    for size in dimensions.iter().rev() {
+      index = format!("[{}]{}", rest % size, index);
+      rest /= size;
+    }
+    index
+  }
+
   fn generate_code(&self, inh: &mut CodeAttrs) -> String {
     match &self.children {
       SemanticNodeData::Allocexpression { var_type, dimensions } => {
         // ALLOCEXPRESSION -> kw_new vartype VAR_INDEX
+        // ALLOCEXPRESSION -> kw_new id VAR_INDEX
         // will not call generate_code for dimensions,
         // because it would print the dimensions between brackets
         let SemanticNodeData::VarIndex { index 
@@ -546,8 +1731,8 @@ impl SemanticNode {
             inh.code.push_str(&format!("{} = {} * {}\n", tmp_dim, tmp_dim, tmp0));
           }
         }
-        // Generate code for the variable type
-        let var_size = var_type.generate_code(inh);
+        // Generate code for the variable type (the name of the struct, for a struct type)
+        let var_size = var_type.type_name();
         inh.code.push_str(&format!("param {}\n", var_size));
         let tmp = inh.create_temp();
         inh.code.push_str(&format!("param {}\n", tmp_dim));
@@ -555,27 +1740,81 @@ impl SemanticNode {
         inh.code.push_str("\n");
         tmp
       },
-      SemanticNodeData::Atribstat { lvalue, value } => {
+      SemanticNodeData::Atribstat { lvalue, op, value } => {
         // ATRIBSTAT -> LVALUE op_assign ATRIBSTATEVALUE
         let tmp_lv = lvalue.generate_code(inh);
-        let tmp_v = value.generate_code(inh);
+        let tmp_v = if let Some(op) = op {
+          // ATRIBSTAT -> LVALUE op ATRIBSTATEVALUE
+          // the lvalue (and its index) is evaluated only once, and its current value is read into a temporary
+          let tmp = inh.create_temp();
+          inh.code.push_str(&format!("{} = {}\n", tmp, tmp_lv));
+          let tmp2 = value.generate_code(inh);
+          let tmp2 = inh.convert(&value.attrs.conversion, tmp2);
+          if op.attrs.string_operation {
+            inh.code.push_str(&format!("param {}\nparam {}\n{} = call str_concat, 2\n", tmp, tmp2, tmp));
+          } else {
+            inh.code.push_str(&format!("{} = {} ", tmp, tmp));
+            op.generate_code(inh);
+            inh.code.push_str(&format!(" {}\n", tmp2));
+          }
+          tmp
+        } else {
+          // an int value assigned to a float variable is converted first
+          let tmp = value.generate_code(inh);
+          inh.convert(&value.attrs.conversion, tmp)
+        };
         inh.code.push_str(&format!("{} = {}\n", tmp_lv, tmp_v));
         tmp_lv
       },
-      SemanticNodeData::Atribstatevalue { expression, allocexpression, funccall } => {
+      SemanticNodeData::Atribstatevalue { expression, allocexpression } => {
         // ATRIBSTATEVALUE -> EXPRESSION 
         // ATRIBSTATEVALUE -> ALLOCEXPRESSION
-        // ATRIBSTATEVALUE -> FUNCCALL
         if let Some(expression) = expression {
           expression.generate_code(inh)
         } else if let Some(allocexpression) = allocexpression {
           allocexpression.generate_code(inh)
-        } else if let Some(funccall) = funccall {
-          funccall.generate_code(inh)          
         } else {
           panic!("ATRIBSTATEVALUE is empty");
         }
       },
+      SemanticNodeData::Caselist { .. } => {
+        // CASELIST -> CASESTAT*
+        // the cases are generated by SWITCHSTAT, which places a label before each one
+        panic!("CASELIST is generated by SWITCHSTAT")
+      },
+      SemanticNodeData::Casestat { statelist, .. } => {
+        // CASESTAT -> kw_case NUMEXPRESSION colon [STATELIST]
+        // CASESTAT -> kw_default colon [STATELIST]
+        // the value is only used by the jumps generated by SWITCHSTAT
+        match statelist {
+          Some(statelist) => statelist.generate_code(inh),
+          None => String::new(),
+        }
+      },
+      SemanticNodeData::Castexpression { factor, .. } => {
+        // CASTEXPRESSION -> var_type FACTOR
+        // an enumerator converted to int keeps its value, so only the numeric conversions need code
+        let tmp = factor.generate_code(inh);
+        inh.convert(&factor.attrs.conversion, tmp)
+      },
+      SemanticNodeData::Condexpression { condition, then_expression, else_expression } => {
+        // EXPRESSION -> EXPRESSION question EXPRESSION colon EXPRESSION
+        // jumps over the branch that was not selected, so only one of them is evaluated
+        let cond_tmp = condition.generate_code(inh);
+        // both branches store their value in the same temporary variable
+        let tmp = inh.create_temp();
+        let else_label = inh.create_label();
+        let end_label = inh.create_label();
+        inh.code.push_str(&format!("if {} == 0 goto {}\n", cond_tmp, else_label));
+        let then_tmp = then_expression.generate_code(inh);
+        inh.code.push_str(&format!("{} = {}\n", tmp, then_tmp));
+        inh.code.push_str(&format!("goto {}\n", end_label));
+        inh.code.push_str(&format!("{}:\n", else_label));
+        let else_tmp = else_expression.generate_code(inh);
+        inh.code.push_str(&format!("{} = {}\n", tmp, else_tmp));
+        inh.code.push_str(&format!("{}:\n", end_label));
+        tmp
+      },
       SemanticNodeData::Constant { value, .. } => {
         // creates a new temporary variable for the constant
         let tmp = inh.create_temp();
@@ -583,8 +1822,8 @@ impl SemanticNode {
         tmp
       },
       SemanticNodeData::ConstIndex { index } => {
-        // CONSTINDEX -> [CONSTANT1, CONSTANT2, CONSTANT3...]
-        // CONST_INDEX -> [lbracket const_int rbracket]+
+        // CONSTINDEX -> [NUMEXPRESSION1, NUMEXPRESSION2, NUMEXPRESSION3...]
+        // CONST_INDEX -> [lbracket NUMEXPRESSION rbracket]+
         for child in index.iter() {
           inh.code.push_str("[");
           child.generate_code(inh);
@@ -592,6 +1831,56 @@ impl SemanticNode {
         }
         String::new() // No temporary variable is created for CONSTINDEX
       },
+      SemanticNodeData::Constdecl { var_type, id, value } => {
+        // CONSTDECL -> kw_const var_type id op_assign EXPRESSION
+        // a local constant is stored like a variable that is assigned only once, at its declaration
+        let tmp = value.generate_code(inh);
+        let tmp = inh.convert(&value.attrs.conversion, tmp);
+        let name = id.token().value.as_ref().unwrap().to_string();
+        let variable = inh.declare_local(&name);
+        inh.code.push_str(&format!("{} = {}\n", variable, tmp));
+        // its value is also kept, for the case values of the switch statements that use it
+        let constant = value.const_value(&|name: &str| inh.constants.get(name).cloned()).unwrap();
+        let constant = Self::convert_constant(constant, &var_type.token().value.as_ref().unwrap().get_keyword_type());
+        inh.constants.insert(name, constant);
+        String::new()
+      },
+      SemanticNodeData::Dowhilestat { label, body, condition } => {
+        // DOWHILESTAT -> kw_do lbrace STATELIST rbrace kw_while lparenthesis EXPRESSION rparenthesis
+        // the body always runs before the first test of the condition
+        let loop_start_label = inh.create_label();
+        let loop_continue_label = inh.create_label();
+        let loop_end_label = inh.create_label();
+        inh.code.push_str(&format!("{}:\n", loop_start_label));
+        // break leaves the loop and continue jumps to the condition
+        inh.push_loop(label.as_deref().map(SemanticNode::label_name), loop_end_label.clone(), loop_continue_label.clone());
+        body.generate_code(inh);
+        inh.pop_loop();
+        inh.code.push_str(&format!("{}:\n", loop_continue_label));
+        let cond_tmp = condition.generate_code(inh);
+        // leaves the loop when the condition is false, otherwise goes back to the start
+        inh.code.push_str(&format!("if {} == 0 goto {}\n", cond_tmp, loop_end_label));
+        inh.code.push_str(&format!("goto {}\n", loop_start_label));
+        inh.code.push_str(&format!("{}:\n", loop_end_label));
+        cond_tmp
+      },
+      SemanticNodeData::Enumdef { enumlist, .. } => {
+        // ENUMDEF -> kw_enum id lbrace ENUMLIST rbrace
+        // enumerators are lowered to their integer values: each one is "named" by its value in the generated code,
+        // and is also a constant for the sizes of arrays and the values of cases
+        let SemanticNodeData::Enumlist { enumerators } = &enumlist.children else { panic!() };
+        for (value, enumerator) in enumerators.iter().enumerate() {
+          let name = enumerator.token().value.as_ref().unwrap().to_string();
+          inh.variables.insert(name.clone(), value.to_string());
+          inh.structs.remove(&name);
+          inh.constants.insert(name, ConstType::Int(value as i64));
+        }
+        String::new()
+      },
+      SemanticNodeData::Enumlist { .. } => {
+        // ENUMLIST is handled by ENUMDEF
+        panic!("ENUMLIST should not appear on generated code");
+      },
       SemanticNodeData::Elsestat { statement } => {
         // ELSESTAT -> kw_else IFSTAT
         // ELSESTAT -> kw_else lbrace STATELIST rbrace
@@ -602,21 +1891,33 @@ impl SemanticNode {
         // EXPRESSION -> NUMEXPRESSION op_expression numexpression2
         // inh.code.push_str(&format!("{{\n"));
 
-        let tmp = numexpression.generate_code(inh);
+        let mut tmp = numexpression.generate_code(inh);
         if let Some(numexpression2) = numexpression2 {
           if let Some(op_expression) = op_expression {
+            // an int compared with a float is converted to float
+            tmp = inh.convert(&numexpression.attrs.conversion, tmp);
             let tmp2 = numexpression2.generate_code(inh);
-            inh.code.push_str(&format!("{} = {} ", tmp, tmp));
-            op_expression.generate_code(inh);
-            inh.code.push_str(&format!(" {}\n", tmp2));
+            let tmp2 = inh.convert(&numexpression2.attrs.conversion, tmp2);
+            if op_expression.attrs.string_operation {
+              // strings are compared by the runtime, which returns a negative, zero or positive value
+              inh.code.push_str(&format!("param {}\nparam {}\n{} = call str_compare, 2\n", tmp, tmp2, tmp));
+              inh.code.push_str(&format!("{} = {} ", tmp, tmp));
+              op_expression.generate_code(inh);
+              inh.code.push_str(" 0\n");
+            } else {
+              inh.code.push_str(&format!("{} = {} ", tmp, tmp));
+              op_expression.generate_code(inh);
+              inh.code.push_str(&format!(" {}\n", tmp2));
+            }
           }
         }
         tmp
       },
-      SemanticNodeData::Factor { expression, lvalue, constant } => {
+      SemanticNodeData::Factor { expression, lvalue, constant, funccall } => {
         // FACTOR -> EXPRESSION
         // FACTOR -> LVALUE
         // FACTOR -> CONSTANT
+        // FACTOR -> FUNCCALL
         if let Some(expression) = expression {
           expression.generate_code(inh)
         } else if let Some(lvalue) = lvalue {
@@ -626,12 +1927,34 @@ impl SemanticNode {
           tmp
         } else if let Some(constant) = constant {
           constant.generate_code(inh)
+        } else if let Some(funccall) = funccall {
+          // the call result is already in a temporary variable
+          funccall.generate_code(inh)
         } else {
             panic!("FACTOR is empty");
         }
       },
-      SemanticNodeData::Forstat { init, condition, increment, body } => {
-        // FORSTAT -> kw_for lparenthesis ATRIBSTAT semicolon EXPRESSION semicolon ATRIBSTAT rparenthesis lbrace STATELIST rbrace
+      SemanticNodeData::Fieldlist { .. } => {
+        // FIELDLIST is handled by STRUCTDEF
+        panic!("FIELDLIST should not appear on generated code");
+      },
+      SemanticNodeData::Forinit { id, value, .. } => {
+        // FORINIT -> var_type id op_assign EXPRESSION
+        // the loop variable gets its own name, so sibling loops that declare the same name don't share it
+        let tmp = value.generate_code(inh);
+        let tmp = inh.convert(&value.attrs.conversion, tmp);
+        let name = id.token().value.as_ref().unwrap().to_string();
+        let variable = inh.create_variable(&name);
+        inh.code.push_str(&format!("{} = {}\n", variable, tmp));
+        inh.structs.remove(&name);
+        inh.references.remove(&name);
+        inh.variables.insert(name, variable);
+        String::new()
+      },
+      SemanticNodeData::Forstat { label, init, condition, increment, body } => {
+        // FORSTAT -> kw_for lparenthesis FORINIT semicolon EXPRESSION semicolon ATRIBSTAT rparenthesis lbrace STATELIST rbrace
+        // a variable declared by the initialization is not visible after the loop
+        let (variables, structs, references) = (inh.variables.clone(), inh.structs.clone(), inh.references.clone());
         // creates a loop label
         let loop_start_label = inh.create_label();
         // generates code for the loop initialization
@@ -645,48 +1968,190 @@ impl SemanticNode {
         let loop_end_label = inh.create_label();
         // inverts the condition and adds a goto to the end of the loop
         inh.code.push_str(&format!("if {} == 0 goto {}\n", cond_tmp, loop_end_label));
-        // sets the end label as the break label
-        inh.set_scope_end(loop_end_label.clone());
+        // break jumps to the end label and continue jumps to the increment, not to the condition
+        let loop_continue_label = inh.create_label();
+        inh.push_loop(label.as_deref().map(SemanticNode::label_name), loop_end_label.clone(), loop_continue_label.clone());
         // generates code for the loop body
         body.generate_code(inh);
+        // restores the break and continue targets of the enclosing loop
+        inh.pop_loop();
         // generates code for the loop increment
+        inh.code.push_str(&format!("{}:\n", loop_continue_label));
         increment.generate_code(inh);
         // adds a goto to the start of the loop
         inh.code.push_str(&format!("goto {}\n", loop_start_label));
         // adds a label for the end of the loop
         inh.code.push_str(&format!("{}:\n", loop_end_label));
+        (inh.variables, inh.structs, inh.references) = (variables, structs, references);
         cond_tmp
       },
       SemanticNodeData::Funccall { id, paramlistcall } => {
         // FUNCCALL -> id
         // FUNCCALL -> id PARAMLISTCALL
-        let n_params = if let Some(paramlistcall) = paramlistcall {
-          paramlistcall.generate_code(inh);
-          let SemanticNodeData::Paramlistcall { paramlist } = &paramlistcall.children else { panic!(); };
-          paramlist.len()
-        } else {
-          0
+        // PARAMLISTCALL -> (EXPRESSION)+
+        // arrays are passed by reference: the param is the address of the array instead of its value
+        // an indirect call (FUNCCALL -> LVALUE PARAMLISTCALL) calls the function whose address is stored in the value,
+        // which is read before the arguments
+        let callee = match &id.children {
+          SemanticNodeData::Lvalue { .. } => {
+            let lval = id.generate_code(inh);
+            let tmp = inh.create_temp();
+            inh.code.push_str(&format!("{} = {}\n", tmp, lval));
+            Some(tmp)
+          },
+          _ => None,
+        };
+        let by_reference = match callee {
+          Some(_) => vec![],
+          None => inh.array_params.get(&id.function_name()).cloned().unwrap_or_default(),
         };
+        // evaluates every argument before the first param, so that the code of a
+        // nested call does not get mixed with the params of this call
+        let tmps: Vec<String> = match paramlistcall {
+          Some(paramlistcall) => {
+            let SemanticNodeData::Paramlistcall { paramlist } = &paramlistcall.children else { panic!(); };
+            paramlist.iter().enumerate().map(|(position, param)| match param.bare_lvalue() {
+              Some(lvalue) if by_reference.get(position) == Some(&true) => lvalue.array_reference(inh),
+              _ => param.generate_code(inh),
+            }).collect()
+          },
+          None => vec![],
+        };
+        // a builtin is an operation of its own, named after the function, that receives the arguments directly
+        if callee.is_none() && id.attrs.builtin {
+          let tmp = inh.create_temp();
+          let operation = id.token().value.as_ref().unwrap().to_string();
+          inh.code.push_str(&format!("{} = {} {}\n", tmp, operation.trim_start_matches('@'), tmps.join(", ")));
+          return tmp;
+        }
+        for tmp in tmps.iter() {
+          inh.code.push_str(&format!("param {}\n", tmp));
+        }
+        let n_params = tmps.len();
         let tmp = inh.create_temp();
         inh.code.push_str(&format!("{} = call ", tmp));
-        id.generate_code(inh);
+        match callee {
+          Some(callee) => inh.code.push_str(&format!("*{}", callee)),
+          None => { id.generate_code(inh); },
+        }
         inh.code.push_str(&format!(", {}\n", n_params));
         tmp
       },
-      SemanticNodeData::Funcdef { func_id, paramlist, statelist } => {
+      SemanticNodeData::Funcref { id } => {
+        // FACTOR -> func_id
+        // the value of a function is its address, used by the indirect calls
+        let tmp = inh.create_temp();
+        inh.code.push_str(&format!("{} = &{}\n", tmp, id.function_name()));
+        tmp
+      },
+      SemanticNodeData::Interpolation { segments } => {
+        // INTERPOLATION -> string_start EXPRESSION (string_middle EXPRESSION)* string_end
+        // each piece is converted to a string, and the pieces are concatenated by the runtime, from left to right
+        let mut result: Option<String> = None;
+        for segment in segments.iter() {
+          let tmp = if let SemanticNodeData::Terminal { value } = &segment.children {
+            // the text of the literal, without the quotes and braces that surround it, with its escaped braces replaced
+            let Some(ConstType::String(text)) = &value.value else { panic!(); };
+            let text = unescape_braces(&text[1..text.len() - 1]);
+            if text.is_empty() { continue; }
+            let tmp = inh.create_temp();
+            inh.code.push_str(&format!("{} = \"{}\"\n", tmp, text));
+            tmp
+          } else {
+            let tmp = segment.generate_code(inh);
+            inh.convert(&segment.attrs.conversion, tmp)
+          };
+          match &result {
+            Some(result) => inh.code.push_str(&format!("param {}\nparam {}\n{} = call str_concat, 2\n", result, tmp, result)),
+            None => result = Some(tmp),
+          }
+        }
+        // there is always at least one expression
+        result.unwrap()
+      },
+      SemanticNodeData::Funcdef { func_id, paramlist, statelist, .. } => {
         // FUNCDEF -> func_id PARAMLIST STATELIST
         // FUNCDEF -> func_id STATELIST
-        func_id.generate_code(inh);
+        let func_name = func_id.generate_code(inh);
         inh.code.push_str(":\n");
+        // the parameters hide the enumerators and global variables with the same name until the end of the function
+        let variables = inh.variables.clone();
+        // the array parameters are registered before the body, so that a recursive call passes them by reference
+        let mut by_reference = vec![];
         if let Some(paramlist) = paramlist {
           paramlist.generate_code(inh);
+          let SemanticNodeData::Paramlist { paramlist } = &paramlist.children else { panic!(); };
+          let mut name = String::new();
+          for param in paramlist.iter() {
+            match &param.children {
+              SemanticNodeData::Terminal { value } if value.token_type == TokenType::Id => {
+                name = value.value.as_ref().unwrap().to_string();
+                inh.declare_local(&name);
+                by_reference.push(false);
+              },
+              SemanticNodeData::ParamIndex { .. } => {
+                inh.references.insert(name.clone());
+                *by_reference.last_mut().unwrap() = true;
+              },
+              _ => {},
+            }
+          }
         }
-        statelist.generate_code(inh)
+        inh.array_params.insert(func_name, by_reference);
+        let tmp = statelist.generate_code(inh);
+        inh.variables = variables;
+        inh.references.clear();
+        tmp
       },
       SemanticNodeData::Funclist { funclist } => {
-        // FUNCLIST -> FUNCDEF+
+        // FUNCLIST -> (FUNCDEF | VARDECL | CONSTDECL | ENUMDEF | STRUCTDEF)*
+        // the values of the global constants are kept in inh.constants, and are used to compute the sizes of the global arrays
+        // the names of all global variables and constants are known before the functions, even the ones declared after them,
+        // so that a local with the same name never shares the storage of a global
+        for func in funclist.iter() {
+          if let SemanticNodeData::Statement { vardecl: Some(vardecl), .. } = &func.children {
+            if let SemanticNodeData::Vardecl { id, .. } | SemanticNodeData::Constdecl { id, .. } = &vardecl.children {
+              inh.globals.insert(id.token().value.as_ref().unwrap().to_string());
+            }
+          }
+        }
         for func in funclist.iter() {
-          func.generate_code(inh);
+          let SemanticNodeData::Statement { vardecl: Some(vardecl), .. } = &func.children else {
+            func.generate_code(inh);
+            continue;
+          };
+          // global variables are declared with their type and dimensions (or value, for constants),
+          // so they get static storage instead of a place in the function frames.
+          // The semantic analysis already checked that these are constant expressions
+          match &vardecl.children {
+            SemanticNodeData::Vardecl { var_type, id, const_index, init } => {
+              let dimensions = Self::const_dimensions(const_index, inh);
+              let name = id.token().value.as_ref().unwrap().to_string();
+              let sizes: String = dimensions.iter().map(|size| format!("[{}]", size)).collect();
+              // the initial values of a global array are part of its static storage
+              let values = match init.as_deref().map(|init| &init.children) {
+                Some(SemanticNodeData::Initlist { values }) => {
+                  let values: Vec<String> = values.iter().map(|value| value.const_value(&|name: &str| inh.constants.get(name).cloned()).unwrap().to_string()).collect();
+                  format!(" = {{{}}}", values.join(", "))
+                },
+                _ => String::new(),
+              };
+              inh.code.push_str(&format!("global {} {}{}{}\n", var_type.type_name(), name, sizes, values));
+              Self::declare_variable(var_type, &name, dimensions, inh);
+            },
+            SemanticNodeData::Constdecl { var_type, id, value } => {
+              let value = value.const_value(&|name: &str| inh.constants.get(name).cloned()).unwrap();
+              let value = Self::convert_constant(value, &var_type.token().value.as_ref().unwrap().get_keyword_type());
+              let var_type = var_type.generate_code(inh);
+              let name = id.token().value.as_ref().unwrap().to_string();
+              inh.code.push_str(&format!("global const {} {} = {}\n", var_type, name, value));
+              inh.constants.insert(name, value);
+            },
+            SemanticNodeData::Enumdef { .. } => {
+              vardecl.generate_code(inh);
+            },
+            _ => panic!("Expected declaration"),
+          }
         }
         String::new() // No temporary variable is created for FUNCLIST
       },
@@ -716,9 +2181,34 @@ impl SemanticNode {
         }
         cond_tmp // returns the temporary variable generated for the condition
       },
-      SemanticNodeData::Lvalue { id, var_index } => {
+      SemanticNodeData::Jumpstat { token, label } => {
+        // JUMPSTAT -> kw_break [id]
+        // JUMPSTAT -> kw_continue [id]
+        // jumps out of the innermost loop, or out of the loop with the given label
+        let name = label.as_deref().map(SemanticNode::label_name);
+        let target = if token.token_type == TokenType::KwBreak {
+          inh.get_break_label(name.as_deref())
+        } else {
+          inh.get_continue_label(name.as_deref())
+        };
+        let goto = format!("goto {}\n", target);
+        inh.code.push_str(&goto);
+        String::new()
+      },
+      SemanticNodeData::Lvalue { id, var_index, field } => {
         // LVALUE -> id
         // LVALUE -> id VARINDEX
+        // LVALUE -> id VARINDEX LVALUE
+        // a character of a string is read by the runtime, from the string accessed without the last index
+        if self.indexes_string() {
+          let (base, index) = self.without_string_index();
+          let base = base.generate_code(inh);
+          let tmp = inh.create_temp();
+          inh.code.push_str(&format!("{} = {}\n", tmp, base));
+          let index = index.generate_code(inh);
+          inh.code.push_str(&format!("param {}\nparam {}\n{} = call str_at, 2\n", tmp, index, tmp));
+          return tmp;
+        }
         // let tmp = inh.create_temp();
         // inh.code.push_str(&format!("{} = ", tmp));
         let mut lval = if let SemanticNodeData::Terminal { value } = &id.children {
@@ -726,6 +2216,24 @@ impl SemanticNode {
         } else {
           panic!("Expected Terminal for LVALUE id");
         };
+        // a variable declared in the initialization of a for, or a local that hides a global, uses the name it got in its declaration
+        if let Some(variable) = inh.variables.get(&lval) {
+          lval = variable.clone();
+        }
+        if field.is_some() {
+          // a field of a struct is accessed by its offset, in words, from the start of the variable
+          let (struct_name, dimensions) = inh.structs.get(&lval).cloned().unwrap();
+          let (offset, constant) = self.access_offset(&VarType::Struct(struct_name), &dimensions, inh);
+          let offset = match offset {
+            Some(tmp) if constant > 0 => {
+              inh.code.push_str(&format!("{} = {} + {}\n", tmp, tmp, constant));
+              tmp
+            },
+            Some(tmp) => tmp,
+            None => constant.to_string(),
+          };
+          return format!("{}[{}]", lval, offset);
+        }
         if let Some(var_index) = var_index {
           lval.push_str(var_index.generate_code(inh).as_str());
         }
@@ -738,13 +2246,21 @@ impl SemanticNode {
         // term may also be a Numexpression
         // inh.code.push_str(&format!("[\n"));
         // there should always be a previous return value
-        let tmp = term.generate_code(inh);
+        let mut tmp = term.generate_code(inh);
         if let Some(term2) = term2 {
+          // in an operation between an int and a float, the int operand is converted to float
+          tmp = inh.convert(&term.attrs.conversion, tmp);
           let tmp2 = term2.generate_code(inh);
+          let tmp2 = inh.convert(&term2.attrs.conversion, tmp2);
           if let Some(op_numexpression) = op_numexpression {
-            inh.code.push_str(&format!("{} = {} ", tmp, tmp));
-            op_numexpression.generate_code(inh);
-            inh.code.push_str(&format!(" {}\n", tmp2));
+            if op_numexpression.attrs.string_operation {
+              // strings are concatenated by the runtime
+              inh.code.push_str(&format!("param {}\nparam {}\n{} = call str_concat, 2\n", tmp, tmp2, tmp));
+            } else {
+              inh.code.push_str(&format!("{} = {} ", tmp, tmp));
+              op_numexpression.generate_code(inh);
+              inh.code.push_str(&format!(" {}\n", tmp2));
+            }
           }
         } 
         // inh.code.push_str("]\n");
@@ -780,20 +2296,41 @@ impl SemanticNode {
         // PARAMLIST -> (vartype id)+
         String::new()
       },
-      SemanticNodeData::Paramlistcall { paramlist } => {
-        // PARAMLISTCALL -> (id)+
-        for param in paramlist.iter() {
-          inh.code.push_str("param ");
-          param.generate_code(inh);
-          inh.code.push_str("\n");
-        }
-        String::new() // No temporary variable is created for PARAMLISTCALL
+      SemanticNodeData::Paramlistcall { .. } => {
+        // PARAMLISTCALL is handled by FUNCCALL, which knows the parameters passed by reference
+        panic!("PARAMLISTCALL should not appear on generated code");
+      },
+      SemanticNodeData::ParamIndex { .. } => {
+        // PARAMINDEX is handled by FUNCDEF
+        panic!("PARAMINDEX should not appear on generated code");
+      },
+      SemanticNodeData::Initlist { .. } => {
+        // INITLIST is handled by VARDECL
+        panic!("INITLIST should not appear on generated code");
+      },
+      SemanticNodeData::Signature { .. } => {
+        // SIGNATURE is handled by the declarations, through type_name
+        panic!("SIGNATURE should not appear on generated code");
       },
-      SemanticNodeData::Printstat { expression } => {
-        // PRINTSTAT -> EXPRESSION
+      SemanticNodeData::Printstat { expressions } => {
+        // PRINTSTAT -> EXPRESSION (comma EXPRESSION)*
         // converts to TAC version of a print statement
-        // gets the value of the expression
-        let tmp = expression.generate_code(inh);
+        // gets the value of the expressions, converted to strings and joined by the separator when there are several
+        let mut tmp = String::new();
+        for (i, expression) in expressions.iter().enumerate() {
+          let value = expression.generate_code(inh);
+          let value = inh.convert(&expression.attrs.conversion, value);
+          if i == 0 {
+            tmp = value;
+            continue;
+          }
+          if !inh.print_separator.is_empty() {
+            let separator = inh.create_temp();
+            inh.code.push_str(&format!("{} = \"{}\"\n", separator, inh.print_separator));
+            inh.code.push_str(&format!("param {}\nparam {}\n{} = call str_concat, 2\n", tmp, separator, tmp));
+          }
+          inh.code.push_str(&format!("param {}\nparam {}\n{} = call str_concat, 2\n", tmp, value, tmp));
+        }
         // loads as a parameter to the print function
         inh.code.push_str(&format!("param {}\n", tmp));
         // calls the print function
@@ -815,35 +2352,62 @@ impl SemanticNode {
         // READSTAT -> LVALUE
         // converts to TAC version of a read statement
         let tmp = lvalue.generate_code(inh);
-        // call read function for the temporary variable
-        inh.code.push_str(&format!("{} = call read, 0\n", tmp));
+        // the runtime converts the text read to the type of the lvalue
+        let function = match lvalue.attrs.conversion {
+          Some(VarType::Int) => "read_int",
+          Some(VarType::Float) => "read_float",
+          _ => "read_string",
+        };
+        // an array element or a field is stored from a temporary variable, like in an assignment
+        if tmp.contains('[') {
+          let value = inh.create_temp();
+          inh.code.push_str(&format!("{} = call {}, 0\n{} = {}\n", value, function, tmp, value));
+        } else {
+          inh.code.push_str(&format!("{} = call {}, 0\n", tmp, function));
+        }
         tmp
       },
-      SemanticNodeData::Returnstat { .. } => {
+      SemanticNodeData::Returnstat { expression, .. } => {
+        // RETURNSTAT -> kw_return EXPRESSION semicolon
+        // returns the temporary variable with the value of the expression
+        if let Some(expression) = expression {
+          let tmp = expression.generate_code(inh);
+          let tmp = inh.convert(&expression.attrs.conversion, tmp);
+          inh.code.push_str(&format!("return {}\n", tmp));
+          return tmp;
+        }
         // RETURNSTAT -> kw_return semicolon
         inh.code.push_str("return\n");
         String::new() // No temporary variable is created for RETURNSTAT
       },
       SemanticNodeData::Statelist { statelist } => {
         // STATELIST -> STATEMENT+
+        // constants declared in this block, and the names they hide, are not visible after it
+        let (constants, variables, structs, references) = (inh.constants.clone(), inh.variables.clone(), inh.structs.clone(), inh.references.clone());
         let mut tmp = String::new();
         for statement in statelist.iter() {
           tmp = statement.generate_code(inh);
         }
+        (inh.constants, inh.variables, inh.structs, inh.references) = (constants, variables, structs, references);
         // returns the last temporary variable generated
         tmp
       },
-      SemanticNodeData::Statement { vardecl, atribstat, ifstat, forstat, statelist, commandstat } => {
+      SemanticNodeData::Statement { vardecl, atribstat, ifstat, forstat, whilestat, dowhilestat, statelist, commandstat } => {
         // STATEMENT -> VARDECL 
         // STATEMENT -> ATRIBSTAT
         // STATEMENT -> IFSTAT
         // STATEMENT -> FORSTAT
+        // STATEMENT -> WHILESTAT
+        // STATEMENT -> DOWHILESTAT
         // STATEMENT -> STATELIST
         // os comandos são:
         // STATEMENT -> PRINTSTAT
         // STATEMENT -> READSTAT
         // STATEMENT -> RETURNSTAT
+        // STATEMENT -> FUNCCALL
         // STATEMENT -> kw_break
+        // STATEMENT -> kw_continue
+        // STATEMENT -> SWITCHSTAT
         if let Some(vardecl) = vardecl {
           vardecl.generate_code(inh)
         } else if let Some(atribstat) = atribstat {
@@ -852,6 +2416,10 @@ impl SemanticNode {
           ifstat.generate_code(inh)
         } else if let Some(forstat) = forstat {
           forstat.generate_code(inh)
+        } else if let Some(whilestat) = whilestat {
+          whilestat.generate_code(inh)
+        } else if let Some(dowhilestat) = dowhilestat {
+          dowhilestat.generate_code(inh)
         } else if let Some(statelist) = statelist {
           statelist.generate_code(inh)
         } else if let Some(commandstat) = commandstat {
@@ -860,13 +2428,93 @@ impl SemanticNode {
           String::new()
         }
       },
+      SemanticNodeData::Structdef { id, .. } => {
+        // STRUCTDEF -> kw_struct id lbrace FIELDLIST rbrace
+        // declares the size of the struct in words, the unit of every index; its fields are accessed by their offsets
+        let name = id.token().value.as_ref().unwrap().to_string();
+        let size = inh.types.get_struct(&name).unwrap().size;
+        inh.code.push_str(&format!("struct {} {}\n", name, size));
+        String::new()
+      },
+      SemanticNodeData::Switchstat { expression, caselist } => {
+        // SWITCHSTAT -> kw_switch lparenthesis EXPRESSION rparenthesis lbrace CASELIST rbrace
+        let tmp = expression.generate_code(inh);
+        let SemanticNodeData::Caselist { cases } = &caselist.children else { panic!() };
+        // each case starts with a label, and the bodies are placed in order, so a case without break falls through to the next one
+        let end_label = inh.create_label();
+        let case_labels: Vec<String> = cases.iter().map(|_| inh.create_label()).collect();
+        // without a default case, a value that matches no case jumps to the end of the switch
+        let mut default_label = end_label.clone();
+        // the semantic analysis already checked that the case values are constant
+        let mut values: Vec<(ConstType, String)> = vec![];
+        for (case, label) in cases.iter().zip(case_labels.iter()) {
+          let SemanticNodeData::Casestat { value, .. } = &case.children else { panic!() };
+          match value {
+            Some(value) => values.push((value.const_value(&|name: &str| inh.constants.get(name).cloned()).unwrap(), label.clone())),
+            None => default_label = label.clone(),
+          }
+        }
+        let int_values: Option<Vec<i64>> = values.iter()
+          .map(|(value, _)| if let ConstType::Int(value) = value { Some(*value) } else { None })
+          .collect();
+        match int_values {
+          // dense int values: jumps through a table with one entry for each value between the smallest and the largest
+          Some(int_values) if Self::is_dense(&int_values) => {
+            let min = *int_values.iter().min().unwrap();
+            let max = *int_values.iter().max().unwrap();
+            // values outside of the table go to the default case
+            let tmp_below = inh.create_temp();
+            inh.code.push_str(&format!("{} = {} < {}\n", tmp_below, tmp, min));
+            inh.code.push_str(&format!("if {} goto {}\n", tmp_below, default_label));
+            let tmp_above = inh.create_temp();
+            inh.code.push_str(&format!("{} = {} > {}\n", tmp_above, tmp, max));
+            inh.code.push_str(&format!("if {} goto {}\n", tmp_above, default_label));
+            let tmp_offset = inh.create_temp();
+            inh.code.push_str(&format!("{} = {} - {}\n", tmp_offset, tmp, min));
+            // the table is a sequence of gotos, and the offset selects which of them is executed
+            let table_label = inh.create_label();
+            inh.code.push_str(&format!("goto {}[{}]\n", table_label, tmp_offset));
+            inh.code.push_str(&format!("{}:\n", table_label));
+            for entry in min..=max {
+              let target = int_values.iter().position(|value| *value == entry).map_or(&default_label, |index| &values[index].1);
+              inh.code.push_str(&format!("goto {}\n", target));
+            }
+          },
+          // sparse values: compares the expression with each case value, in order
+          _ => {
+            for (value, label) in values.iter() {
+              let tmp_cmp = inh.create_temp();
+              if expression.attrs.string_operation {
+                // strings are compared by the runtime, which returns zero when they are equal
+                inh.code.push_str(&format!("param {}\nparam {}\n{} = call str_compare, 2\n", tmp, value, tmp_cmp));
+                inh.code.push_str(&format!("{} = {} == 0\n", tmp_cmp, tmp_cmp));
+              } else {
+                inh.code.push_str(&format!("{} = {} == {}\n", tmp_cmp, tmp, value));
+              }
+              inh.code.push_str(&format!("if {} goto {}\n", tmp_cmp, label));
+            }
+            inh.code.push_str(&format!("goto {}\n", default_label));
+          },
+        }
+        // break leaves the switch, while continue still targets the enclosing loop
+        inh.push_switch(end_label.clone());
+        for (case, label) in cases.iter().zip(case_labels.iter()) {
+          inh.code.push_str(&format!("{}:\n", label));
+          case.generate_code(inh);
+        }
+        inh.pop_loop();
+        inh.code.push_str(&format!("{}:\n", end_label));
+        String::new() // No temporary variable is created for SWITCHSTAT
+      },
       SemanticNodeData::Term { unaryexpression, op_term, unaryexpression2 } => {
         // TERM -> UNARYEXPRESSION
         // TERM -> UNARYEXPRESSION op_term UNARYEXPRESSION
         // inh.code.push_str(&format!("(\n"));
-        let tmp = unaryexpression.generate_code(inh);
+        let mut tmp = unaryexpression.generate_code(inh);
         if let Some(unaryexpression2) = unaryexpression2 {
+          tmp = inh.convert(&unaryexpression.attrs.conversion, tmp);
           let tmp2 = unaryexpression2.generate_code(inh);
+          let tmp2 = inh.convert(&unaryexpression2.attrs.conversion, tmp2);
           if let Some(op_term) = op_term {
             inh.code.push_str(&format!("{} = {} ", tmp, tmp));
             op_term.generate_code(inh);
@@ -886,17 +2534,33 @@ impl SemanticNode {
           let tmp2 = inh.create_temp();
           inh.code.push_str(&format!("{} = ", tmp2));
           op.generate_code(inh);
-          inh.code.push_str(&format!(" {}", tmp));
+          inh.code.push_str(&format!(" {}\n", tmp));
           tmp2
         } else {
           // If there is no operator, we just return the factor
           tmp
         }
       },
-      SemanticNodeData::Vardecl { .. } => {
+      SemanticNodeData::Vardecl { var_type, id, const_index, init } => {
         //VARDECL -> var_type id
         //VARDECL -> var_type id CONSTINDEX
-        // Vardecl is not used in code generation, 
+        //VARDECL -> var_type id CONSTINDEX INITLIST
+        //VARDECL -> id id [CONSTINDEX]
+        // Vardecl only generates code for the initial values of an array,
+        // but it hides a loop variable (or array parameter) with the same name until the end of the block,
+        // and it gets its own name when it hides a global variable
+        let name = inh.declare_local(&id.token().value.as_ref().unwrap().to_string());
+        let dimensions = Self::const_dimensions(const_index, inh);
+        // the initial values are stored one by one, in row-major order
+        if let Some(init) = init {
+          let SemanticNodeData::Initlist { values } = &init.children else { panic!() };
+          for (position, value) in values.iter().enumerate() {
+            let tmp = value.generate_code(inh);
+            let tmp = inh.convert(&value.attrs.conversion, tmp);
+            inh.code.push_str(&format!("{}{} = {}\n", name, Self::element_index(position, &dimensions), tmp));
+          }
+        }
+        Self::declare_variable(var_type, &name, dimensions, inh);
         String::new()
       },
       SemanticNodeData::VarIndex { index } => {
@@ -909,13 +2573,36 @@ impl SemanticNode {
         }
         output
       },
+      SemanticNodeData::Whilestat { label, condition, body } => {
+        // WHILESTAT -> kw_while lparenthesis EXPRESSION rparenthesis lbrace STATELIST rbrace
+        // creates a loop label, where the condition is tested
+        let loop_start_label = inh.create_label();
+        inh.code.push_str(&format!("{}:\n", loop_start_label));
+        let cond_tmp = condition.generate_code(inh);
+        // leaves the loop when the condition is false
+        let loop_end_label = inh.create_label();
+        inh.code.push_str(&format!("if {} == 0 goto {}\n", cond_tmp, loop_end_label));
+        // break leaves the loop and continue tests the condition again
+        inh.push_loop(label.as_deref().map(SemanticNode::label_name), loop_end_label.clone(), loop_start_label.clone());
+        body.generate_code(inh);
+        inh.pop_loop();
+        inh.code.push_str(&format!("goto {}\n", loop_start_label));
+        inh.code.push_str(&format!("{}:\n", loop_end_label));
+        cond_tmp
+      },
       SemanticNodeData::Terminal { value: token } => {
         match token.token_type {
-          TokenType::Id | TokenType::FuncId => {
+          TokenType::Id => {
             let t = format!("{}", token.value.as_ref().unwrap().to_string());
             inh.code.push_str(&t);
             t
           },
+          TokenType::FuncId => {
+            // an overloaded function is named after the definition chosen by the semantic analysis
+            let t = self.function_name();
+            inh.code.push_str(&t);
+            t
+          },
           TokenType::ConstInt | TokenType::ConstFloat | TokenType::ConstString => {
             let val =format!("{}\n", token.value.as_ref().unwrap());
             inh.code.push_str(&val);
@@ -942,11 +2629,6 @@ impl SemanticNode {
             inh.code.push_str(&val);
             val
           },
-          TokenType::KwBreak => {
-            let br = inh.get_scope_label();
-            inh.code.push_str(&format!("goto {}\n", br));
-            String::new()
-          },
           TokenType::KwNew => {
             // This is usually handled in the AllocExpression node
             panic!("New keyword should not appear on generated code");
@@ -972,7 +2654,7 @@ impl SemanticNode {
     match &self.children {
       SemanticNodeData::Allocexpression { var_type, dimensions } => {        
         let name = format!("{}", count);
-        output.push_str(&format!("  {} [label=\"AllocExpression\"]\n", name,));
+        output.push_str(&format!("  {} [label=\"AllocExpression\\n{}\"]\n", name, self.span));
         output.push_str(&format!("  {} -> {}\n", name, *count+1));
         *count += 1;
         var_type.save(output, count);
@@ -980,19 +2662,24 @@ impl SemanticNode {
         *count += 1;
         dimensions.save(output, count);
       },
-      SemanticNodeData::Atribstat { lvalue, value } => {
+      SemanticNodeData::Atribstat { lvalue, op, value } => {
         let name = format!("{}", count);
-        output.push_str(&format!("  {} [label=\"AtribStatement\"]\n", count));
+        output.push_str(&format!("  {} [label=\"AtribStatement\\n{}\"]\n", count, self.span));
         output.push_str(&format!("  {} -> {}\n", name, *count+1));
         *count += 1;
         lvalue.save(output, count);
+        if let Some(op) = op {
+          output.push_str(&format!("  {} -> {}\n", name, *count+1));
+          *count += 1;
+          op.save(output, count);
+        }
         output.push_str(&format!("  {} -> {}\n", name, *count+1));
         *count += 1;
         value.save(output, count);
       },
-      SemanticNodeData::Atribstatevalue { expression, allocexpression, funccall } => {
+      SemanticNodeData::Atribstatevalue { expression, allocexpression } => {
         let name = format!("{}", count);
-        output.push_str(&format!("  {} [label=\"AtribStatementValue\"]\n", count));
+        output.push_str(&format!("  {} [label=\"AtribStatementValue\\n{}\"]\n", count, self.span));
         if let Some(expression) = expression {
           output.push_str(&format!("  {} -> {}\n", name, *count+1));
           *count += 1;
@@ -1001,36 +2688,112 @@ impl SemanticNode {
         if let Some(allocexpression) = allocexpression {
           output.push_str(&format!("  {} -> {}\n", name, *count+1));
           *count += 1;
-          allocexpression.save(output, count);
+          allocexpression.save(output, count);
+        }
+      },
+      SemanticNodeData::Caselist { cases } => {
+        let name = format!("{}", count);
+        output.push_str(&format!("  {} [label=\"CaseList\\n{}\"]\n", count, self.span));
+        for case in cases.iter() {
+          output.push_str(&format!("  {} -> {}\n", name, *count+1));
+          *count += 1;
+          case.save(output, count);
+        }
+      },
+      SemanticNodeData::Casestat { value, statelist } => {
+        let name = format!("{}", count);
+        let statement = if value.is_some() { "CaseStatement" } else { "DefaultStatement" };
+        output.push_str(&format!("  {} [label=\"{}\\n{}\"]\n", count, statement, self.span));
+        if let Some(value) = value {
+          output.push_str(&format!("  {} -> {}\n", name, *count+1));
+          *count += 1;
+          value.save(output, count);
+        }
+        if let Some(statelist) = statelist {
+          output.push_str(&format!("  {} -> {}\n", name, *count+1));
+          *count += 1;
+          statelist.save(output, count);
+        }
+      },
+      SemanticNodeData::Castexpression { var_type, factor } => {
+        let name = format!("{}", count);
+        output.push_str(&format!("  {} [label=\"CastExpression\\n{}\"]\n", count, self.span));
+        output.push_str(&format!("  {} -> {}\n", name, *count+1));
+        *count += 1;
+        var_type.save(output, count);
+        output.push_str(&format!("  {} -> {}\n", name, *count+1));
+        *count += 1;
+        factor.save(output, count);
+      },
+      SemanticNodeData::Condexpression { condition, then_expression, else_expression } => {
+        let name = format!("{}", count);
+        output.push_str(&format!("  {} [label=\"ConditionalExpression\\n{}\"]\n", count, self.span));
+        output.push_str(&format!("  {} -> {}\n", name, *count+1));
+        *count += 1;
+        condition.save(output, count);
+        output.push_str(&format!("  {} -> {}\n", name, *count+1));
+        *count += 1;
+        then_expression.save(output, count);
+        output.push_str(&format!("  {} -> {}\n", name, *count+1));
+        *count += 1;
+        else_expression.save(output, count);
+      },
+      SemanticNodeData::Constant { value, .. } => {
+        output.push_str(&format!("  {} [label=\"{:?}\\n{}\"]\n", count, value, self.span));
+      },
+      SemanticNodeData::ConstIndex { index } => {
+        let name = format!("{}", count);
+        output.push_str(&format!("  {} [label=\"ConstIndex\\n{}\"]\n", count, self.span));
+        for i in index.iter() {
+          output.push_str(&format!("  {} -> {}\n", name, *count+1));
+          *count += 1;
+          i.save(output, count);
         }
-        if let Some(funccall) = funccall {
+      },
+      SemanticNodeData::Dowhilestat { label, body, condition } => {
+        let name = format!("{}", count);
+        output.push_str(&format!("  {} [label=\"DoWhileStatement\\n{}\"]\n", count, self.span));
+        if let Some(label) = label {
           output.push_str(&format!("  {} -> {}\n", name, *count+1));
           *count += 1;
-          funccall.save(output, count);
+          label.save(output, count);
         }
+        output.push_str(&format!("  {} -> {}\n", name, *count+1));
+        *count += 1;
+        body.save(output, count);
+        output.push_str(&format!("  {} -> {}\n", name, *count+1));
+        *count += 1;
+        condition.save(output, count);
       },
-      SemanticNodeData::Constant { value, .. } => {
-        output.push_str(&format!("  {} [label=\"{:?}\"]\n", count, value));
+      SemanticNodeData::Enumdef { id, enumlist } => {
+        let name = format!("{}", count);
+        output.push_str(&format!("  {} [label=\"EnumDef\\n{}\"]\n", count, self.span));
+        output.push_str(&format!("  {} -> {}\n", name, *count+1));
+        *count += 1;
+        id.save(output, count);
+        output.push_str(&format!("  {} -> {}\n", name, *count+1));
+        *count += 1;
+        enumlist.save(output, count);
       },
-      SemanticNodeData::ConstIndex { index } => {
+      SemanticNodeData::Enumlist { enumerators } => {
         let name = format!("{}", count);
-        output.push_str(&format!("  {} [label=\"ConstIndex\"]\n", count));
-        for i in index.iter() {
+        output.push_str(&format!("  {} [label=\"EnumList\\n{}\"]\n", count, self.span));
+        for enumerator in enumerators.iter() {
           output.push_str(&format!("  {} -> {}\n", name, *count+1));
           *count += 1;
-          i.save(output, count);
+          enumerator.save(output, count);
         }
       },
       SemanticNodeData::Elsestat { statement } => {
         let name = format!("{}", count);
-        output.push_str(&format!("  {} [label=\"ElseStatement\"]\n", count));
+        output.push_str(&format!("  {} [label=\"ElseStatement\\n{}\"]\n", count, self.span));
         output.push_str(&format!("  {} -> {}\n", name, *count+1));
         *count += 1;
         statement.save(output, count);
       },
       SemanticNodeData::Expression { numexpression, numexpression2, op_expression } => {
         let name = format!("{}", count);
-        output.push_str(&format!("  {} [label=\"Expression\"]\n", count,));
+        output.push_str(&format!("  {} [label=\"Expression\\n{}\"]\n", count, self.span));
         output.push_str(&format!("  {} -> {}\n", name, *count+1));
         *count += 1;
         numexpression.save(output, count);
@@ -1045,9 +2808,9 @@ impl SemanticNode {
           numexpression2.save(output, count);
         }
       },
-      SemanticNodeData::Factor { expression, lvalue, constant } => {
+      SemanticNodeData::Factor { expression, lvalue, constant, funccall } => {
         let name = format!("{}", count);
-        output.push_str(&format!("  {} [label=\"Factor\"]\n", count));
+        output.push_str(&format!("  {} [label=\"Factor\\n{}\"]\n", count, self.span));
         if let Some(expression) = expression {
           output.push_str(&format!("  {} -> {}\n", name, *count+1));
           *count += 1;
@@ -1063,10 +2826,42 @@ impl SemanticNode {
           *count += 1;
           constant.save(output, count);
         }
+        if let Some(funccall) = funccall {
+          output.push_str(&format!("  {} -> {}\n", name, *count+1));
+          *count += 1;
+          funccall.save(output, count);
+        }
+      },
+      SemanticNodeData::Fieldlist { fields } => {
+        let name = format!("{}", count);
+        output.push_str(&format!("  {} [label=\"FieldList\\n{}\"]\n", count, self.span));
+        for field in fields.iter() {
+          output.push_str(&format!("  {} -> {}\n", name, *count+1));
+          *count += 1;
+          field.save(output, count);
+        }
       },
-      SemanticNodeData::Forstat { init, condition, increment, body } => {
+      SemanticNodeData::Forinit { var_type, id, value } => {
         let name = format!("{}", count);
-        output.push_str(&format!("  {} [label=\"ForStatement\"]\n", count));
+        output.push_str(&format!("  {} [label=\"ForInit\\n{}\"]\n", count, self.span));
+        output.push_str(&format!("  {} -> {}\n", name, *count+1));
+        *count += 1;
+        var_type.save(output, count);
+        output.push_str(&format!("  {} -> {}\n", name, *count+1));
+        *count += 1;
+        id.save(output, count);
+        output.push_str(&format!("  {} -> {}\n", name, *count+1));
+        *count += 1;
+        value.save(output, count);
+      },
+      SemanticNodeData::Forstat { label, init, condition, increment, body } => {
+        let name = format!("{}", count);
+        output.push_str(&format!("  {} [label=\"ForStatement\\n{}\"]\n", count, self.span));
+        if let Some(label) = label {
+          output.push_str(&format!("  {} -> {}\n", name, *count+1));
+          *count += 1;
+          label.save(output, count);
+        }
         output.push_str(&format!("  {} -> {}\n", name, *count+1));
         *count += 1;
         init.save(output, count);
@@ -1080,9 +2875,28 @@ impl SemanticNode {
         *count += 1;
         body.save(output, count);
       },
+      SemanticNodeData::Funcref { id } => {
+        let name = format!("{}", count);
+        output.push_str(&format!("  {} [label=\"FuncRef\\n{}\"]\n", count, self.span));
+        output.push_str(&format!("  {} -> {}\n", name, *count+1));
+        *count += 1;
+        id.save(output, count);
+      },
+      SemanticNodeData::Signature { params, return_type } => {
+        let name = format!("{}", count);
+        output.push_str(&format!("  {} [label=\"Signature\\n{}\"]\n", count, self.span));
+        for param in params.iter() {
+          output.push_str(&format!("  {} -> {}\n", name, *count+1));
+          *count += 1;
+          param.save(output, count);
+        }
+        output.push_str(&format!("  {} -> {}\n", name, *count+1));
+        *count += 1;
+        return_type.save(output, count);
+      },
       SemanticNodeData::Funccall { id, paramlistcall } => {
         let name = format!("{}", count);
-        output.push_str(&format!("  {} [label=\"FuncCall\"]\n", count));
+        output.push_str(&format!("  {} [label=\"FuncCall\\n{}\"]\n", count, self.span));
         output.push_str(&format!("  {} -> {}\n", name, *count+1));
         *count += 1;
         id.save(output, count);
@@ -1092,9 +2906,14 @@ impl SemanticNode {
           paramlistcall.save(output, count);
         }
       },
-      SemanticNodeData::Funcdef { func_id, paramlist, statelist } => {
+      SemanticNodeData::Funcdef { return_type, func_id, paramlist, statelist } => {
         let name = format!("{}", count);
-        output.push_str(&format!("  {} [label=\"FuncDef\"]\n", count));
+        output.push_str(&format!("  {} [label=\"FuncDef\\n{}\"]\n", count, self.span));
+        if let Some(return_type) = return_type {
+          output.push_str(&format!("  {} -> {}\n", name, *count+1));
+          *count += 1;
+          return_type.save(output, count);
+        }
         output.push_str(&format!("  {} -> {}\n", name, *count+1));
         *count += 1;
         func_id.save(output, count);
@@ -1109,7 +2928,7 @@ impl SemanticNode {
       },
       SemanticNodeData::Funclist { funclist } => {
         let name = format!("{}", count);
-        output.push_str(&format!("  {} [label=\"FuncList\"]\n", count));
+        output.push_str(&format!("  {} [label=\"FuncList\\n{}\"]\n", count, self.span));
         for func in funclist.iter() {
           output.push_str(&format!("  {} -> {}\n", name, *count+1));
           *count += 1;
@@ -1118,7 +2937,7 @@ impl SemanticNode {
       },
       SemanticNodeData::Ifstat { condition, then_branch, else_branch } => {
         let name = format!("{}", count);
-        output.push_str(&format!("  {} [label=\"IfStatement\"]\n", name));
+        output.push_str(&format!("  {} [label=\"IfStatement\\n{}\"]\n", name, self.span));
         output.push_str(&format!("  {} -> {}\n", name, *count+1));
         *count += 1;
         condition.save(output, count);
@@ -1131,9 +2950,19 @@ impl SemanticNode {
           else_branch.save(output, count);
         }
       },
-      SemanticNodeData::Lvalue { id, var_index } => {
+      SemanticNodeData::Jumpstat { token, label } => {
+        let name = format!("{}", count);
+        let statement = if token.token_type == TokenType::KwBreak { "BreakStatement" } else { "ContinueStatement" };
+        output.push_str(&format!("  {} [label=\"{}\\n{}\"]\n", count, statement, self.span));
+        if let Some(label) = label {
+          output.push_str(&format!("  {} -> {}\n", name, *count+1));
+          *count += 1;
+          label.save(output, count);
+        }
+      },
+      SemanticNodeData::Lvalue { id, var_index, field } => {
         let name = format!("{}", count);
-        output.push_str(&format!("  {} [label=\"LValue\"]\n", count));
+        output.push_str(&format!("  {} [label=\"LValue\\n{}\"]\n", count, self.span));
         output.push_str(&format!("  {} -> {}\n", name, *count+1));
         *count += 1;
         id.save(output, count);
@@ -1142,10 +2971,15 @@ impl SemanticNode {
           *count += 1;
           var_index.save(output, count);
         }
+        if let Some(field) = field {
+          output.push_str(&format!("  {} -> {}\n", name, *count+1));
+          *count += 1;
+          field.save(output, count);
+        }
       },
       SemanticNodeData::Numexpression { term, op_numexpression, term2 } => {
         let name = format!("{}", count);
-        output.push_str(&format!("  {} [label=\"Numexpression\"]\n", count));
+        output.push_str(&format!("  {} [label=\"Numexpression\\n{}\"]\n", count, self.span));
         output.push_str(&format!("  {} -> {}\n", name, *count+1));
         *count += 1;
         term.save(output, count);
@@ -1162,42 +2996,53 @@ impl SemanticNode {
         }
       },
       SemanticNodeData::OpExpression { op } => {
-        output.push_str(&format!("  {} [label=\"{:?}\"]\n", count, op));
+        output.push_str(&format!("  {} [label=\"{:?}\\n{}\"]\n", count, op, self.span));
       },
       SemanticNodeData::OpNumexpression { op } => {
-        output.push_str(&format!("  {} [label=\"{:?}\"]\n", count, op));
+        output.push_str(&format!("  {} [label=\"{:?}\\n{}\"]\n", count, op, self.span));
       },
       SemanticNodeData::OpTerm { op } => {
-        output.push_str(&format!("  {} [label=\"{:?}\"]\n", count, op));
+        output.push_str(&format!("  {} [label=\"{:?}\\n{}\"]\n", count, op, self.span));
       },
       SemanticNodeData::Paramlist { paramlist } => {
         let name = format!("{}", count);
-        output.push_str(&format!("  {} [label=\"ParamList\"]\n", count));
+        output.push_str(&format!("  {} [label=\"ParamList\\n{}\"]\n", count, self.span));
         for param in paramlist.iter() {
           output.push_str(&format!("  {} -> {}\n", name, *count+1));
           *count += 1;
           param.save(output, count);
         }
       },
+      SemanticNodeData::ParamIndex { const_index } => {
+        let name = format!("{}", count);
+        output.push_str(&format!("  {} [label=\"ParamIndex\\n{}\"]\n", count, self.span));
+        if let Some(const_index) = const_index {
+          output.push_str(&format!("  {} -> {}\n", name, *count+1));
+          *count += 1;
+          const_index.save(output, count);
+        }
+      },
       SemanticNodeData::Paramlistcall { paramlist } => {
         let name = format!("{}", count);
-        output.push_str(&format!("  {} [label=\"ParamListCall\"]\n", count));
+        output.push_str(&format!("  {} [label=\"ParamListCall\\n{}\"]\n", count, self.span));
         for param in paramlist.iter() {
           output.push_str(&format!("  {} -> {}\n", name, *count+1));
           *count += 1;
           param.save(output, count);
         }
       },
-      SemanticNodeData::Printstat { expression } => {
+      SemanticNodeData::Printstat { expressions } => {
         let name = format!("{}", count);
-        output.push_str(&format!("  {} [label=\"PrintStatement\"]\n", count));
-        output.push_str(&format!("  {} -> {}\n", name, *count+1));
-        *count += 1;
-        expression.save(output, count);
+        output.push_str(&format!("  {} [label=\"PrintStatement\\n{}\"]\n", count, self.span));
+        for expression in expressions.iter() {
+          output.push_str(&format!("  {} -> {}\n", name, *count+1));
+          *count += 1;
+          expression.save(output, count);
+        }
       },
       SemanticNodeData::Program { funclist, statement } => {
         let name = format!("{}", count);
-        output.push_str(&format!("  {} [label=\"Program\"]\n", count));
+        output.push_str(&format!("  {} [label=\"Program\\n{}\"]\n", count, self.span));
         if let Some(funclist) = funclist {
           output.push_str(&format!("  {} -> {}\n", name, *count+1));
           *count += 1;
@@ -1211,26 +3056,32 @@ impl SemanticNode {
       },
       SemanticNodeData::Readstat { lvalue } => {
         let name = format!("{}", count);
-        output.push_str(&format!("  {} [label=\"ReadStatement\"]\n", name));
+        output.push_str(&format!("  {} [label=\"ReadStatement\\n{}\"]\n", name, self.span));
         output.push_str(&format!("  {} -> {}\n", name, *count+1));
         *count += 1;
         lvalue.save(output, count);
       },
-      SemanticNodeData::Returnstat { .. } => {
-        output.push_str(&format!("  {} [label=\"ReturnStatement\"]\n", count));
+      SemanticNodeData::Returnstat { expression, .. } => {
+        let name = format!("{}", count);
+        output.push_str(&format!("  {} [label=\"ReturnStatement\\n{}\"]\n", count, self.span));
+        if let Some(expression) = expression {
+          output.push_str(&format!("  {} -> {}\n", name, *count+1));
+          *count += 1;
+          expression.save(output, count);
+        }
       },
       SemanticNodeData::Statelist { statelist } => {
         let name = format!("{}", count);
-        output.push_str(&format!("  {} [label=\"StateList\"]\n", count));
+        output.push_str(&format!("  {} [label=\"StateList\\n{}\"]\n", count, self.span));
         for statement in statelist.iter() {
           output.push_str(&format!("  {} -> {}\n", name, *count+1));
           *count += 1;
           statement.save(output, count);
         }
       },
-      SemanticNodeData::Statement { vardecl, atribstat, ifstat, forstat, statelist, commandstat } => {
+      SemanticNodeData::Statement { vardecl, atribstat, ifstat, forstat, whilestat, dowhilestat, statelist, commandstat } => {
         let name = format!("{}", count);
-        output.push_str(&format!("  {} [label=\"Statement\"]\n", count));
+        output.push_str(&format!("  {} [label=\"Statement\\n{}\"]\n", count, self.span));
         if let Some(vardecl) = vardecl {
           output.push_str(&format!("  {} -> {}\n", name, *count+1));
           *count += 1;
@@ -1251,6 +3102,16 @@ impl SemanticNode {
           *count += 1;
           forstat.save(output, count);
         }
+        if let Some(whilestat) = whilestat {
+          output.push_str(&format!("  {} -> {}\n", name, *count+1));
+          *count += 1;
+          whilestat.save(output, count);
+        }
+        if let Some(dowhilestat) = dowhilestat {
+          output.push_str(&format!("  {} -> {}\n", name, *count+1));
+          *count += 1;
+          dowhilestat.save(output, count);
+        }
         if let Some(statelist) = statelist {
           output.push_str(&format!("  {} -> {}\n", name, *count+1));
           *count += 1;
@@ -1262,9 +3123,29 @@ impl SemanticNode {
           commandstat.save(output, count);
         }
       },
+      SemanticNodeData::Structdef { id, fieldlist } => {
+        let name = format!("{}", count);
+        output.push_str(&format!("  {} [label=\"StructDef\\n{}\"]\n", count, self.span));
+        output.push_str(&format!("  {} -> {}\n", name, *count+1));
+        *count += 1;
+        id.save(output, count);
+        output.push_str(&format!("  {} -> {}\n", name, *count+1));
+        *count += 1;
+        fieldlist.save(output, count);
+      },
+      SemanticNodeData::Switchstat { expression, caselist } => {
+        let name = format!("{}", count);
+        output.push_str(&format!("  {} [label=\"SwitchStatement\\n{}\"]\n", count, self.span));
+        output.push_str(&format!("  {} -> {}\n", name, *count+1));
+        *count += 1;
+        expression.save(output, count);
+        output.push_str(&format!("  {} -> {}\n", name, *count+1));
+        *count += 1;
+        caselist.save(output, count);
+      },
       SemanticNodeData::Term { unaryexpression, op_term, unaryexpression2 } => {
         let name = format!("{}", count);
-        output.push_str(&format!("  {} [label=\"Term\"]\n", count));
+        output.push_str(&format!("  {} [label=\"Term\\n{}\"]\n", count, self.span));
         output.push_str(&format!("  {} -> {}\n", name, *count+1));
         *count += 1;
         unaryexpression.save(output, count);
@@ -1281,7 +3162,7 @@ impl SemanticNode {
       },
       SemanticNodeData::Unaryexpression { op, factor } => {
         let name = format!("{}", count);
-        output.push_str(&format!("  {} [label=\"Unaryexpression\"]\n", count));
+        output.push_str(&format!("  {} [label=\"Unaryexpression\\n{}\"]\n", count, self.span));
         if let Some(op) = op {
           output.push_str(&format!("  {} -> {}\n", name, *count+1));
           *count += 1;
@@ -1291,9 +3172,9 @@ impl SemanticNode {
         *count += 1;
         factor.save(output, count);
       },
-      SemanticNodeData::Vardecl { var_type, id, const_index } => {
+      SemanticNodeData::Vardecl { var_type, id, const_index, init } => {
         let name = format!("{}", count);
-        output.push_str(&format!("  {} [label=\"Vardecl\"]\n", count));
+        output.push_str(&format!("  {} [label=\"Vardecl\\n{}\"]\n", count, self.span));
         output.push_str(&format!("  {} -> {}\n", name, *count+1));
         *count += 1;
         var_type.save(output, count);
@@ -1305,25 +3186,114 @@ impl SemanticNode {
           *count += 1;
           const_index.save(output, count);
         }
+        if let Some(init) = init {
+          output.push_str(&format!("  {} -> {}\n", name, *count+1));
+          *count += 1;
+          init.save(output, count);
+        }
+      },
+      SemanticNodeData::Initlist { values } => {
+        let name = format!("{}", count);
+        output.push_str(&format!("  {} [label=\"InitList\\n{}\"]\n", count, self.span));
+        for value in values.iter() {
+          output.push_str(&format!("  {} -> {}\n", name, *count+1));
+          *count += 1;
+          value.save(output, count);
+        }
+      },
+      SemanticNodeData::Interpolation { segments } => {
+        let name = format!("{}", count);
+        output.push_str(&format!("  {} [label=\"Interpolation\\n{}\"]\n", count, self.span));
+        for segment in segments.iter() {
+          output.push_str(&format!("  {} -> {}\n", name, *count+1));
+          *count += 1;
+          segment.save(output, count);
+        }
+      },
+      SemanticNodeData::Constdecl { var_type, id, value } => {
+        let name = format!("{}", count);
+        output.push_str(&format!("  {} [label=\"Constdecl\\n{}\"]\n", count, self.span));
+        output.push_str(&format!("  {} -> {}\n", name, *count+1));
+        *count += 1;
+        var_type.save(output, count);
+        output.push_str(&format!("  {} -> {}\n", name, *count+1));
+        *count += 1;
+        id.save(output, count);
+        output.push_str(&format!("  {} -> {}\n", name, *count+1));
+        *count += 1;
+        value.save(output, count);
       },
       SemanticNodeData::VarIndex { index } => {
         let name = format!("{}", count);
-        output.push_str(&format!("  {} [label=\"VarIndex\"]\n", count));
+        output.push_str(&format!("  {} [label=\"VarIndex\\n{}\"]\n", count, self.span));
         for i in index.iter() {
           output.push_str(&format!("  {} -> {}\n", name, *count+1));
           *count += 1;
           i.save(output, count);
         }
       },
+      SemanticNodeData::Whilestat { label, condition, body } => {
+        let name = format!("{}", count);
+        output.push_str(&format!("  {} [label=\"WhileStatement\\n{}\"]\n", count, self.span));
+        if let Some(label) = label {
+          output.push_str(&format!("  {} -> {}\n", name, *count+1));
+          *count += 1;
+          label.save(output, count);
+        }
+        output.push_str(&format!("  {} -> {}\n", name, *count+1));
+        *count += 1;
+        condition.save(output, count);
+        output.push_str(&format!("  {} -> {}\n", name, *count+1));
+        *count += 1;
+        body.save(output, count);
+      },
       SemanticNodeData::Terminal { value: token } => {
         if let Some(value) = &token.value {
           let nome = format!("{:?}", value).replace("\"", "\\\"");
-          output.push_str(&format!("  {} [label=\"{}\"]\n", count, nome));
+          output.push_str(&format!("  {} [label=\"{}\\n{}\"]\n", count, nome, self.span));
         }
       }
     }
   }
 
+  /// Raiz da árvore de expressão de um nó EXPRESSION, sem inseri-la no vetor de árvores.
+  /// Os ramos de uma expressão condicional fazem parte da mesma árvore.
+  fn expression_tree_root(&self, trees: &mut Vec<ExpressionTree>) -> ExpressionTreeNode {
+    match &self.children {
+      // EXPRESSION -> EXPRESSION question EXPRESSION colon EXPRESSION
+      //  EXPRESSION.ptr = NODE(?:, EXPRESSION_1.ptr, EXPRESSION_2.ptr, EXPRESSION_3.ptr)
+      SemanticNodeData::Condexpression { condition, then_expression, else_expression } => {
+        ExpressionTreeNode::TernaryOperator {
+          condition: Box::new(condition.expression_tree_root(trees)),
+          then_branch: Box::new(then_expression.expression_tree_root(trees)),
+          else_branch: Box::new(else_expression.expression_tree_root(trees)),
+        }
+      },
+      SemanticNodeData::Expression { numexpression, op_expression, numexpression2 } => {
+        match op_expression {
+          // EXPRESSION -> NUMEXPRESSION op_expression numexpression2 
+          //  EXPRESSION_1.ptr = NODE(op_expression.op, NUMEXPRESSION.ptr, NUMEXPRESSION2.ptr)
+          Some(op_expression) => {
+            let n1 = numexpression.create_expression_tree(trees).unwrap();
+            let n2 = numexpression2.clone().unwrap().create_expression_tree(trees).unwrap();
+            let SemanticNodeData::OpExpression { op } = op_expression.children else { panic!(); }; 
+            ExpressionTreeNode::BinaryOperator { 
+              operator: op.get_operator_type(),
+              left: Box::new(n1),
+              right: Box::new(n2)
+            }
+          }
+          // EXPRESSION -> NUMEXPRESSION 
+          //   EXPRESSION.ptr -> NUMEXPRESSION.ptr 
+          None => {
+            numexpression.create_expression_tree(trees).unwrap()
+          }
+        }
+      },
+      _ => panic!(),
+    }
+  }
+
   fn create_expression_tree(&self, trees: &mut Vec<ExpressionTree>) -> Option<ExpressionTreeNode> {
     match &self.children {
       SemanticNodeData::Atribstat { value, .. } => {
@@ -1335,6 +3305,16 @@ impl SemanticNode {
         if let Some(expression) = expression { expression.create_expression_tree(trees); None }
         else { None }
       },
+      SemanticNodeData::Caselist { cases } => {
+        for case in cases.iter() { case.create_expression_tree(trees); }
+        None
+      },
+      SemanticNodeData::Casestat { statelist, .. } => {
+        // CASESTAT -> kw_case NUMEXPRESSION colon [STATELIST]
+        //  Assim como os índices, o valor do caso não gera uma árvore de expressão
+        if let Some(statelist) = statelist { statelist.create_expression_tree(trees); }
+        None
+      },
       SemanticNodeData::Constant { value, .. } => {
         // CONSTANT -> const_int | const_float | const_string 
         // CONSTANT.ptr = const.ptr
@@ -1345,39 +3325,34 @@ impl SemanticNode {
           ConstType::VarType(_) => { panic!() },
         }
       },
+      SemanticNodeData::Castexpression { factor, .. } => {
+        // CASTEXPRESSION -> var_type FACTOR
+        //    CASTEXPRESSION.ptr = FACTOR.ptr
+        factor.create_expression_tree(trees)
+      },
+      SemanticNodeData::Constdecl { value, .. } => {
+        value.create_expression_tree(trees);
+        None
+      },
+      SemanticNodeData::Dowhilestat { body, condition, .. } => {
+        body.create_expression_tree(trees);
+        condition.create_expression_tree(trees);
+        None
+      },
       SemanticNodeData::Elsestat { statement } => {
         statement.create_expression_tree(trees);
         None
       },
-      SemanticNodeData::Expression { numexpression, op_expression, numexpression2 } => {
-        // Expression -> NumExpression | NumExpression OpExpression NumExpression
+      SemanticNodeData::Expression { .. } | SemanticNodeData::Condexpression { .. } => {
+        // Expression -> NumExpression | NumExpression OpExpression NumExpression | Expression ? Expression : Expression
         // Nesse nodo, é criada a raiz da árvore de expressão, e a árvore é inserida no vetor de árvores para retorno da função
         // Em todos os outros nodos, são criados e retornados os outros nodos da árvore.
-
-        let root = match op_expression {
-          // EXPRESSION -> NUMEXPRESSION op_expression numexpression2 
-          //  EXPRESSION_1.ptr = NODE(op_expression.op, NUMEXPRESSION.ptr, NUMEXPRESSION2.ptr)
-          Some(op_expression) => {
-            let n1 = numexpression.create_expression_tree(trees).unwrap();
-            let n2 = numexpression2.clone().unwrap().create_expression_tree(trees).unwrap();
-            let SemanticNodeData::OpExpression { op } = op_expression.children else { panic!(); }; 
-            ExpressionTreeNode::BinaryOperator { 
-              operator: op.get_operator_type(),
-              left: Box::new(n1),
-              right: Box::new(n2)
-            }
-          }
-          // EXPRESSION -> NUMEXPRESSION 
-          //   EXPRESSION.ptr -> NUMEXPRESSION.ptr 
-          None => {
-            numexpression.create_expression_tree(trees).unwrap()
-          }
-        };
+        let root = self.expression_tree_root(trees);
         let tree = ExpressionTree { root };
         trees.push(tree);
         None
       },
-      SemanticNodeData::Factor { expression, lvalue, constant } => {
+      SemanticNodeData::Factor { expression, lvalue, constant, funccall } => {
         let node;
         // FACTOR -> EXPRESSION
         //  FACTOR.ptr = expression.ptr
@@ -1393,22 +3368,50 @@ impl SemanticNode {
         //  FACTOR.ptr = constant.ptr
         else if let Some(constant) = constant {
           node = constant.create_expression_tree(trees);
+        }
+        // FACTOR -> FUNCCALL
+        //  FACTOR.ptr = funccall.ptr
+        else if let Some(funccall) = funccall {
+          node = funccall.create_expression_tree(trees);
         } else {
           panic!();
         }
         node
       },
-      SemanticNodeData::Forstat { init, condition, increment, body } => {
+      SemanticNodeData::Forinit { value, .. } => {
+        value.create_expression_tree(trees);
+        None
+      },
+      SemanticNodeData::Forstat { init, condition, increment, body, .. } => {
         init.create_expression_tree(trees);
         condition.create_expression_tree(trees);
         increment.create_expression_tree(trees);
         body.create_expression_tree(trees);
         None
       },
+      SemanticNodeData::Funccall { id, paramlistcall } => {
+        // FUNCCALL -> func_id PARAMLISTCALL
+        //  FUNCCALL.ptr = NODE(operando, func_id.value)
+        // Cada argumento é uma expressão com a sua própria árvore
+        // FUNCCALL -> LVALUE PARAMLISTCALL
+        //  FUNCCALL.ptr = LVALUE.ptr
+        if let Some(paramlistcall) = paramlistcall { paramlistcall.create_expression_tree(trees); }
+        let SemanticNodeData::Terminal { value: id_node } = &id.children else { return id.create_expression_tree(trees); };
+        if let Some(ConstType::String(func_name)) = &id_node.value {
+          Some(ExpressionTreeNode::Operand { value: Operand::Identifier(func_name.clone()) })
+        } else {
+          panic!("Expected function identifier in FuncCall");
+        }
+      },
       SemanticNodeData::Funcdef { statelist, .. } => {
         statelist.create_expression_tree(trees);
         None
       },
+      SemanticNodeData::Funcref { id } => {
+        // FACTOR -> func_id
+        //  FACTOR.ptr = NODE(operando, func_id.value)
+        Some(ExpressionTreeNode::Operand { value: Operand::Identifier(id.token().value.as_ref().unwrap().to_string()) })
+      },
       SemanticNodeData::Funclist { funclist } => {
         for func in funclist.iter() { func.create_expression_tree(trees); }
         None
@@ -1421,18 +3424,24 @@ impl SemanticNode {
         }
         None
       },
-      SemanticNodeData::Lvalue { id, var_index } => {
+      SemanticNodeData::Lvalue { id, var_index, field } => {
         // LVALUE -> id
         // LVALUE -> id VARINDEX
         //    LVALUE.ptr = id.ptr
+        // LVALUE -> id VARINDEX LVALUE
+        //    LVALUE_1.ptr = id.ptr, com o caminho dos campos acessados (p.pos.x)
         if let Some(var_index) = var_index { var_index.create_expression_tree(trees); }
         let SemanticNodeData::Terminal { value: id_node } = &id.children else { panic!(); };
 
-        if let Some(ConstType::String(id_name)) = &id_node.value {
-          Some(ExpressionTreeNode::Operand { value: Operand::Identifier(id_name.clone())})
-        } else {
+        let Some(ConstType::String(id_name)) = &id_node.value else {
           panic!("Expected variable identifier in LValue");
+        };
+        let mut path = id_name.clone();
+        if let Some(field) = field {
+          let Some(ExpressionTreeNode::Operand { value: Operand::Identifier(field_path) }) = field.create_expression_tree(trees) else { panic!(); };
+          path = format!("{}.{}", path, field_path);
         }
+        Some(ExpressionTreeNode::Operand { value: Operand::Identifier(path)})
       },
       SemanticNodeData::Numexpression { term, op_numexpression, term2 } => {
         let root = match op_numexpression {
@@ -1458,8 +3467,42 @@ impl SemanticNode {
         };
         Some(root)
       },
-      SemanticNodeData::Printstat { expression } => {
-        expression.create_expression_tree(trees);
+      SemanticNodeData::Paramlistcall { paramlist } => {
+        for param in paramlist.iter() { param.create_expression_tree(trees); }
+        None
+      },
+      SemanticNodeData::Vardecl { init: Some(init), .. } => {
+        // VARDECL -> var_type id CONST_INDEX INITLIST
+        //  Cada valor inicial gera uma árvore de expressão
+        init.create_expression_tree(trees);
+        None
+      },
+      SemanticNodeData::Initlist { values } => {
+        for value in values.iter() { value.create_expression_tree(trees); }
+        None
+      },
+      SemanticNodeData::Interpolation { segments } => {
+        // INTERPOLATION -> string_start EXPRESSION (string_middle EXPRESSION)* string_end
+        //  INTERPOLATION.ptr = NODE(operando, texto da string, com {} no lugar de cada expressão)
+        // Cada expressão embutida é uma expressão com a sua própria árvore
+        let mut template = String::new();
+        for segment in segments.iter() {
+          match &segment.children {
+            SemanticNodeData::Terminal { value: Token { value: Some(ConstType::String(text)), .. } } => template.push_str(&text[1..text.len() - 1]),
+            _ => {
+              segment.create_expression_tree(trees);
+              template.push_str("{}");
+            },
+          }
+        }
+        Some(ExpressionTreeNode::Operand { value: Operand::String(format!("\"{}\"", template)) })
+      },
+      SemanticNodeData::Printstat { expressions } => {
+        for expression in expressions.iter() { expression.create_expression_tree(trees); }
+        None
+      },
+      SemanticNodeData::Returnstat { expression, .. } => {
+        if let Some(expression) = expression { expression.create_expression_tree(trees); }
         None
       },
       SemanticNodeData::Program { funclist, statement } => {
@@ -1473,14 +3516,22 @@ impl SemanticNode {
         }
         None
       },
-      SemanticNodeData::Statement { atribstat, ifstat, forstat, statelist, commandstat, .. } => {
+      SemanticNodeData::Statement { vardecl, atribstat, ifstat, forstat, whilestat, dowhilestat, statelist, commandstat } => {
+        if let Some(vardecl) = vardecl { vardecl.create_expression_tree(trees); }
         if let Some(atribstat) = atribstat { atribstat.create_expression_tree(trees); }
         if let Some(ifstat) = ifstat { ifstat.create_expression_tree(trees); }
         if let Some(forstat) = forstat { forstat.create_expression_tree(trees); }
+        if let Some(whilestat) = whilestat { whilestat.create_expression_tree(trees); }
+        if let Some(dowhilestat) = dowhilestat { dowhilestat.create_expression_tree(trees); }
         if let Some(statelist) = statelist { statelist.create_expression_tree(trees); }
         if let Some(commandstat) = commandstat { commandstat.create_expression_tree(trees); }
         None
       },
+      SemanticNodeData::Switchstat { expression, caselist } => {
+        expression.create_expression_tree(trees);
+        caselist.create_expression_tree(trees);
+        None
+      },
       SemanticNodeData::Term { unaryexpression, op_term, unaryexpression2 } => {
         let root = match op_term {
           // TERM -> TERM_1 op_term UNARYEXPRESSION
@@ -1556,9 +3607,60 @@ impl SemanticNode {
         for i in index.iter() { i.create_expression_tree(trees); }
         None
       },
+      SemanticNodeData::Whilestat { condition, body, .. } => {
+        condition.create_expression_tree(trees);
+        body.create_expression_tree(trees);
+        None
+      },
       _ => { None }
     }
   }
+
+  /// Nome único no código gerado (`@f.1`, `@f.2`, ...) dos func_ids que escolheram uma definição
+  /// de uma função sobrecarregada do escopo global.
+  fn name_overloads(&mut self, global: &Scope) {
+    if let (Some(overload), SemanticNodeData::Terminal { value }) = (self.attrs.overload, &self.children) {
+      let name = value.value.as_ref().unwrap().to_string();
+      if global.1.get(&name).is_some_and(|entry| !entry.overloads.is_empty()) {
+        self.attrs.function_name = Some(format!("{}.{}", name, overload + 1));
+      }
+    }
+    for child in self.children.children_mut() { child.name_overloads(global); }
+  }
+
+  /// Nome de uma função no código gerado, que é o nome da definição escolhida quando a função é sobrecarregada.
+  fn function_name(&self) -> String {
+    match &self.attrs.function_name {
+      Some(name) => name.clone(),
+      None => self.token().value.as_ref().unwrap().to_string(),
+    }
+  }
+
+  /// Remove as posições do código fonte do nó e de todos os seus descendentes.
+  /// Duas árvores sem posições são iguais quando representam o mesmo programa,
+  /// independente da formatação do código fonte.
+  pub fn clear_positions(&mut self) {
+    self.span = Span::default();
+    match &mut self.children {
+      SemanticNodeData::Terminal { value } => {
+        value.line = 0;
+        value.column = 0;
+      },
+      SemanticNodeData::Returnstat { token, expression } => {
+        token.line = 0;
+        token.column = 0;
+        if let Some(expression) = expression { expression.clear_positions(); }
+      },
+      SemanticNodeData::Jumpstat { token, label } => {
+        token.line = 0;
+        token.column = 0;
+        if let Some(label) = label { label.clear_positions(); }
+      },
+      children => {
+        for child in children.children_mut() { child.clear_positions(); }
+      }
+    }
+  }
 }
 
 pub struct SemanticTree {
@@ -1572,11 +3674,21 @@ impl SemanticTree {
     // This is where we would check for variable declarations, types, etc.
     // For now, we will just print the structure of the semantic tree
     self.root.semantic_analysis(&mut self.scopes)?;
+    // The global scope is listed last, with the functions, global variables and enums
+    if let Some(global) = self.scopes.pop_scope() {
+      self.root.name_overloads(&global);
+    }
     Ok(())
   }
 
-  pub fn generate_code(&self) -> String {
+  pub fn generate_code(&self, print_separator: &str) -> String {
     let mut code_attrs = CodeAttrs::new();
+    code_attrs.types = self.scopes.types.clone();
+    code_attrs.print_separator = print_separator.to_string();
+    // the array parameters of the builtins, left in the prelude scope, are passed by reference like the ones of the program
+    for (name, entry) in self.scopes.stack.iter().flat_map(|scope| scope.1.iter()) {
+      code_attrs.array_params.insert(name.clone(), entry.param_index.iter().map(|dimensions| !dimensions.is_empty()).collect());
+    }
     self.root.generate_code(&mut code_attrs);
     code_attrs.code
   }
@@ -1600,6 +3712,13 @@ impl SemanticTree {
     }
     output.push_str(&format!("]\n"));
     output.push_str(&format!("Tipos de símbolos por escopo:\n{}\n", self.scopes.output));
+    let types = self.scopes.types.output();
+    if !types.is_empty() {
+      output.push_str(&format!("Structs definidas:\n{}\n", types));
+    }
+    if !self.scopes.warnings.is_empty() {
+      output.push_str(&format!("Avisos:\n{}\n", self.scopes.warnings.join("\n")));
+    }
     output.push_str(&format!("Verificação de tipos: Ok\n"));
     output.push_str(&format!("Verificação identificadores de escopos: Ok\n"));
     output.push_str(&format!("Verificação de comandos por escopo: Ok\n"));
@@ -3,11 +3,13 @@ use crate::semantic::SemanticTree;
 use crate::token::Token;
 use crate::grammar::token_type::TokenType;
 use crate::grammar::non_terminals::NonTerminal;
-use crate::semantic::SemanticNode;
+use crate::semantic::{SemanticAttrs, SemanticNode, Span};
 use crate::grammar::semantic_node::SemanticNodeData;
 use std::collections::HashMap;
 use std::rc::Rc;
 use crate::scope_stack::ScopeStack;
+use crate::formatter::Formatter;
+use crate::grammar::transform::Grammar;
 
 #[derive(Clone)] 
 pub enum Symbol {
@@ -89,6 +91,114 @@ impl Node {
     }
   }
 
+  /// Primeiro token lido pelo nó, ignorando produções vazias e o eof.
+  fn first_token(&self) -> Option<&Token> {
+    match &self.value {
+      Symbol::Terminal(TokenType::Eof, _) => None,
+      Symbol::Terminal(_, token) => token.as_ref(),
+      Symbol::NonTerminal(_) => self.children.iter().find_map(|child| child.first_token()),
+    }
+  }
+
+  /// Último token lido pelo nó, ignorando produções vazias e o eof.
+  fn last_token(&self) -> Option<&Token> {
+    match &self.value {
+      Symbol::Terminal(TokenType::Eof, _) => None,
+      Symbol::Terminal(_, token) => token.as_ref(),
+      Symbol::NonTerminal(_) => self.children.iter().rev().find_map(|child| child.last_token()),
+    }
+  }
+
+  /// Trecho do código fonte lido pelo nó, do primeiro ao último token.
+  fn span(&self) -> Span {
+    match (self.first_token(), self.last_token()) {
+      (Some(first), Some(last)) => Span::from_token(first).merge(&Span::from_token(last)),
+      _ => Span::default(),
+    }
+  }
+
+  /// Substitui o span de um nó construído por atributos herdados pelo span deste nó.
+  /// Usado no topo das cadeias de listas e expressões, onde o nó retornado pela produção vazia
+  /// não conhece o início e o fim da construção completa.
+  fn with_span(&self, mut node: SemanticNode) -> SemanticNode {
+    node.span = self.span();
+    node
+  }
+
+  /// Escreve os tokens do nó no formatador.
+  /// A árvore indica o espaçamento que não pode ser deduzido apenas pela sequência de tokens.
+  fn format(&self, formatter: &mut Formatter) {
+    match &self.value {
+      Symbol::Terminal(TokenType::Eof, _) => {},
+      Symbol::Terminal(_, token) => formatter.token(token.as_ref().unwrap()),
+      // UNARYEXPRESSION -> OP_NUMEXPRESSION FACTOR
+      //   O operador unário fica junto do fator: -x
+      Symbol::NonTerminal(NonTerminal::Unaryexpression) if self.children.len() == 2 => {
+        self.children[0].format(formatter);
+        formatter.glue();
+        self.children[1].format(formatter);
+      },
+      // FORSTAT -> kw_for lparenthesis FORINIT semicolon EXPRESSION semicolon ATRIBSTAT rparenthesis lbrace STATELIST rbrace
+      //   O cabeçalho do for fica em uma única linha
+      Symbol::NonTerminal(NonTerminal::Forstat) => {
+        let (header, body) = self.children.split_at(8);
+        formatter.inline += 1;
+        for child in header { child.format(formatter); }
+        formatter.inline -= 1;
+        for child in body { child.format(formatter); }
+      },
+      // DOWHILESTAT -> kw_do lbrace STATELIST rbrace kw_while lparenthesis EXPRESSION rparenthesis
+      //   O while fica na mesma linha do fechamento do bloco: } while (cond);
+      Symbol::NonTerminal(NonTerminal::Dowhilestat) => {
+        let (body, condition) = self.children.split_at(4);
+        for child in body { child.format(formatter); }
+        formatter.same_line();
+        for child in condition { child.format(formatter); }
+      },
+      // STATEMENT_1 -> colon LOOPSTAT
+      //   O rótulo fica junto dos dois pontos: externo: for (...)
+      Symbol::NonTerminal(NonTerminal::Statement1) if self.children.len() == 2 => {
+        formatter.glue();
+        for child in &self.children { child.format(formatter); }
+      },
+      // VARINIT -> op_assign lbrace INITLIST rbrace
+      //   Os valores iniciais ficam na mesma linha da declaração: int v[3] = {1, 2, 3};
+      Symbol::NonTerminal(NonTerminal::Varinit) if !self.children.is_empty() => {
+        formatter.inline += 1;
+        self.children[0].format(formatter);
+        self.children[1].format(formatter);
+        formatter.glue();
+        self.children[2].format(formatter);
+        formatter.glue();
+        self.children[3].format(formatter);
+        formatter.inline -= 1;
+      },
+      // FACTOR_2 -> lparenthesis PARAMLISTCALL rparenthesis
+      // STATEMENT_2 -> lparenthesis PARAMLISTCALL rparenthesis semicolon
+      //   Os argumentos de uma chamada indireta ficam junto da função chamada: ops[i](a, b)
+      Symbol::NonTerminal(NonTerminal::Factor2 | NonTerminal::Statement2) if self.children.first().is_some_and(|child| matches!(child.value, Symbol::Terminal(TokenType::Lparenthesis, _))) => {
+        formatter.glue();
+        for child in &self.children { child.format(formatter); }
+      },
+      // CASESTAT -> kw_case NUMEXPRESSION colon CASESTAT_1
+      // CASESTAT -> kw_default colon CASESTAT_1
+      //   Os dois pontos ficam junto do valor e os comandos do caso ficam um nível mais indentados
+      Symbol::NonTerminal(NonTerminal::Casestat) => {
+        let Some((body, header)) = self.children.split_last() else { panic!() };
+        let Some((colon, label)) = header.split_last() else { panic!() };
+        for child in label { child.format(formatter); }
+        formatter.glue();
+        colon.format(formatter);
+        formatter.open_block();
+        body.format(formatter);
+        formatter.close_block();
+      },
+      Symbol::NonTerminal(_) => {
+        for child in &self.children { child.format(formatter); }
+      },
+    }
+  }
+
   /// Regras semânticas para criação da AST
   /// Nessa etapa, todos os outros nós serão apenas transformados em nós semânticos.
   /// Já para os nós relacionados a expressões, serão aplicadas as regras semânticas específicas para condensar a AST.
@@ -97,81 +207,153 @@ impl Node {
       Symbol::Terminal(_, token) => {
         // Cria um nó semântico terminal com o tipo do token
         SemanticNode {
+          span: self.span(),
           children: SemanticNodeData::Terminal { value: token.clone().unwrap() },
+          attrs: SemanticAttrs::default(),
         }
       },
+      // PROGRAM -> FUNCLIST eof
+      //  PROGRAM.ptr = Node(PROGRAM, funclist=FUNCLIST.ptr, statement=None)
+      //  Se FUNCLIST possui apenas um STATEMENT: PROGRAM.ptr = Node(PROGRAM, funclist=None, statement=STATEMENT.ptr)
       Symbol::NonTerminal(NonTerminal::Program) => {
         if self.children.len() != 2 { panic!() }
-        let child = self.children[0].clone();
-        match child.value {
-          // PROGRAM -> FUNCLIST
-          //  PROGRAM.ptr = Node(PROGRAM, funclist=FUNCLIST.ptr, statement=None)
-          Symbol::NonTerminal(NonTerminal::Funclist) => {
-            SemanticNode {
-              children: SemanticNodeData::Program {
-                funclist: Some(Box::new(child.visit(None))),
-                statement: None,
-              },
-            }
-          },
-          // PROGRAM -> STATEMENT
-          //  PROGRAM.ptr = Node(PROGRAM, funclist=None, statement=STATEMENT.ptr)
-          Symbol::NonTerminal(NonTerminal::Statement) => {
-            SemanticNode {
-              children: SemanticNodeData::Program {
-                funclist: None,
-                statement: Some(Box::new(child.visit(None))),
-              },
-            }
-          },
-          _ => panic!()
+        SemanticNode {
+          span: self.span(),
+          children: SemanticNode::program(self.children[0].visit(None)),
+          attrs: SemanticAttrs::default(),
         }
       },
       Symbol::NonTerminal(NonTerminal::Funclist) => {
         match self.children.len() {
           // FUNCLIST -> ''
           //   FUNCLIST.ptr = Node(FUNCLIST, funclist=FUNCLIST.inh)
+          //   Em um programa vazio, FUNCLIST não tem herdado: FUNCLIST.ptr = Node(FUNCLIST, funclist=[])
           0 => {
+            let funclist = inh.map_or(vec![], |inh| inh.clone());
             SemanticNode {
-              children: SemanticNodeData::Funclist { funclist: inh.unwrap().clone() },
+              span: Span::of_list(&funclist),
+              children: SemanticNodeData::Funclist { funclist },
+              attrs: SemanticAttrs::default(),
             }
           },
           // FUNCLIST -> FUNCDEF FUNCLIST
-          //   FUNCLIST_2.inh = FUNCLIST_1.inh + [FUNCDEF.ptr]
+          // FUNCLIST -> STATEMENT FUNCLIST
+          // FUNCLIST -> STRUCTDEF FUNCLIST
+          //   FUNCLIST_2.inh = FUNCLIST_1.inh + [FUNCDEF.ptr | STATEMENT.ptr | STRUCTDEF.ptr]
           //   FUNCLIST_1.ptr = FUNCLIST_2.ptr 
           2 => {
+            let is_top = inh.is_none();
             let inh = match inh {
               Some(inh) => inh,
               None => &mut vec![]
             };
             inh.push(self.children[0].visit(None));
-            self.children[1].visit(Some(inh))
+            let funclist = self.children[1].visit(Some(inh));
+            if is_top { self.with_span(funclist) } else { funclist }
           },
           _ => panic!()
         }        
       }, 
       Symbol::NonTerminal(NonTerminal::Funcdef) => {
-        if self.children.len() != 8 { panic!() }
-        // FUNCDEF -> kw_def func_id lparenthesis PARAMLIST rparenthesis lbrace STATELIST rbrace
-        //   FUNCDEF.ptr = Node(FUNCDEF, func_id=func_id.ptr, paramlist=PARAMLIST.ptr, statelist=STATELIST.ptr)
+        if self.children.len() != 9 { panic!() }
+        // FUNCDEF -> kw_def FUNCTYPE func_id lparenthesis PARAMLIST rparenthesis lbrace STATELIST rbrace
+        //   FUNCDEF.ptr = Node(FUNCDEF, return_type=FUNCTYPE.ptr, func_id=func_id.ptr, paramlist=PARAMLIST.ptr, statelist=STATELIST.ptr)
         SemanticNode {
+          span: self.span(),
           children: SemanticNodeData::Funcdef {
-            func_id: Box::new(self.children[1].visit(None)),
+            // FUNCTYPE -> ''
+            //  FUNCTYPE.ptr = None
+            return_type: if !self.children[1].children.is_empty() {
+              Some(Box::new(self.children[1].visit(None)))
+            } else {
+              None
+            },
+            func_id: Box::new(self.children[2].visit(None)),
             // PARAMLIST -> ''
             //  PARAMLIST.ptr = None
-            paramlist: if self.children[3].children.len() > 0 {
-              Some(Box::new(self.children[3].visit(None)))
+            paramlist: if self.children[4].children.len() > 0 {
+              Some(Box::new(self.children[4].visit(None)))
             } else {
               None
             },
-            statelist: Box::new(self.children[6].visit(None)),
+            statelist: Box::new(self.children[7].visit(None)),
           },
+          attrs: SemanticAttrs::default(),
         }
       }, 
-      Symbol::NonTerminal(NonTerminal::Paramlist) => {
+      Symbol::NonTerminal(NonTerminal::Structdef) => {
+        if self.children.len() != 5 { panic!() }
+        // STRUCTDEF -> kw_struct id lbrace FIELDLIST rbrace
+        //   STRUCTDEF.ptr = Node(STRUCTDEF, id=id.ptr, fieldlist=FIELDLIST.ptr)
+        SemanticNode {
+          span: self.span(),
+          children: SemanticNodeData::Structdef {
+            id: Box::new(self.children[1].visit(None)),
+            fieldlist: Box::new(self.children[3].visit(None)),
+          },
+          attrs: SemanticAttrs::default(),
+        }
+      },
+      Symbol::NonTerminal(NonTerminal::Fieldlist) => {
+        if self.children.len() != 3 { panic!() }
+        // FIELDLIST -> FIELDDECL semicolon FIELDLIST_1
+        //   FIELDLIST_1.inh = [FIELDDECL.ptr]
+        //   FIELDLIST.ptr = FIELDLIST_1.ptr
+        let mut fields = vec![self.children[0].visit(None)];
+        self.with_span(self.children[2].visit(Some(&mut fields)))
+      },
+      Symbol::NonTerminal(NonTerminal::Fieldlist1) => {
+        let inh = inh.unwrap();
         match self.children.len() {
-          // PARAMLIST -> var_type id PARAMLIST1
+          // FIELDLIST_1 -> ''
+          //   FIELDLIST_1.ptr = Node(FIELDLIST, fields=FIELDLIST_1.inh)
+          0 => {
+            let fields = inh.clone();
+            SemanticNode {
+              span: Span::of_list(&fields),
+              children: SemanticNodeData::Fieldlist { fields },
+              attrs: SemanticAttrs::default(),
+            }
+          },
+          // FIELDLIST_1 -> FIELDDECL semicolon FIELDLIST_1
+          //   FIELDLIST_1_2.inh = FIELDLIST_1_1.inh + [FIELDDECL.ptr]
+          //   FIELDLIST_1_1.ptr = FIELDLIST_1_2.ptr
           3 => {
+            inh.push(self.children[0].visit(None));
+            self.children[2].visit(Some(inh))
+          },
+          _ => panic!()
+        }
+      },
+      Symbol::NonTerminal(NonTerminal::Fielddecl) => {
+        match self.children.len() {
+          // FIELDDECL -> VARDECL
+          //   FIELDDECL.ptr = VARDECL.ptr
+          1 => self.children[0].visit(None),
+          // FIELDDECL -> id id CONST_INDEX
+          //   FIELDDECL.ptr = Node(VARDECL, var_type=id_1.ptr, id=id_2.ptr, const_index=CONST_INDEX.ptr)
+          3 => SemanticNode {
+            span: self.span(),
+            children: SemanticNodeData::Vardecl {
+              var_type: Box::new(self.children[0].visit(None)),
+              id: Box::new(self.children[1].visit(None)),
+              const_index: if !self.children[2].children.is_empty() {
+                Some(Box::new(self.children[2].visit(None)))
+              } else {
+                None
+              },
+              init: None,
+            },
+            attrs: SemanticAttrs::default(),
+          },
+          _ => panic!()
+        }
+      },
+      Symbol::NonTerminal(NonTerminal::Paramlist) => {
+        match self.children.len() {
+          // PARAMLIST -> TYPE id PARAM_INDEX PARAMLIST1
+          //   PARAMLIST1.inh = [TYPE.ptr, id.ptr] + [PARAM_INDEX.ptr, se não for vazio]
+          4 => {
             let inh = match inh {
               Some(inh) => { inh },
               None => { &mut vec![] }
@@ -179,26 +361,35 @@ impl Node {
 
             inh.push(self.children[0].visit(None));
             inh.push(self.children[1].visit(None));
+            if !self.children[2].children.is_empty() {
+              inh.push(self.children[2].visit(None));
+            }
 
-            self.children[2].visit(Some(inh))
+            self.with_span(self.children[3].visit(Some(inh)))
           }
           // PARAMLIST -> '' is handled in FUNCDEF
           _ => panic!()
         }
       }, 
+      // FUNCTYPE -> var_type | kw_void
+      //   FUNCTYPE.ptr = token.ptr
+      Symbol::NonTerminal(NonTerminal::Functype) => self.children[0].visit(None),
       Symbol::NonTerminal(NonTerminal::Paramlist1) => {
         match self.children.len() {
           // PARAMLIST1 -> ''
           //   PARAMLIST1.ptr = Node(PARAMLIST, paramlist=PARAMLIST.inh)
           0 => {
+            let paramlist = inh.unwrap().clone();
             SemanticNode {
-              children: SemanticNodeData::Paramlist { paramlist: inh.unwrap().clone() },
+              span: Span::of_list(&paramlist),
+              children: SemanticNodeData::Paramlist { paramlist },
+              attrs: SemanticAttrs::default(),
             }
           },
-          // PARAMLIST1 -> comma var_type id PARAMLIST1
-          //   PARAMLIST1_2.inh = PARAMLIST1_1.inh + [var_type.ptr, id.ptr]
+          // PARAMLIST1 -> comma TYPE id PARAM_INDEX PARAMLIST1
+          //   PARAMLIST1_2.inh = PARAMLIST1_1.inh + [TYPE.ptr, id.ptr] + [PARAM_INDEX.ptr, se não for vazio]
           //   PARAMLIST1_1.ptr = PARAMLIST1_2.ptr
-          4 => {
+          5 => {
             let inh = match inh {
               Some(inh) => inh,
               None => &mut vec![],
@@ -206,26 +397,48 @@ impl Node {
 
             inh.push(self.children[1].visit(None));
             inh.push(self.children[2].visit(None));
-            self.children[3].visit(Some(inh))
+            if !self.children[3].children.is_empty() {
+              inh.push(self.children[3].visit(None));
+            }
+            self.children[4].visit(Some(inh))
           },
           _ => panic!()
         }
       }, 
+      Symbol::NonTerminal(NonTerminal::ParamIndex) => {
+        // PARAM_INDEX -> lbracket rbracket CONST_INDEX
+        //   PARAM_INDEX.ptr = Node(PARAM_INDEX, const_index=CONST_INDEX.ptr)
+        // PARAM_INDEX -> '' is handled in PARAMLIST
+        SemanticNode {
+          span: self.span(),
+          children: SemanticNodeData::ParamIndex {
+            const_index: if self.children[2].children.is_empty() {
+              None
+            } else {
+              Some(Box::new(self.children[2].visit(None)))
+            },
+          },
+          attrs: SemanticAttrs::default(),
+        }
+      },
       Symbol::NonTerminal(NonTerminal::Statelist) => {
         if self.children.len() != 2 { panic!() }
         // STATELIST -> STATEMENT STATELIST1
         //   STATELIST1.inh = [STATEMENT.ptr]
         //   STATELIST.ptr = STATELIST1.ptr
         let mut statelist = vec![self.children[0].visit(None)];
-        self.children[1].visit(Some(&mut statelist))
+        self.with_span(self.children[1].visit(Some(&mut statelist)))
       }, 
       Symbol::NonTerminal(NonTerminal::Statelist1) => {
         match self.children.len() {
           // STATELIST1 -> ''
           //   STATELIST1.ptr = Node(STATELIST, statelist=STATELIST.inh)
           0 => {
+            let statelist = inh.unwrap().clone();
             SemanticNode {
-              children: SemanticNodeData::Statelist { statelist: inh.unwrap().clone() },
+              span: Span::of_list(&statelist),
+              children: SemanticNodeData::Statelist { statelist },
+              attrs: SemanticAttrs::default(),
             }
           },
           // STATELIST1 -> STATEMENT STATELIST1
@@ -244,112 +457,321 @@ impl Node {
       Symbol::NonTerminal(NonTerminal::Statement) => {
         match self.children[0].value {
           // STATEMENT -> Vardecl semicolon
-          //   STATEMENT.ptr = Node(STATEMENT, vardecl=Vardecl.ptr, atribstat=None, ifstat=None, forstat=None, statelist=None, commandstat=None)
-          Symbol::NonTerminal(NonTerminal::Vardecl) => {
+          // STATEMENT -> Constdecl semicolon
+          // STATEMENT -> Enumdef
+          //   STATEMENT.ptr = Node(STATEMENT, vardecl=Vardecl.ptr | Constdecl.ptr | Enumdef.ptr, atribstat=None, ifstat=None, forstat=None, whilestat=None, dowhilestat=None, statelist=None, commandstat=None)
+          Symbol::NonTerminal(NonTerminal::Vardecl) | Symbol::NonTerminal(NonTerminal::Constdecl) | Symbol::NonTerminal(NonTerminal::Enumdef) => {
             SemanticNode {
+              span: self.span(),
               children: SemanticNodeData::Statement {
                 vardecl: Some(Box::new(self.children[0].visit(None))),
                 atribstat: None,
                 ifstat: None,
                 forstat: None,
+                whilestat: None,
+                dowhilestat: None,
                 statelist: None,
                 commandstat: None,
-              }
+              },
+              attrs: SemanticAttrs::default(),
             }
           },
-          // STATEMENT -> ATRIBSTAT semicolon
-          //   STATEMENT.ptr = Node(STATEMENT, vardecl=None, atribstat=Atribstat.ptr, ifstat=None, forstat=None, statelist=None, commandstat=None)
-          Symbol::NonTerminal(NonTerminal::Atribstat) => {
-            SemanticNode {
-              children: SemanticNodeData::Statement {
-                vardecl: None,
-                atribstat: Some(Box::new(self.children[0].visit(None))),
-                ifstat: None,
-                forstat: None,
-                statelist: None,
-                commandstat: None,
-              }
-            }
+          // STATEMENT -> id STATEMENT_1
+          //   STATEMENT_1.inh = [id.ptr]
+          //   STATEMENT.ptr = STATEMENT_1.ptr
+          Symbol::Terminal(TokenType::Id, _) => {
+            let inh = self.children[0].visit(None);
+            self.with_span(self.children[1].visit(Some(&mut vec![inh])))
           },
-          // STATEMENT -> (PRINTSTAT | READSTAT | RETURNSTAT | kw_break) semicolon
-          //   STATEMENT.ptr = Node(STATEMENT, vardecl=None, atribstat=None, ifstat=None, forstat=None, statelist=None, commandstat=CommandStat.ptr)
-          Symbol::NonTerminal(NonTerminal::Printstat) | Symbol::NonTerminal(NonTerminal::Readstat) | Symbol::NonTerminal(NonTerminal::Returnstat) | Symbol::Terminal(TokenType::KwBreak, _) => {
+          // STATEMENT -> (PRINTSTAT | READSTAT | RETURNSTAT | FUNCCALL | JUMPSTAT) semicolon
+          // STATEMENT -> SWITCHSTAT
+          //   STATEMENT.ptr = Node(STATEMENT, vardecl=None, atribstat=None, ifstat=None, forstat=None, whilestat=None, dowhilestat=None, statelist=None, commandstat=CommandStat.ptr)
+          Symbol::NonTerminal(NonTerminal::Printstat) | Symbol::NonTerminal(NonTerminal::Readstat) | Symbol::NonTerminal(NonTerminal::Returnstat) | Symbol::NonTerminal(NonTerminal::Funccall) | Symbol::NonTerminal(NonTerminal::Jumpstat) | Symbol::NonTerminal(NonTerminal::Switchstat) => {
             let commandstat = self.children[0].visit(None);
             SemanticNode {
+              span: self.span(),
               children: SemanticNodeData::Statement {
                 vardecl: None,
                 atribstat: None,
                 ifstat: None,
                 forstat: None,
+                whilestat: None,
+                dowhilestat: None,
                 statelist: None,
                 commandstat: Some(Box::new(commandstat)),
-              }
+              },
+              attrs: SemanticAttrs::default(),
             }
           },
           // STATEMENT -> IFSTAT
-          //   STATEMENT.ptr = Node(STATEMENT, vardecl=None, atribstat=None, ifstat=IfStat.ptr, forstat=None, statelist=None, commandstat=None)
+          //   STATEMENT.ptr = Node(STATEMENT, vardecl=None, atribstat=None, ifstat=IfStat.ptr, forstat=None, whilestat=None, dowhilestat=None, statelist=None, commandstat=None)
           Symbol::NonTerminal(NonTerminal::Ifstat) => {
             SemanticNode {
+              span: self.span(),
               children: SemanticNodeData::Statement {
                 vardecl: None,
                 atribstat: None,
                 ifstat: Some(Box::new(self.children[0].visit(None))),
                 forstat: None,
+                whilestat: None,
+                dowhilestat: None,
                 statelist: None,
                 commandstat: None,
-              }
+              },
+              attrs: SemanticAttrs::default(),
             }
           },
           // STATEMENT -> FORSTAT
-          //   STATEMENT.ptr = Node(STATEMENT, vardecl=None, atribstat=None, ifstat=None, forstat=ForStat.ptr, statelist=None, commandstat=None)
+          //   STATEMENT.ptr = Node(STATEMENT, vardecl=None, atribstat=None, ifstat=None, forstat=ForStat.ptr, whilestat=None, dowhilestat=None, statelist=None, commandstat=None)
           Symbol::NonTerminal(NonTerminal::Forstat) => {
             SemanticNode {
+              span: self.span(),
               children: SemanticNodeData::Statement {
                 vardecl: None,
                 atribstat: None,
                 ifstat: None,
                 forstat: Some(Box::new(self.children[0].visit(None))),
+                whilestat: None,
+                dowhilestat: None,
                 statelist: None,
                 commandstat: None,
-              }
+              },
+              attrs: SemanticAttrs::default(),
+            }
+          },
+          // STATEMENT -> WHILESTAT
+          //   STATEMENT.ptr = Node(STATEMENT, vardecl=None, atribstat=None, ifstat=None, forstat=None, whilestat=WhileStat.ptr, dowhilestat=None, statelist=None, commandstat=None)
+          Symbol::NonTerminal(NonTerminal::Whilestat) => {
+            SemanticNode {
+              span: self.span(),
+              children: SemanticNodeData::Statement {
+                vardecl: None,
+                atribstat: None,
+                ifstat: None,
+                forstat: None,
+                whilestat: Some(Box::new(self.children[0].visit(None))),
+                dowhilestat: None,
+                statelist: None,
+                commandstat: None,
+              },
+              attrs: SemanticAttrs::default(),
+            }
+          },
+          // STATEMENT -> DOWHILESTAT semicolon
+          //   STATEMENT.ptr = Node(STATEMENT, vardecl=None, atribstat=None, ifstat=None, forstat=None, whilestat=None, dowhilestat=DoWhileStat.ptr, statelist=None, commandstat=None)
+          Symbol::NonTerminal(NonTerminal::Dowhilestat) => {
+            SemanticNode {
+              span: self.span(),
+              children: SemanticNodeData::Statement {
+                vardecl: None,
+                atribstat: None,
+                ifstat: None,
+                forstat: None,
+                whilestat: None,
+                dowhilestat: Some(Box::new(self.children[0].visit(None))),
+                statelist: None,
+                commandstat: None,
+              },
+              attrs: SemanticAttrs::default(),
             }
           },
           // STATEMENT -> lbrace STATELIST rbrace
-          //   STATEMENT.ptr = Node(STATEMENT, vardecl=None, atribstat=None, ifstat=None, forstat=None, statelist=STATELIST.ptr, commandstat=None)
+          //   STATEMENT.ptr = Node(STATEMENT, vardecl=None, atribstat=None, ifstat=None, forstat=None, whilestat=None, dowhilestat=None, statelist=STATELIST.ptr, commandstat=None)
           Symbol::Terminal(TokenType::Lbrace, _) => {
             let statelist = self.children[1].visit(None);
             SemanticNode {
+              span: self.span(),
               children: SemanticNodeData::Statement {
                 vardecl: None,
                 atribstat: None,
                 ifstat: None,
                 forstat: None,
+                whilestat: None,
+                dowhilestat: None,
                 statelist: Some(Box::new(statelist)),
                 commandstat: None,
-              }
+              },
+              attrs: SemanticAttrs::default(),
             }
           },
           // STATEMENT -> semicolon
-          //   STATEMENT.ptr = Node(STATEMENT, vardecl=None, atribstat=None, ifstat=None, forstat=None, statelist=None, commandstat=None)
+          //   STATEMENT.ptr = Node(STATEMENT, vardecl=None, atribstat=None, ifstat=None, forstat=None, whilestat=None, dowhilestat=None, statelist=None, commandstat=None)
           Symbol::Terminal(TokenType::Semicolon, _) => {
             SemanticNode {
+              span: self.span(),
               children: SemanticNodeData::Statement {
                 vardecl: None,
                 atribstat: None,
                 ifstat: None,
                 forstat: None,
+                whilestat: None,
+                dowhilestat: None,
                 statelist: None,
                 commandstat: None,
-              }
+              },
+              attrs: SemanticAttrs::default(),
+            }
+          },
+          _ => panic!()
+        }
+      },
+      Symbol::NonTerminal(NonTerminal::Statement1) => {
+        let id = inh.unwrap().pop().unwrap();
+        match self.children[0].value {
+          // STATEMENT_1 -> colon LOOPSTAT
+          //   LOOPSTAT.inh = [STATEMENT_1.inh]
+          //   STATEMENT_1.ptr = LOOPSTAT.ptr
+          Symbol::Terminal(TokenType::Colon, _) => self.children[1].visit(Some(&mut vec![id])),
+          // STATEMENT_1 -> id CONST_INDEX semicolon
+          //   O id herdado é o nome da struct
+          //   VARDECL.ptr = Node(VARDECL, var_type=STATEMENT_1.inh, id=id.ptr, const_index=CONST_INDEX.ptr)
+          //   STATEMENT_1.ptr = Node(STATEMENT, vardecl=VARDECL.ptr, atribstat=None, ifstat=None, forstat=None, whilestat=None, dowhilestat=None, statelist=None, commandstat=None)
+          Symbol::Terminal(TokenType::Id, _) => {
+            let vardecl = SemanticNode {
+              span: id.span.merge(&self.children[0].span()).merge(&self.children[1].span()),
+              children: SemanticNodeData::Vardecl {
+                var_type: Box::new(id),
+                id: Box::new(self.children[0].visit(None)),
+                const_index: if !self.children[1].children.is_empty() {
+                  Some(Box::new(self.children[1].visit(None)))
+                } else {
+                  None
+                },
+                init: None,
+              },
+              attrs: SemanticAttrs::default(),
+            };
+            SemanticNode {
+              span: self.span(),
+              children: SemanticNodeData::Statement {
+                vardecl: Some(Box::new(vardecl)),
+                atribstat: None,
+                ifstat: None,
+                forstat: None,
+                whilestat: None,
+                dowhilestat: None,
+                statelist: None,
+                commandstat: None,
+              },
+              attrs: SemanticAttrs::default(),
             }
           },
+          // STATEMENT_1 -> VAR_INDEX FIELD_ACCESS STATEMENT_2
+          //   LVALUE.ptr = Node(LVALUE, id=STATEMENT_1.inh, var_index=VAR_INDEX.ptr, field=FIELD_ACCESS.ptr)
+          //   STATEMENT_2.inh = [LVALUE.ptr]
+          //   STATEMENT_1.ptr = STATEMENT_2.ptr
+          Symbol::NonTerminal(NonTerminal::VarIndex) => {
+            let lvalue = SemanticNode {
+              span: id.span.merge(&self.children[0].span()).merge(&self.children[1].span()),
+              children: SemanticNodeData::Lvalue {
+                id: Box::new(id),
+                var_index: if !self.children[0].children.is_empty() {
+                  Some(Box::new(self.children[0].visit(None)))
+                } else {
+                  None
+                },
+                field: if !self.children[1].children.is_empty() {
+                  Some(Box::new(self.children[1].visit(None)))
+                } else {
+                  None
+                },
+              },
+              attrs: SemanticAttrs::default(),
+            };
+            self.children[2].visit(Some(&mut vec![lvalue]))
+          },
           _ => panic!()
         }
       },
+      Symbol::NonTerminal(NonTerminal::Statement2) => {
+        let lvalue = inh.unwrap().pop().unwrap();
+        match self.children[0].value {
+          // STATEMENT_2 -> ATRIBSTAT_1 semicolon
+          //   ATRIBSTAT_1.inh = [STATEMENT_2.inh]
+          //   STATEMENT_2.ptr = Node(STATEMENT, vardecl=None, atribstat=ATRIBSTAT_1.ptr, ifstat=None, forstat=None, whilestat=None, dowhilestat=None, statelist=None, commandstat=None)
+          Symbol::NonTerminal(NonTerminal::Atribstat1) => {
+            let atribstat = self.children[0].visit(Some(&mut vec![lvalue]));
+            SemanticNode {
+              span: atribstat.span.merge(&self.span()),
+              children: SemanticNodeData::Statement {
+                vardecl: None,
+                atribstat: Some(Box::new(atribstat)),
+                ifstat: None,
+                forstat: None,
+                whilestat: None,
+                dowhilestat: None,
+                statelist: None,
+                commandstat: None,
+              },
+              attrs: SemanticAttrs::default(),
+            }
+          },
+          // STATEMENT_2 -> lparenthesis PARAMLISTCALL rparenthesis semicolon
+          //   Chamada indireta, pelo valor de STATEMENT_2.inh
+          //   FUNCCALL.ptr = Node(FUNCCALL, id=STATEMENT_2.inh, paramlistcall=PARAMLISTCALL.ptr)
+          //   STATEMENT_2.ptr = Node(STATEMENT, vardecl=None, atribstat=None, ifstat=None, forstat=None, whilestat=None, dowhilestat=None, statelist=None, commandstat=FUNCCALL.ptr)
+          Symbol::Terminal(TokenType::Lparenthesis, _) => {
+            let funccall = SemanticNode {
+              span: lvalue.span.merge(&self.children[2].span()),
+              children: SemanticNodeData::Funccall {
+                id: Box::new(lvalue),
+                paramlistcall: if self.children[1].children.is_empty() {
+                  None
+                } else {
+                  Some(Box::new(self.children[1].visit(None)))
+                },
+              },
+              attrs: SemanticAttrs::default(),
+            };
+            SemanticNode {
+              span: funccall.span.merge(&self.span()),
+              children: SemanticNodeData::Statement {
+                vardecl: None,
+                atribstat: None,
+                ifstat: None,
+                forstat: None,
+                whilestat: None,
+                dowhilestat: None,
+                statelist: None,
+                commandstat: Some(Box::new(funccall)),
+              },
+              attrs: SemanticAttrs::default(),
+            }
+          },
+          _ => panic!()
+        }
+      },
+      // LOOPSTAT -> FORSTAT | WHILESTAT | DOWHILESTAT semicolon
+      //   FORSTAT.inh = WHILESTAT.inh = DOWHILESTAT.inh = LOOPSTAT.inh (rótulo do laço)
+      //   LOOPSTAT.ptr = Node(STATEMENT, forstat=FORSTAT.ptr | whilestat=WHILESTAT.ptr | dowhilestat=DOWHILESTAT.ptr)
+      Symbol::NonTerminal(NonTerminal::Loopstat) => {
+        let node = Some(Box::new(self.children[0].visit(inh)));
+        let (forstat, whilestat, dowhilestat) = match self.children[0].value {
+          Symbol::NonTerminal(NonTerminal::Forstat) => (node, None, None),
+          Symbol::NonTerminal(NonTerminal::Whilestat) => (None, node, None),
+          Symbol::NonTerminal(NonTerminal::Dowhilestat) => (None, None, node),
+          _ => panic!()
+        };
+        SemanticNode {
+          span: self.span(),
+          children: SemanticNodeData::Statement {
+            vardecl: None,
+            atribstat: None,
+            ifstat: None,
+            forstat,
+            whilestat,
+            dowhilestat,
+            statelist: None,
+            commandstat: None,
+          },
+          attrs: SemanticAttrs::default(),
+        }
+      },
       Symbol::NonTerminal(NonTerminal::Vardecl) => {
-        // VARDECL -> var_type id CONST_INDEX
-        //   VARDECL.ptr = Node(VARDECL, var_type=var_type.ptr, id=id.ptr, const_index=CONST_INDEX.ptr)
+        // VARDECL -> TYPE id CONST_INDEX VARINIT
+        //   VARDECL.ptr = Node(VARDECL, var_type=TYPE.ptr, id=id.ptr, const_index=CONST_INDEX.ptr, init=VARINIT.ptr)
         SemanticNode {
+          span: self.span(),
           children: SemanticNodeData::Vardecl {
             var_type: Box::new(self.children[0].visit(None)),
             id: Box::new(self.children[1].visit(None)),
@@ -358,28 +780,184 @@ impl Node {
               None
             } else {
               Some(Box::new(self.children[2].visit(None)))
+            },
+            init: if self.children[3].children.is_empty() {
+              // VARINIT -> '': VARINIT.ptr = None
+              None
+            } else {
+              Some(Box::new(self.children[3].visit(None)))
+            },
+          },
+          attrs: SemanticAttrs::default(),
+        }
+      },
+      // VARINIT -> op_assign lbrace INITLIST rbrace
+      //   VARINIT.ptr = INITLIST.ptr
+      // VARINIT -> '' is handled in VARDECL
+      Symbol::NonTerminal(NonTerminal::Varinit) => self.children[2].visit(None),
+      Symbol::NonTerminal(NonTerminal::Initlist) => {
+        // INITLIST -> EXPRESSION INITLIST_1
+        //   INITLIST_1.inh = [EXPRESSION.ptr]
+        //   INITLIST.ptr = INITLIST_1.ptr
+        let mut values = vec![self.children[0].visit(None)];
+        self.with_span(self.children[1].visit(Some(&mut values)))
+      },
+      Symbol::NonTerminal(NonTerminal::Initlist1) => {
+        let inh = inh.unwrap();
+        match self.children.len() {
+          // INITLIST_1 -> ''
+          //   INITLIST_1.ptr = Node(INITLIST, values=INITLIST_1.inh)
+          0 => {
+            let values = inh.clone();
+            SemanticNode {
+              span: Span::of_list(&values),
+              children: SemanticNodeData::Initlist { values },
+              attrs: SemanticAttrs::default(),
             }
-          }
+          },
+          // INITLIST_1 -> comma EXPRESSION INITLIST_1
+          //   INITLIST_1_2.inh = INITLIST_1_1.inh + [EXPRESSION.ptr]
+          //   INITLIST_1_1.ptr = INITLIST_1_2.ptr
+          3 => {
+            inh.push(self.children[1].visit(None));
+            self.children[2].visit(Some(inh))
+          },
+          _ => panic!()
+        }
+      },
+      // TYPE -> var_type | SIGNATURE
+      //   TYPE.ptr = var_type.ptr | SIGNATURE.ptr
+      Symbol::NonTerminal(NonTerminal::Type) => self.children[0].visit(None),
+      Symbol::NonTerminal(NonTerminal::Signature) => {
+        // SIGNATURE -> kw_func lparenthesis TYPELIST rparenthesis arrow SIGNATURE_1
+        //   TYPELIST.inh = [SIGNATURE_1.ptr]
+        //   SIGNATURE.ptr = TYPELIST.ptr
+        // TYPELIST -> '': SIGNATURE.ptr = Node(SIGNATURE, params=[], return_type=SIGNATURE_1.ptr)
+        let return_type = self.children[5].visit(None);
+        if self.children[2].children.is_empty() {
+          return SemanticNode {
+            span: self.span(),
+            children: SemanticNodeData::Signature { params: vec![], return_type: Box::new(return_type) },
+            attrs: SemanticAttrs::default(),
+          };
+        }
+        self.with_span(self.children[2].visit(Some(&mut vec![return_type])))
+      },
+      // SIGNATURE_1 -> var_type | kw_void
+      //   SIGNATURE_1.ptr = token.ptr
+      Symbol::NonTerminal(NonTerminal::Signature1) => self.children[0].visit(None),
+      Symbol::NonTerminal(NonTerminal::Typelist) => {
+        // TYPELIST -> TYPE TYPELIST_1
+        //   TYPELIST_1.inh = TYPELIST.inh + [TYPE.ptr]
+        //   TYPELIST.ptr = TYPELIST_1.ptr
+        // TYPELIST -> '' is handled in SIGNATURE
+        let inh = inh.unwrap();
+        inh.push(self.children[0].visit(None));
+        self.children[1].visit(Some(inh))
+      },
+      Symbol::NonTerminal(NonTerminal::Typelist1) => {
+        let inh = inh.unwrap();
+        match self.children.len() {
+          // TYPELIST_1 -> ''
+          //   TYPELIST_1.inh = [SIGNATURE_1.ptr, TYPE1.ptr, TYPE2.ptr...]
+          //   TYPELIST_1.ptr = Node(SIGNATURE, params=[TYPE1.ptr, TYPE2.ptr...], return_type=SIGNATURE_1.ptr)
+          0 => {
+            let mut params = inh.clone();
+            let return_type = params.remove(0);
+            SemanticNode {
+              span: Span::of_list(&params),
+              children: SemanticNodeData::Signature { params, return_type: Box::new(return_type) },
+              attrs: SemanticAttrs::default(),
+            }
+          },
+          // TYPELIST_1 -> comma TYPE TYPELIST_1
+          //   TYPELIST_1_2.inh = TYPELIST_1_1.inh + [TYPE.ptr]
+          //   TYPELIST_1_1.ptr = TYPELIST_1_2.ptr
+          3 => {
+            inh.push(self.children[1].visit(None));
+            self.children[2].visit(Some(inh))
+          },
+          _ => panic!()
+        }
+      },
+      Symbol::NonTerminal(NonTerminal::Constdecl) => {
+        // CONSTDECL -> kw_const var_type id op_assign EXPRESSION
+        //   CONSTDECL.ptr = Node(CONSTDECL, var_type=var_type.ptr, id=id.ptr, value=EXPRESSION.ptr)
+        SemanticNode {
+          span: self.span(),
+          children: SemanticNodeData::Constdecl {
+            var_type: Box::new(self.children[1].visit(None)),
+            id: Box::new(self.children[2].visit(None)),
+            value: Box::new(self.children[4].visit(None)),
+          },
+          attrs: SemanticAttrs::default(),
+        }
+      },
+      Symbol::NonTerminal(NonTerminal::Enumdef) => {
+        // ENUMDEF -> kw_enum id lbrace ENUMLIST rbrace
+        //   ENUMDEF.ptr = Node(ENUMDEF, id=id.ptr, enumlist=ENUMLIST.ptr)
+        SemanticNode {
+          span: self.span(),
+          children: SemanticNodeData::Enumdef {
+            id: Box::new(self.children[1].visit(None)),
+            enumlist: Box::new(self.children[3].visit(None)),
+          },
+          attrs: SemanticAttrs::default(),
+        }
+      },
+      Symbol::NonTerminal(NonTerminal::Enumlist) => {
+        // ENUMLIST -> id ENUMLIST_1
+        //   ENUMLIST_1.inh = [id.ptr]
+        //   ENUMLIST.ptr = ENUMLIST_1.ptr
+        let mut enumerators = vec![self.children[0].visit(None)];
+        self.with_span(self.children[1].visit(Some(&mut enumerators)))
+      },
+      Symbol::NonTerminal(NonTerminal::Enumlist1) => {
+        let inh = inh.unwrap();
+        match self.children.len() {
+          // ENUMLIST_1 -> ''
+          //   ENUMLIST_1.ptr = Node(ENUMLIST, enumerators=ENUMLIST_1.inh)
+          0 => {
+            let enumerators = inh.clone();
+            SemanticNode {
+              span: Span::of_list(&enumerators),
+              children: SemanticNodeData::Enumlist { enumerators },
+              attrs: SemanticAttrs::default(),
+            }
+          },
+          // ENUMLIST_1 -> comma id ENUMLIST_1
+          //   ENUMLIST_1_2.inh = ENUMLIST_1_1.inh + [id.ptr]
+          //   ENUMLIST_1_1.ptr = ENUMLIST_1_2.ptr
+          3 => {
+            inh.push(self.children[1].visit(None));
+            self.children[2].visit(Some(inh))
+          },
+          _ => panic!()
         }
       },
       Symbol::NonTerminal(NonTerminal::ConstIndex) => {
         match self.children.len() {
-          // CONST_INDEX -> lbracket const_int rbracket CONST_INDEX
-          //   CONST_INDEX_2.inh = CONST_INDEX_1.inh + [const_int.ptr]
+          // CONST_INDEX -> lbracket NUMEXPRESSION rbracket CONST_INDEX
+          //   CONST_INDEX_2.inh = CONST_INDEX_1.inh + [NUMEXPRESSION.ptr]
           //   CONST_INDEX_1.ptr = CONST_INDEX_2.ptr
           4 => {
+            let is_top = inh.is_none();
             let inh = match inh {
               Some(inh) => inh,
               None => &mut vec![],
             };
             inh.push(self.children[1].visit(None));
-            self.children[3].visit(Some(inh))
+            let index = self.children[3].visit(Some(inh));
+            if is_top { self.with_span(index) } else { index }
           },
           // CONST_INDEX -> ''
           //   CONST_INDEX.ptr = Node(CONST_INDEX, index=CONST_INDEX.inh)
           0 => {
+            let index = inh.unwrap().clone();
             SemanticNode {
-              children: SemanticNodeData::ConstIndex { index: inh.unwrap().clone() },
+              span: Span::of_list(&index),
+              children: SemanticNodeData::ConstIndex { index },
+              attrs: SemanticAttrs::default(),
             }
           },
           _ => panic!()
@@ -390,55 +968,89 @@ impl Node {
           // VAR_INDEX -> ''
           //   VAR_INDEX.ptr = Node(VAR_INDEX, index=VAR_INDEX.inh)
           0 => {
+            let index = inh.unwrap().clone();
             SemanticNode {
-              children: SemanticNodeData::VarIndex { index: inh.unwrap().clone() }
+              span: Span::of_list(&index),
+              children: SemanticNodeData::VarIndex { index },
+              attrs: SemanticAttrs::default(),
             }
           },
           // VAR_INDEX -> lbracket NUMEXPRESSION rbracket VAR_INDEX
           //   VAR_INDEX_2.inh = VAR_INDEX_1.inh + [NUMEXPRESSION.ptr]
           //   VAR_INDEX_1.ptr = VAR_INDEX_2.ptr
           4 => {
+            let is_top = inh.is_none();
             let inh = match inh {
               Some(inh) => inh,
               None => &mut vec![],
             };
             inh.push(self.children[1].visit(None));
-            self.children[3].visit(Some(inh))
+            let index = self.children[3].visit(Some(inh));
+            if is_top { self.with_span(index) } else { index }
           },
           _ => panic!()
         }
       }, 
-      // ATRIBSTAT -> LVALUE op_assign ATRIBSTATEVALUE
-      //   ATRIBSTAT.ptr = Node(ATRIBSTAT, lvalue=LVALUE.ptr, value=ATRIBSTATEVALUE.ptr)
+      // ATRIBSTAT -> LVALUE ATRIBSTAT_1
+      //   ATRIBSTAT_1.inh = [LVALUE.ptr]
+      //   ATRIBSTAT.ptr = ATRIBSTAT_1.ptr
       Symbol::NonTerminal(NonTerminal::Atribstat) => {
-        SemanticNode {
-          children: SemanticNodeData::Atribstat {
-            lvalue: Box::new(self.children[0].visit(None)),
-            value: Box::new(self.children[2].visit(None))
-          }
+        let inh = self.children[0].visit(None);
+        self.with_span(self.children[1].visit(Some(&mut vec![inh])))
+      },
+      Symbol::NonTerminal(NonTerminal::Atribstat1) => {
+        let lvalue = inh.unwrap().pop().unwrap();
+        let span = lvalue.span.merge(&self.span());
+        match &self.children[0].value {
+          // ATRIBSTAT_1 -> op_assign ATRIBSTATEVALUE
+          //   ATRIBSTAT_1.ptr = Node(ATRIBSTAT, lvalue=ATRIBSTAT_1.inh, op=None, value=ATRIBSTATEVALUE.ptr)
+          Symbol::Terminal(TokenType::OpAssign, _) => {
+            SemanticNode {
+              span,
+              children: SemanticNodeData::Atribstat {
+                lvalue: Box::new(lvalue),
+                op: None,
+                value: Box::new(self.children[1].visit(None))
+              },
+              attrs: SemanticAttrs::default(),
+            }
+          },
+          // ATRIBSTAT_1 -> OP_ATRIBSTAT EXPRESSION
+          //   ATRIBSTAT_1.ptr = Node(ATRIBSTAT, lvalue=ATRIBSTAT_1.inh, op=OP_ATRIBSTAT.op, value=EXPRESSION.ptr)
+          Symbol::NonTerminal(NonTerminal::OpAtribstat) => {
+            let op = self.children[0].visit(None);
+            let value = self.children[1].visit(None);
+            SemanticNode { span, children: SemanticNode::compound_atribstat(lvalue, op.token(), Some(value)), attrs: SemanticAttrs::default() }
+          },
+          // ATRIBSTAT_1 -> op_increment | op_decrement
+          //   ATRIBSTAT_1.ptr = Node(ATRIBSTAT, lvalue=ATRIBSTAT_1.inh, op=op_plus | op_minus, value=1)
+          Symbol::Terminal(_, op) => {
+            SemanticNode { span, children: SemanticNode::compound_atribstat(lvalue, op.as_ref().unwrap(), None), attrs: SemanticAttrs::default() }
+          },
+          _ => panic!()
         }
       },
+      // OP_ATRIBSTAT -> op_plus_assign | op_minus_assign | op_multiply_assign | op_division_assign | op_modular_assign
+      //   OP_ATRIBSTAT.ptr = op.ptr
+      Symbol::NonTerminal(NonTerminal::OpAtribstat) => self.children[0].visit(None),
       Symbol::NonTerminal(NonTerminal::Atribstatevalue) => {
         match self.children[0].value {
           // ATRIBSTATEVALUE -> expression
-          //   ATRIBSTATEVALUE.ptr = Node(ATRIBSTATEVALUE, expression=EXPRESSION.ptr, allocexpression=None, funccall=None)
+          //   ATRIBSTATEVALUE.ptr = Node(ATRIBSTATEVALUE, expression=EXPRESSION.ptr, allocexpression=None)
           Symbol::NonTerminal(NonTerminal::Expression) => {
             SemanticNode {
-              children: SemanticNodeData::Atribstatevalue { expression: Some(Box::new(self.children[0].visit(None))), allocexpression: None, funccall: None }
+              span: self.span(),
+              children: SemanticNodeData::Atribstatevalue { expression: Some(Box::new(self.children[0].visit(None))), allocexpression: None },
+              attrs: SemanticAttrs::default(),
             }
           },
           // ATRIBSTATEVALUE -> allocexpression
-          //   ATRIBSTATEVALUE.ptr = Node(ATRIBSTATEVALUE, expression=None, allocexpression=ALLOCEXPRESSION.ptr, funccall=None)
+          //   ATRIBSTATEVALUE.ptr = Node(ATRIBSTATEVALUE, expression=None, allocexpression=ALLOCEXPRESSION.ptr)
           Symbol::NonTerminal(NonTerminal::Allocexpression) => {
             SemanticNode {
-              children: SemanticNodeData::Atribstatevalue { expression: None, allocexpression: Some(Box::new(self.children[0].visit(None))), funccall: None }
-            }
-          },
-          // ATRIBSTATEVALUE -> funccall
-          //   ATRIBSTATEVALUE.ptr = Node(ATRIBSTATEVALUE, expression=None, allocexpression=None, funccall=FUNCCALL.ptr)
-          Symbol::NonTerminal(NonTerminal::Funccall) => {
-            SemanticNode {
-              children: SemanticNodeData::Atribstatevalue { expression: None, allocexpression: None, funccall: Some(Box::new(self.children[0].visit(None))) }
+              span: self.span(),
+              children: SemanticNodeData::Atribstatevalue { expression: None, allocexpression: Some(Box::new(self.children[0].visit(None))) },
+              attrs: SemanticAttrs::default(),
             }
           },
           _ => panic!()
@@ -448,6 +1060,7 @@ impl Node {
       //   FUNCCALL.ptr = Node(FUNCCALL, id=func_id.ptr, paramlistcall=PARAMLISTCALL.ptr)
       Symbol::NonTerminal(NonTerminal::Funccall) => {
         SemanticNode {
+          span: self.span(),
           children: SemanticNodeData::Funccall {
             id: Box::new(self.children[0].visit(None)),
             paramlistcall: if self.children[2].children.len() > 0 {
@@ -456,7 +1069,8 @@ impl Node {
               // PARAMLISTCALL -> '': PARAMLISTCALL.ptr = None
               None
             }
-          }
+          },
+          attrs: SemanticAttrs::default(),
         }
       }, 
       Symbol::NonTerminal(NonTerminal::Paramlistcall) => {
@@ -465,7 +1079,7 @@ impl Node {
           //   PARAMLISTCALL_1.inh = [EXPRESSION.ptr]
           2 => {
             let mut inh = vec![self.children[0].visit(None)];
-            self.children[1].visit(Some(&mut inh))
+            self.with_span(self.children[1].visit(Some(&mut inh)))
           },
           // PARAMLISTCALL -> '' is handled in FUNCCALL
           _ => panic!()
@@ -476,12 +1090,15 @@ impl Node {
           // PARAMLISTCALL_1 -> ''
           //   PARAMLISTCALL_1.ptr = Node(PARAMLISTCALL, paramlist=PARAMLISTCALL.inh)
           0 => {
+            let paramlist = inh.unwrap().clone();
             SemanticNode {
-              children: SemanticNodeData::Paramlistcall { paramlist: inh.unwrap().clone() },
+              span: Span::of_list(&paramlist),
+              children: SemanticNodeData::Paramlistcall { paramlist },
+              attrs: SemanticAttrs::default(),
             }
           },
-          // PARAMLISTCALL_1 -> comma id PARAMLISTCALL_1
-          //   PARAMLISTCALL_1_2.inh = PARAMLISTCALL_1_1.inh + [id.ptr]
+          // PARAMLISTCALL_1 -> comma EXPRESSION PARAMLISTCALL_1
+          //   PARAMLISTCALL_1_2.inh = PARAMLISTCALL_1_1.inh + [EXPRESSION.ptr]
           3 => {
             let inh = match inh {
               Some(inh) => inh,
@@ -494,11 +1111,33 @@ impl Node {
         }
       },
       Symbol::NonTerminal(NonTerminal::Printstat) => {
-        if self.children.len() != 2 { panic!() }
-        // PRINTSTAT -> kw_print EXPRESSION
-        //   PRINTSTAT.ptr = Node(PRINTSTAT, expression=EXPRESSION.ptr) 
-        SemanticNode {
-          children: SemanticNodeData::Printstat { expression: Box::new(self.children[1].visit(None)) },
+        if self.children.len() != 3 { panic!() }
+        // PRINTSTAT -> kw_print EXPRESSION PRINTSTAT_1
+        //   PRINTSTAT_1.inh = [EXPRESSION.ptr]
+        //   PRINTSTAT.ptr = PRINTSTAT_1.ptr
+        let mut expressions = vec![self.children[1].visit(None)];
+        self.with_span(self.children[2].visit(Some(&mut expressions)))
+      },
+      Symbol::NonTerminal(NonTerminal::Printstat1) => {
+        let inh = inh.unwrap();
+        match self.children.len() {
+          // PRINTSTAT_1 -> ''
+          //   PRINTSTAT_1.ptr = Node(PRINTSTAT, expressions=PRINTSTAT_1.inh)
+          0 => {
+            let expressions = inh.clone();
+            SemanticNode {
+              span: Span::of_list(&expressions),
+              children: SemanticNodeData::Printstat { expressions },
+              attrs: SemanticAttrs::default(),
+            }
+          },
+          // PRINTSTAT_1 -> comma EXPRESSION PRINTSTAT_1
+          //   PRINTSTAT_1_2.inh = PRINTSTAT_1_1.inh + [EXPRESSION.ptr]
+          3 => {
+            inh.push(self.children[1].visit(None));
+            self.children[2].visit(Some(inh))
+          },
+          _ => panic!()
         }
       },
       Symbol::NonTerminal(NonTerminal::Readstat) => {
@@ -506,24 +1145,61 @@ impl Node {
         // READSTAT -> kw_read LVALUE
         //   READSTAT.ptr = Node(READSTAT, lvalue=LVALUE.ptr)
         SemanticNode {
+          span: self.span(),
           children: SemanticNodeData::Readstat { lvalue: Box::new(self.children[1].visit(None)) },
+          attrs: SemanticAttrs::default(),
         }
       }, 
       Symbol::NonTerminal(NonTerminal::Returnstat) => {
         let Symbol::Terminal(_, token) = &self.children[0].value else { panic!("Expected terminal token for return statement"); };
-        // RETURNSTAT -> kw_return semicolon
-        //   RETURNSTAT.ptr = Node(kw_return, token=token)
+        // RETURNSTAT -> kw_return RETURNSTAT_1
+        //   RETURNSTAT.ptr = Node(kw_return, token=token, expression=RETURNSTAT_1.ptr)
         SemanticNode {
+          span: self.span(),
           children: SemanticNodeData::Returnstat {
-            token: token.clone().unwrap()
+            token: token.clone().unwrap(),
+            // RETURNSTAT_1 -> ''
+            //   RETURNSTAT_1.ptr = None
+            expression: if !self.children[1].children.is_empty() {
+              Some(Box::new(self.children[1].visit(None)))
+            } else {
+              None
+            },
+          },
+          attrs: SemanticAttrs::default(),
+        }
+      },
+      // RETURNSTAT_1 -> EXPRESSION
+      //   RETURNSTAT_1.ptr = EXPRESSION.ptr
+      Symbol::NonTerminal(NonTerminal::Returnstat1) => self.children[0].visit(None),
+      Symbol::NonTerminal(NonTerminal::Jumpstat) => {
+        let Symbol::Terminal(_, token) = &self.children[0].value else { panic!("Expected terminal token for jump statement"); };
+        // JUMPSTAT -> (kw_break | kw_continue) JUMPSTAT_1
+        //   JUMPSTAT.ptr = Node(JUMPSTAT, token=token, label=JUMPSTAT_1.ptr)
+        SemanticNode {
+          span: self.span(),
+          children: SemanticNodeData::Jumpstat {
+            token: token.clone().unwrap(),
+            // JUMPSTAT_1 -> ''
+            //   JUMPSTAT_1.ptr = None
+            label: if !self.children[1].children.is_empty() {
+              Some(Box::new(self.children[1].visit(None)))
+            } else {
+              None
+            },
           },
+          attrs: SemanticAttrs::default(),
         }
       },
+      // JUMPSTAT_1 -> id
+      //   JUMPSTAT_1.ptr = id.ptr
+      Symbol::NonTerminal(NonTerminal::Jumpstat1) => self.children[0].visit(None),
       Symbol::NonTerminal(NonTerminal::Ifstat) => {
         if self.children.len() != 8 { panic!() }
         // IFSTAT -> kw_if lparenthesis EXPRESSION rparenthesis lbrace STATELIST rbrace ELSESTAT 
         //   IFSTAT.ptr = Node(IFSTAT, condition=EXPRESSION.ptr, then_branch=STATELIST.ptr, else_branch=ELSESTAT.ptr)
         SemanticNode {
+          span: self.span(),
           children: SemanticNodeData::Ifstat {
             condition: Box::new(self.children[2].visit(None)),
             then_branch: Box::new(self.children[5].visit(None)),
@@ -534,6 +1210,7 @@ impl Node {
               None
             }
           },
+          attrs: SemanticAttrs::default(),
         }
       }, 
       Symbol::NonTerminal(NonTerminal::Elsestat) => {
@@ -541,9 +1218,11 @@ impl Node {
         // ELSESTAT -> kw_else ELSESTAT_1
         //   ELSESTAT.ptr = ELSESTAT_1.ptr
         SemanticNode {
+          span: self.span(),
           children: SemanticNodeData::Elsestat {
             statement: Box::new(self.children[1].visit(None)),
-          }
+          },
+          attrs: SemanticAttrs::default(),
         }
         // ELSESTAT -> '' is handled in IFSTAT
       }, 
@@ -560,72 +1239,227 @@ impl Node {
       },
       Symbol::NonTerminal(NonTerminal::Forstat) => {
         if self.children.len() != 11 { panic!() }
-        // FORSTAT -> kw_for lparenthesis ATRIBSTAT_1 semicolon EXPRESSION semicolon ATRIBSTAT_2 rparenthesis lbrace STATELIST rbrace
-        //  FORSTAT.ptr = Node(FORSTAT, init=ATRIBSTAT_1.ptr, condition=EXPRESSION.ptr, increment=ATRIBSTAT_2.ptr, body=STATELIST.ptr)
+        // FORSTAT -> kw_for lparenthesis FORINIT semicolon EXPRESSION semicolon ATRIBSTAT rparenthesis lbrace STATELIST rbrace
+        //  FORSTAT.ptr = Node(FORSTAT, label=FORSTAT.inh, init=FORINIT.ptr, condition=EXPRESSION.ptr, increment=ATRIBSTAT.ptr, body=STATELIST.ptr)
         SemanticNode {
+          span: self.span(),
           children: SemanticNodeData::Forstat {
+            label: inh.and_then(|inh| inh.pop()).map(Box::new),
             init: Box::new(self.children[2].visit(None)),
             condition: Box::new(self.children[4].visit(None)),
             increment: Box::new(self.children[6].visit(None)),
             body: Box::new(self.children[9].visit(None)),
           },
+          attrs: SemanticAttrs::default(),
         }
       }, 
+      Symbol::NonTerminal(NonTerminal::Forinit) => {
+        match self.children.len() {
+          // FORINIT -> ATRIBSTAT
+          //   FORINIT.ptr = ATRIBSTAT.ptr
+          1 => self.children[0].visit(None),
+          // FORINIT -> var_type id op_assign EXPRESSION
+          //   FORINIT.ptr = Node(FORINIT, var_type=var_type.ptr, id=id.ptr, value=EXPRESSION.ptr)
+          4 => SemanticNode {
+            span: self.span(),
+            children: SemanticNodeData::Forinit {
+              var_type: Box::new(self.children[0].visit(None)),
+              id: Box::new(self.children[1].visit(None)),
+              value: Box::new(self.children[3].visit(None)),
+            },
+            attrs: SemanticAttrs::default(),
+          },
+          _ => panic!()
+        }
+      },
+      Symbol::NonTerminal(NonTerminal::Whilestat) => {
+        if self.children.len() != 7 { panic!() }
+        // WHILESTAT -> kw_while lparenthesis EXPRESSION rparenthesis lbrace STATELIST rbrace
+        //  WHILESTAT.ptr = Node(WHILESTAT, label=WHILESTAT.inh, condition=EXPRESSION.ptr, body=STATELIST.ptr)
+        SemanticNode {
+          span: self.span(),
+          children: SemanticNodeData::Whilestat {
+            label: inh.and_then(|inh| inh.pop()).map(Box::new),
+            condition: Box::new(self.children[2].visit(None)),
+            body: Box::new(self.children[5].visit(None)),
+          },
+          attrs: SemanticAttrs::default(),
+        }
+      },
+      Symbol::NonTerminal(NonTerminal::Dowhilestat) => {
+        if self.children.len() != 8 { panic!() }
+        // DOWHILESTAT -> kw_do lbrace STATELIST rbrace kw_while lparenthesis EXPRESSION rparenthesis
+        //  DOWHILESTAT.ptr = Node(DOWHILESTAT, label=DOWHILESTAT.inh, body=STATELIST.ptr, condition=EXPRESSION.ptr)
+        SemanticNode {
+          span: self.span(),
+          children: SemanticNodeData::Dowhilestat {
+            label: inh.and_then(|inh| inh.pop()).map(Box::new),
+            body: Box::new(self.children[2].visit(None)),
+            condition: Box::new(self.children[6].visit(None)),
+          },
+          attrs: SemanticAttrs::default(),
+        }
+      },
+      Symbol::NonTerminal(NonTerminal::Switchstat) => {
+        if self.children.len() != 7 { panic!() }
+        // SWITCHSTAT -> kw_switch lparenthesis EXPRESSION rparenthesis lbrace CASELIST rbrace
+        //  CASELIST.inh = []
+        //  SWITCHSTAT.ptr = Node(SWITCHSTAT, expression=EXPRESSION.ptr, caselist=CASELIST.ptr)
+        SemanticNode {
+          span: self.span(),
+          children: SemanticNodeData::Switchstat {
+            expression: Box::new(self.children[2].visit(None)),
+            caselist: Box::new(self.children[5].visit(Some(&mut vec![]))),
+          },
+          attrs: SemanticAttrs::default(),
+        }
+      },
+      Symbol::NonTerminal(NonTerminal::Caselist) => {
+        let inh = inh.unwrap();
+        match self.children.len() {
+          // CASELIST -> ''
+          //   CASELIST.ptr = Node(CASELIST, cases=CASELIST.inh)
+          0 => {
+            let cases = inh.clone();
+            SemanticNode {
+              span: Span::of_list(&cases),
+              children: SemanticNodeData::Caselist { cases },
+              attrs: SemanticAttrs::default(),
+            }
+          },
+          // CASELIST -> CASESTAT CASELIST
+          //   CASELIST_2.inh = CASELIST_1.inh + [CASESTAT.ptr]
+          //   CASELIST_1.ptr = CASELIST_2.ptr
+          2 => {
+            inh.push(self.children[0].visit(None));
+            self.children[1].visit(Some(inh))
+          },
+          _ => panic!()
+        }
+      },
+      Symbol::NonTerminal(NonTerminal::Casestat) => {
+        // CASESTAT -> kw_case NUMEXPRESSION colon CASESTAT_1
+        // CASESTAT -> kw_default colon CASESTAT_1
+        //  CASESTAT.ptr = Node(CASESTAT, value=NUMEXPRESSION.ptr | None, statelist=CASESTAT_1.ptr)
+        let (value, body) = match self.children.len() {
+          4 => (Some(Box::new(self.children[1].visit(None))), &self.children[3]),
+          3 => (None, &self.children[2]),
+          _ => panic!()
+        };
+        SemanticNode {
+          span: self.span(),
+          children: SemanticNodeData::Casestat {
+            value,
+            // CASESTAT_1 -> ''
+            //   CASESTAT_1.ptr = None
+            statelist: if !body.children.is_empty() {
+              Some(Box::new(body.visit(None)))
+            } else {
+              None
+            },
+          },
+          attrs: SemanticAttrs::default(),
+        }
+      },
+      // CASESTAT_1 -> STATELIST
+      //   CASESTAT_1.ptr = STATELIST.ptr
+      Symbol::NonTerminal(NonTerminal::Casestat1) => self.children[0].visit(None),
       Symbol::NonTerminal(NonTerminal::Allocexpression) => {
         if self.children.len() != 3 { panic!() }
-        // ALLOCEXPRESSION -> kw_new var_type VAR_INDEX
-        //  ALLOCEXPRESSION.ptr = Node(ALLOCEXPRESSION, var_type=var_type.ptr, dimensions=VAR_INDEX.ptr)
+        // ALLOCEXPRESSION -> kw_new ALLOCTYPE VAR_INDEX
+        //  ALLOCEXPRESSION.ptr = Node(ALLOCEXPRESSION, var_type=ALLOCTYPE.ptr, dimensions=VAR_INDEX.ptr)
         SemanticNode {
+          span: self.span(),
           children: SemanticNodeData::Allocexpression {
             var_type: Box::new(self.children[1].visit(None)),
-            dimensions: Box::new(self.children[2].visit(None)),
+            // VAR_INDEX -> ''
+            //   VAR_INDEX.ptr = Node(VAR_INDEX, index=[])
+            dimensions: Box::new(if !self.children[2].children.is_empty() {
+              self.children[2].visit(None)
+            } else {
+              self.children[2].visit(Some(&mut vec![]))
+            }),
           },
+          attrs: SemanticAttrs::default(),
         }
       },
+      // ALLOCTYPE -> var_type | id
+      //   ALLOCTYPE.ptr = token.ptr
+      Symbol::NonTerminal(NonTerminal::Alloctype) => self.children[0].visit(None),
       // Aqui começam as regras semânticas para construção da AST
       // Parte das regras anteriores aplicaram o mesmo conceito de forma a otimizar o processo de compilação
       // Porém, como apenas essa parte estava descrita no enunciado, as regras semânticas a seguir são mais específicas
       
-      // EXPRESSION -> NUMEXPRESSION EXPRESSION_1
+      // EXPRESSION -> NUMEXPRESSION EXPRESSION_1 EXPRESSION_2
       //  EXPRESSION_1.inh = NUMEXPRESSION.ptr
-      //  EXPRESSION.ptr = EXPRESSION_1.ptr
+      //  EXPRESSION_2.inh = EXPRESSION_1.ptr
+      //  EXPRESSION.ptr = EXPRESSION_2.ptr
       Symbol::NonTerminal(NonTerminal::Expression) => {
         let inh = self.children[0].visit(None);
-        self.children[1].visit(Some(&mut vec![inh]))
+        let condition = self.children[1].visit(Some(&mut vec![inh]));
+        self.with_span(self.children[2].visit(Some(&mut vec![condition])))
       }, 
       Symbol::NonTerminal(NonTerminal::Expression1) => {
         match self.children.len() {
           // EXPRESSION_1 -> OP_EXPRESSION NUMEXPRESSION
           //  EXPRESSION_1.ptr = Node(EXPRESSION, Some(vec![EXPRESSION_1.inh, OP_EXPRESSION.ptr, NUMEXPRESSION.ptr]))
           2 => {
+            let inh = inh.unwrap();
             SemanticNode {
+              span: inh[0].span.merge(&self.span()),
               children: SemanticNodeData::Expression { 
-                numexpression: Box::new(inh.unwrap()[0].clone()),
+                numexpression: Box::new(inh[0].clone()),
                 op_expression: Some(Box::new(self.children[0].visit(None))),
                 numexpression2: Some(Box::new(self.children[1].visit(None))),
               },
+              attrs: SemanticAttrs::default(),
             }
           }
           // EXPRESSION_1 -> ''
           // EXPRESSION_1.ptr = EXPRESSION_1.inh
           0 => {
+            let inh = inh.unwrap();
             SemanticNode {
+              span: inh[0].span,
               children: SemanticNodeData::Expression { 
-                numexpression: Box::new(inh.unwrap()[0].clone()),
+                numexpression: Box::new(inh[0].clone()),
                 op_expression: None,
                 numexpression2: None,
               },
+              attrs: SemanticAttrs::default(),
             }
           },
           _ => panic!()
         }
       }, 
+      Symbol::NonTerminal(NonTerminal::Expression2) => {
+        let condition = inh.unwrap().pop().unwrap();
+        match self.children.len() {
+          // EXPRESSION_2 -> question EXPRESSION colon EXPRESSION
+          //  EXPRESSION_2.ptr = Node(CONDEXPRESSION, condition=EXPRESSION_2.inh, then_expression=EXPRESSION.ptr, else_expression=EXPRESSION2.ptr)
+          4 => {
+            SemanticNode {
+              span: condition.span.merge(&self.span()),
+              children: SemanticNodeData::Condexpression {
+                condition: Box::new(condition),
+                then_expression: Box::new(self.children[1].visit(None)),
+                else_expression: Box::new(self.children[3].visit(None)),
+              },
+              attrs: SemanticAttrs::default(),
+            }
+          },
+          // EXPRESSION_2 -> ''
+          //  EXPRESSION_2.ptr = EXPRESSION_2.inh
+          0 => condition,
+          _ => panic!()
+        }
+      },
       Symbol::NonTerminal(NonTerminal::Numexpression) => {
         // NUMEXPRESSION -> TERM NUMEXPRESSION_1
         //  NUMEXPRESSION_1.inh = [TERM.ptr]
         //  NUMEXPRESSION.ptr = NUMEXPRESSION_1.ptr
         let inh = self.children[0].visit(None);
-        self.children[1].visit(Some(&mut vec![inh]))
+        self.with_span(self.children[1].visit(Some(&mut vec![inh])))
       }, 
       Symbol::NonTerminal(NonTerminal::Numexpression1) => {
         match self.children.len() {
@@ -633,12 +1467,16 @@ impl Node {
         //  NUMEXPRESSION_1_1.inh = Node(NUMEXPRESSION, vec![NUMEXPRESSION_1.inh.children[0], OP_NUMEXPRESSION.ptr, TERM.ptr])
         //  NUMEXPRESSION_1.ptr = NUMEXPRESSION_1_1.ptr
           3 => {
+            let inh = inh.unwrap();
+            let term2 = self.children[1].visit(None);
             let left_size = SemanticNode {
+              span: inh[0].span.merge(&term2.span),
               children: SemanticNodeData::Numexpression { 
-                term: Box::new(inh.unwrap()[0].clone()),
+                term: Box::new(inh[0].clone()),
                 op_numexpression: Some(Box::new(self.children[0].visit(None))),
-                term2: Some(Box::new(self.children[1].visit(None))),
+                term2: Some(Box::new(term2)),
               },
+              attrs: SemanticAttrs::default(),
             };
             self.children[2].visit(Some(&mut vec![left_size]))
           }
@@ -653,11 +1491,13 @@ impl Node {
               // Se for do tipo Term, retorna um novo nodo Numexpression com o termo herdado
               SemanticNodeData::Term { .. } => {
                 SemanticNode {
+                  span: inh[0].span,
                   children: SemanticNodeData::Numexpression { 
                     term: Box::new(inh[0].clone()),
                     op_numexpression: None,
                     term2: None,
                   },
+                  attrs: SemanticAttrs::default(),
                 }
               }
               _ => panic!()
@@ -671,7 +1511,7 @@ impl Node {
         //  TERM_1.inh = [FACTOR.ptr]
         //  TERM.ptr = TERM_1.ptr
         let inh = self.children[0].visit(None);
-        self.children[1].visit(Some(&mut vec![inh]))
+        self.with_span(self.children[1].visit(Some(&mut vec![inh])))
       }, 
       Symbol::NonTerminal(NonTerminal::Term1) => {
         match self.children.len() {
@@ -679,12 +1519,16 @@ impl Node {
           //  TERM_1_1.inh = Node(TERM, vec![TERM_1.inh.children[0], OP_TERM.ptr, FACTOR.ptr])
           //  TERM_1.ptr = TERM_1_1.ptr
           3 => {
+            let inh = inh.unwrap();
+            let unaryexpression2 = self.children[1].visit(None);
             let left_size = SemanticNode {
+              span: inh[0].span.merge(&unaryexpression2.span),
               children: SemanticNodeData::Term { 
-                unaryexpression: Box::new(inh.unwrap()[0].clone()),
+                unaryexpression: Box::new(inh[0].clone()),
                 op_term: Some(Box::new(self.children[0].visit(None))),
-                unaryexpression2: Some(Box::new(self.children[1].visit(None))),
+                unaryexpression2: Some(Box::new(unaryexpression2)),
               },
+              attrs: SemanticAttrs::default(),
             };
             self.children[2].visit(Some(&mut vec![left_size]))
           }
@@ -699,11 +1543,13 @@ impl Node {
               // Se for do tipo Unaryexpression, retorna um novo nodo Term com o fator herdado
               SemanticNodeData::Unaryexpression { .. } => {
                 SemanticNode {
+                  span: inh[0].span,
                   children: SemanticNodeData::Term { 
                     unaryexpression: Box::new(inh[0].clone()),
                     op_term: None,
                     unaryexpression2: None,
                   },
+                  attrs: SemanticAttrs::default(),
                 }
               }
               _ => panic!()
@@ -718,19 +1564,23 @@ impl Node {
           //  UNARYEXPRESSION.ptr = FACTOR.ptr
           1 => {
             SemanticNode {
+              span: self.span(),
               children: SemanticNodeData::Unaryexpression { 
                 op: None,
                 factor: Box::new(self.children[0].visit(None))
               },
+              attrs: SemanticAttrs::default(),
             }
           },
           // UNARYEXPRESSION -> OP_NUMEXPRESSION FACTOR
           2 => {
             SemanticNode {
+              span: self.span(),
               children: SemanticNodeData::Unaryexpression { 
                 op: Some(Box::new(self.children[0].visit(None))),
                 factor: Box::new(self.children[1].visit(None))
               },
+              attrs: SemanticAttrs::default(),
             }
           },
           _ => panic!()
@@ -738,42 +1588,175 @@ impl Node {
       }, 
       Symbol::NonTerminal(NonTerminal::Factor) => {
         match self.children[0].value {
-          // FACTOR -> lparenthesis EXPRESSION rparenthesis
+          // FACTOR -> lparenthesis FACTOR_1
+          //   FACTOR.ptr = FACTOR_1.ptr, com o trecho a partir do lparenthesis
           Symbol::Terminal(TokenType::Lparenthesis, _) => {
+            self.with_span(self.children[1].visit(None))
+          },
+          // FACTOR -> LVALUE FACTOR_2
+          //   FACTOR_2.inh = [LVALUE.ptr]
+          //   FACTOR.ptr = Node(FACTOR, funccall=FACTOR_2.ptr)
+          Symbol::NonTerminal(NonTerminal::Lvalue) if !self.children[1].children.is_empty() => {
+            let lvalue = self.children[0].visit(None);
             SemanticNode {
-              children: SemanticNodeData::Factor { 
-                expression: Some(Box::new(self.children[1].visit(None))),
+              span: self.span(),
+              children: SemanticNodeData::Factor {
+                expression: None,
                 lvalue: None,
                 constant: None,
+                funccall: Some(Box::new(self.children[1].visit(Some(&mut vec![lvalue])))),
               },
+              attrs: SemanticAttrs::default(),
             }
           },
           // FACTOR -> LVALUE
           Symbol::NonTerminal(NonTerminal::Lvalue) => {
             SemanticNode {
+              span: self.span(),
               children: SemanticNodeData::Factor { 
                 expression: None,
                 lvalue: Some(Box::new(self.children[0].visit(None))),
                 constant: None,
+                funccall: None,
               },
+              attrs: SemanticAttrs::default(),
             }
           },
           // FACTOR -> CONSTANT
           Symbol::NonTerminal(NonTerminal::Constant) => {
             SemanticNode {
+              span: self.span(),
               children: SemanticNodeData::Factor { 
                 expression: None,
                 lvalue: None,
                 constant: Some(Box::new(self.children[0].visit(None))),
+                funccall: None,
+              },
+              attrs: SemanticAttrs::default(),
+            }
+          },
+          // FACTOR -> func_id FACTOR_2
+          //   FACTOR_2.inh = [func_id.ptr]
+          //   FACTOR.ptr = Node(FACTOR, funccall=FACTOR_2.ptr)
+          Symbol::Terminal(TokenType::FuncId, _) if !self.children[1].children.is_empty() => {
+            let id = self.children[0].visit(None);
+            SemanticNode {
+              span: self.span(),
+              children: SemanticNodeData::Factor {
+                expression: None,
+                lvalue: None,
+                constant: None,
+                funccall: Some(Box::new(self.children[1].visit(Some(&mut vec![id])))),
               },
+              attrs: SemanticAttrs::default(),
+            }
+          },
+          // FACTOR -> INTERPOLATION
+          //   FACTOR.ptr = INTERPOLATION.ptr
+          Symbol::NonTerminal(NonTerminal::Interpolation) => self.children[0].visit(None),
+          // FACTOR -> func_id
+          //   Referência à função, sem chamá-la
+          //   FACTOR.ptr = Node(FUNCREF, id=func_id.ptr)
+          Symbol::Terminal(TokenType::FuncId, _) => {
+            SemanticNode {
+              span: self.span(),
+              children: SemanticNodeData::Funcref { id: Box::new(self.children[0].visit(None)) },
+              attrs: SemanticAttrs::default(),
             }
           },
           _ => panic!()
         }
       }, 
+      Symbol::NonTerminal(NonTerminal::Factor2) => {
+        // FACTOR_2 -> lparenthesis PARAMLISTCALL rparenthesis
+        //   A função chamada é FACTOR_2.inh: um func_id ou um LVALUE, em uma chamada indireta
+        //   FACTOR_2.ptr = Node(FUNCCALL, id=FACTOR_2.inh, paramlistcall=PARAMLISTCALL.ptr)
+        // FACTOR_2 -> '' is handled in FACTOR
+        let id = inh.unwrap().pop().unwrap();
+        SemanticNode {
+          span: id.span.merge(&self.span()),
+          children: SemanticNodeData::Funccall {
+            id: Box::new(id),
+            paramlistcall: if self.children[1].children.is_empty() {
+              None
+            } else {
+              Some(Box::new(self.children[1].visit(None)))
+            },
+          },
+          attrs: SemanticAttrs::default(),
+        }
+      },
+      Symbol::NonTerminal(NonTerminal::Factor1) => {
+        match self.children[0].value {
+          // FACTOR_1 -> NUMEXPRESSION rparenthesis
+          //   FACTOR_1.ptr = Node(FACTOR, expression=NUMEXPRESSION.ptr)
+          Symbol::NonTerminal(NonTerminal::Numexpression) => {
+            SemanticNode {
+              span: self.span(),
+              children: SemanticNodeData::Factor { 
+                expression: Some(Box::new(self.children[0].visit(None))),
+                lvalue: None,
+                constant: None,
+                funccall: None,
+              },
+              attrs: SemanticAttrs::default(),
+            }
+          },
+          // FACTOR_1 -> CASTEXPRESSION
+          Symbol::NonTerminal(NonTerminal::Castexpression) => self.children[0].visit(None),
+          _ => panic!()
+        }
+      }, 
+      Symbol::NonTerminal(NonTerminal::Interpolation) => {
+        // INTERPOLATION -> string_start EXPRESSION INTERPOLATION_1
+        //   INTERPOLATION_1.inh = [string_start.ptr, EXPRESSION.ptr]
+        //   INTERPOLATION.ptr = INTERPOLATION_1.ptr
+        let mut segments = vec![self.children[0].visit(None), self.children[1].visit(None)];
+        self.with_span(self.children[2].visit(Some(&mut segments)))
+      },
+      Symbol::NonTerminal(NonTerminal::Interpolation1) => {
+        let inh = inh.unwrap();
+        match self.children.len() {
+          // INTERPOLATION_1 -> string_end
+          //   INTERPOLATION_1.ptr = Node(INTERPOLATION, segments=INTERPOLATION_1.inh + [string_end.ptr])
+          1 => {
+            inh.push(self.children[0].visit(None));
+            let segments = inh.clone();
+            SemanticNode {
+              span: Span::of_list(&segments),
+              children: SemanticNodeData::Interpolation { segments },
+              attrs: SemanticAttrs::default(),
+            }
+          },
+          // INTERPOLATION_1 -> string_middle EXPRESSION INTERPOLATION_1
+          //   INTERPOLATION_1_2.inh = INTERPOLATION_1_1.inh + [string_middle.ptr, EXPRESSION.ptr]
+          //   INTERPOLATION_1_1.ptr = INTERPOLATION_1_2.ptr
+          3 => {
+            inh.push(self.children[0].visit(None));
+            inh.push(self.children[1].visit(None));
+            self.children[2].visit(Some(inh))
+          },
+          _ => panic!()
+        }
+      },
+      Symbol::NonTerminal(NonTerminal::Castexpression) => {
+        // CASTEXPRESSION -> var_type rparenthesis FACTOR
+        //   CASTEXPRESSION.ptr = Node(CASTEXPRESSION, var_type=var_type.ptr, factor=FACTOR.ptr)
+        SemanticNode {
+          span: self.span(),
+          children: SemanticNodeData::Castexpression {
+            var_type: Box::new(self.children[0].visit(None)),
+            factor: Box::new(self.children[2].visit(None)),
+          },
+          attrs: SemanticAttrs::default(),
+        }
+      },
       Symbol::NonTerminal(NonTerminal::Lvalue) => {
-        if self.children.len() != 2 { panic!() }
+        if self.children.len() != 3 { panic!() }
+        // LVALUE -> id VAR_INDEX FIELD_ACCESS
+        //   LVALUE.ptr = Node(LVALUE, id=id.ptr, var_index=VAR_INDEX.ptr, field=FIELD_ACCESS.ptr)
         SemanticNode {
+          span: self.span(),
           children: SemanticNodeData::Lvalue { 
             id: Box::new(self.children[0].visit(None)),
             var_index: if self.children[1].children.len() > 0 {
@@ -781,42 +1764,78 @@ impl Node {
             } else {
               None
             },
+            field: if !self.children[2].children.is_empty() {
+              Some(Box::new(self.children[2].visit(None)))
+            } else {
+              None
+            },
           },
+          attrs: SemanticAttrs::default(),
         }
       }, 
+      Symbol::NonTerminal(NonTerminal::FieldAccess) => {
+        if self.children.len() != 4 { panic!() }
+        // FIELD_ACCESS -> dot id VAR_INDEX FIELD_ACCESS
+        //   O acesso ao campo é um LVALUE que começa pelo nome do campo, sem o ponto
+        //   FIELD_ACCESS_1.ptr = Node(LVALUE, id=id.ptr, var_index=VAR_INDEX.ptr, field=FIELD_ACCESS_2.ptr)
+        // FIELD_ACCESS -> '' is handled in LVALUE
+        SemanticNode {
+          span: self.children[1].span().merge(&self.span()),
+          children: SemanticNodeData::Lvalue {
+            id: Box::new(self.children[1].visit(None)),
+            var_index: if !self.children[2].children.is_empty() {
+              Some(Box::new(self.children[2].visit(None)))
+            } else {
+              None
+            },
+            field: if !self.children[3].children.is_empty() {
+              Some(Box::new(self.children[3].visit(None)))
+            } else {
+              None
+            },
+          },
+          attrs: SemanticAttrs::default(),
+        }
+      },
       Symbol::NonTerminal(NonTerminal::Constant) => {
         let Symbol::Terminal(_token_type, token ) = self.children[0].clone().value else { panic!(); };
         let token = token.unwrap();
         SemanticNode {
+          span: self.span(),
           children: SemanticNodeData::Constant { 
-            value: token.value.unwrap(),
-            line: token.line,
-            column: token.column,
+            value: token.value.unwrap().unescape_braces(),
           },
+          attrs: SemanticAttrs::default(),
         }
       }, 
       Symbol::NonTerminal(NonTerminal::OpExpression) => {
         let Symbol::Terminal(token_type, _) = self.children[0].value else { panic!(); };
         SemanticNode {
+          span: self.span(),
           children: SemanticNodeData::OpExpression {
             op: token_type,
-          }
+          },
+          attrs: SemanticAttrs::default(),
         }
       }, 
       Symbol::NonTerminal(NonTerminal::OpNumexpression) => {
         let Symbol::Terminal(token_type, _) = self.children[0].value else { panic!(); };
         SemanticNode {
+          span: self.span(),
           children: SemanticNodeData::OpNumexpression {
             op: token_type,
-          }
+          },
+          attrs: SemanticAttrs::default(),
         }
       }, 
       Symbol::NonTerminal(NonTerminal::OpTerm) => {
         let Symbol::Terminal(token_type, _) = self.children[0].value else { panic!(); };
         SemanticNode {
+          span: self.span(),
           children: SemanticNodeData::OpTerm {
             op: token_type,
-          }
+          },
+          attrs: SemanticAttrs::default(),
         }
       }, 
     }
@@ -862,18 +1881,16 @@ pub struct SyntaxTree {
 impl SyntaxTree {
   pub fn new() -> Result<Self, Box<dyn Error>> {
     // Load Grammar rules
-    let rule_content = include_str!("../grammars/syntax.txt");
+    let grammar = Grammar::parse(include_str!("../grammars/syntax.txt"))?;
     let mut rules = vec![];
-    for line in rule_content.lines() {
-      let parts: Vec<&str> = line.split(",").collect();
-      if parts.len() != 2 { continue; }
-      let head = NonTerminal::from_str(parts[0])?;
-      let body: Option<Vec<Symbol>> = match parts[1] {
-        "''" => None,
+    for (head, body) in grammar.productions() {
+      let head = NonTerminal::from_str(head)?;
+      let body: Option<Vec<Symbol>> = match body {
+        [] => None,
         // The else case is when grammars/syntax.txt has an invalid rule, this problem
         // should be identified at compile time so that it's fixed in the grammar file instead of here.
         // Hopefully the else case will never be hit.  
-        _ => Some(parts[1].split_whitespace().map(|s| {
+        _ => Some(body.iter().map(|s| {
           if let Ok(token) = TokenType::from_str(s) { Symbol::Terminal(token, None) }
           else if let Ok(nt) = NonTerminal::from_str(s) { Symbol::NonTerminal(nt) }
           else { panic!("Invalid grammar") }
@@ -918,6 +1935,10 @@ impl SyntaxTree {
     Ok(semantic_tree)
   }
 
+  pub fn format(&self, formatter: &mut Formatter) {
+    self.root.format(formatter);
+  }
+
   pub fn output_stats(&self, output: &mut String) {
     output.push_str(&format!("Análise sintática concluída com sucesso. Árvore sintática gerada:\n"));
     output.push_str(&format!("// Visualize a árvore colando este arquivo em https://dreampuf.github.io/GraphvizOnline/?engine=dot\ndigraph G {{{}}}\n", self.root.to_string(&mut 0)));
@@ -7,6 +7,7 @@ pub struct Token {
   pub value: Option<ConstType>,
   pub line: usize,
   pub column: usize,
+  pub length: usize,
 }
 
 impl std::fmt::Debug for Token {
@@ -28,6 +29,11 @@ impl std::fmt::Display for Token {
 }
 
 impl Token {
+  /// Posição (linha, coluna) logo após o último caractere do token.
+  pub fn end(&self) -> (usize, usize) {
+    (self.line, self.column + self.length)
+  }
+
   pub fn get_type(&self) -> VarType {
     match self.token_type {
       TokenType::ConstFloat => VarType::Float,